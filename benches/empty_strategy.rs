@@ -0,0 +1,29 @@
+use std::future::{self, Future};
+use std::iter;
+use std::pin::pin;
+use std::task::Context;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use futures::task::noop_waker;
+use tokio_retry::Retry;
+
+fn action() -> future::Ready<Result<u64, u64>> {
+    future::ready(Err(black_box(42)))
+}
+
+/// Compares a single attempt through `Retry` with an empty strategy to polling the action's
+/// future directly, which is the baseline a fast path for empty strategies could reach.
+fn empty_strategy(c: &mut Criterion) {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut group = c.benchmark_group("empty_strategy");
+    group.bench_function("direct", |b| b.iter(|| pin!(action()).poll(&mut cx)));
+    group.bench_function("retry", |b| {
+        b.iter(|| pin!(Retry::spawn(iter::empty(), action)).poll(&mut cx))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, empty_strategy);
+criterion_main!(benches);
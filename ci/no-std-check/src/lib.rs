@@ -0,0 +1,25 @@
+//! Checks that the retry strategies compile without `std`.
+//!
+//! Build with `cargo build --target thumbv7m-none-eabi`.
+
+#![no_std]
+
+use core::time::Duration;
+
+use tokio_retry::strategy::{ExponentialBackoff, StrategyExt};
+
+pub fn delays(out: &mut [Duration]) {
+    let strategy = ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1));
+    for (slot, delay) in out.iter_mut().zip(strategy) {
+        *slot = delay;
+    }
+}
+
+pub fn capped_delays(out: &mut [Duration]) {
+    let strategy = ExponentialBackoff::from_millis(10)
+        .take(3)
+        .max_delay(Duration::from_millis(500));
+    for (slot, delay) in out.iter_mut().zip(strategy) {
+        *slot = delay;
+    }
+}
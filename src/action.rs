@@ -1,4 +1,13 @@
-use std::future::Future;
+use std::any::Any;
+use std::future::{self, Future, Ready};
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+use pin_project::pin_project;
+#[cfg(feature = "tokio")]
+use tokio::sync::{AcquireError, OwnedSemaphorePermit, Semaphore};
 
 /// An action can be run multiple times and produces a future.
 pub trait Action {
@@ -21,3 +30,573 @@ impl<R, E, T: Future<Output = Result<R, E>>, F: FnMut() -> T> Action for F {
         self()
     }
 }
+
+/// An action that is told which attempt it is running for.
+pub trait ActionWithAttempt {
+    /// The future that this action produces.
+    type Future: Future<Output = Result<Self::Item, Self::Error>>;
+    /// The item that the future may resolve with.
+    type Item;
+    /// The error that the future may resolve with.
+    type Error;
+
+    /// Runs the action for the given zero-based attempt.
+    fn run_with(&mut self, attempt: usize) -> Self::Future;
+}
+
+impl<R, E, T: Future<Output = Result<R, E>>, F: FnMut(usize) -> T> ActionWithAttempt for F {
+    type Item = R;
+    type Error = E;
+    type Future = T;
+
+    fn run_with(&mut self, attempt: usize) -> Self::Future {
+        self(attempt)
+    }
+}
+
+/// Action that passes the zero-based attempt number to an `ActionWithAttempt`.
+pub struct WithAttempt<A> {
+    action: A,
+    attempt: usize,
+}
+
+impl<A: ActionWithAttempt> WithAttempt<A> {
+    pub fn new(action: A) -> WithAttempt<A> {
+        WithAttempt {
+            action: action,
+            attempt: 0,
+        }
+    }
+}
+
+impl<A: ActionWithAttempt> Action for WithAttempt<A> {
+    type Item = A::Item;
+    type Error = A::Error;
+    type Future = A::Future;
+
+    fn run(&mut self) -> Self::Future {
+        let attempt = self.attempt;
+        self.attempt += 1;
+        self.action.run_with(attempt)
+    }
+}
+
+/// Action that runs a synchronous, fallible closure.
+///
+/// The closure is run on the task that polls the retry, so it should not block for long.
+pub struct SyncAction<F> {
+    f: F,
+}
+
+impl<R, E, F: FnMut() -> Result<R, E>> SyncAction<F> {
+    pub fn new(f: F) -> SyncAction<F> {
+        SyncAction { f: f }
+    }
+}
+
+impl<R, E, F: FnMut() -> Result<R, E>> Action for SyncAction<F> {
+    type Item = R;
+    type Error = E;
+    type Future = Ready<Result<R, E>>;
+
+    fn run(&mut self) -> Self::Future {
+        future::ready((self.f)())
+    }
+}
+
+type BoxedFuture<R, E> = Pin<Box<dyn Future<Output = Result<R, E>> + Send>>;
+
+/// Action that boxes the futures of an `async fn`, so that its type can be named.
+///
+/// A bare `async fn` item that takes no arguments is already an action, like any closure that
+/// returns a future, and can be passed to `Retry::spawn` as is. The future it returns has an
+/// anonymous type though, hence so does the retry, which can then not be stored in a struct
+/// field or returned from a trait method. This adapter erases both the function and its future
+/// behind a box, in exchange for an allocation per attempt:
+///
+/// ```rust
+/// # use tokio_retry::{BoxedAction, Retry};
+/// # use tokio_retry::strategy::FixedInterval;
+/// # use std::iter::Take;
+/// async fn fetch() -> Result<u64, std::io::Error> {
+///     Ok(42)
+/// }
+///
+/// struct Client {
+///     pending: Retry<Take<FixedInterval>, BoxedAction<u64, std::io::Error>>,
+/// }
+///
+/// let client = Client {
+///     pending: Retry::spawn(
+///         FixedInterval::from_millis(10).take(3),
+///         BoxedAction::from_async_fn(fetch),
+///     ),
+/// };
+/// ```
+///
+/// An `async fn` that takes arguments, or a method, is wrapped in a closure that passes them,
+/// for instance `BoxedAction::from_async_fn(move || client.get(url.clone()))`.
+pub struct BoxedAction<R, E> {
+    f: Box<dyn FnMut() -> BoxedFuture<R, E> + Send>,
+}
+
+impl<R, E> BoxedAction<R, E> {
+    /// Wraps `f`, which is typically the name of an `async fn`.
+    pub fn from_async_fn<F, T>(mut f: F) -> BoxedAction<R, E>
+    where
+        F: FnMut() -> T + Send + 'static,
+        T: Future<Output = Result<R, E>> + Send + 'static,
+    {
+        BoxedAction {
+            f: Box::new(move || Box::pin(f()) as BoxedFuture<R, E>),
+        }
+    }
+}
+
+impl<R, E> Action for BoxedAction<R, E> {
+    type Item = R;
+    type Error = E;
+    type Future = BoxedFuture<R, E>;
+
+    fn run(&mut self) -> Self::Future {
+        (self.f)()
+    }
+}
+
+/// Action that resolves with a fallback item instead of an error,
+/// whenever the fallback function provides one for that error.
+pub struct Fallbacks<A, F> {
+    action: A,
+    fallbacks: Arc<F>,
+}
+
+impl<A, F> Fallbacks<A, F>
+where
+    A: Action,
+    F: Fn(&A::Error) -> Option<A::Item>,
+{
+    pub fn new(action: A, fallbacks: F) -> Fallbacks<A, F> {
+        Fallbacks {
+            action: action,
+            fallbacks: Arc::new(fallbacks),
+        }
+    }
+}
+
+impl<A, F> Action for Fallbacks<A, F>
+where
+    A: Action,
+    F: Fn(&A::Error) -> Option<A::Item>,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+    type Future = FallbacksFuture<A::Future, F>;
+
+    fn run(&mut self) -> Self::Future {
+        FallbacksFuture {
+            future: self.action.run(),
+            fallbacks: self.fallbacks.clone(),
+        }
+    }
+}
+
+/// Future produced by the `Fallbacks` action.
+#[pin_project]
+pub struct FallbacksFuture<T, F> {
+    #[pin]
+    future: T,
+    fallbacks: Arc<F>,
+}
+
+impl<R, E, T, F> Future for FallbacksFuture<T, F>
+where
+    T: Future<Output = Result<R, E>>,
+    F: Fn(&E) -> Option<R>,
+{
+    type Output = Result<R, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.future.poll(cx) {
+            Poll::Ready(Err(err)) => match (this.fallbacks)(&err) {
+                Some(item) => Poll::Ready(Ok(item)),
+                None => Poll::Ready(Err(err)),
+            },
+            poll => poll,
+        }
+    }
+}
+
+/// Action that maps the errors of an action with a function, before they are seen by the
+/// condition and the result of the retry.
+///
+/// The action and every attempt share the function, which is called once the attempt fails.
+pub struct MapErr<A, F> {
+    action: A,
+    f: Arc<Mutex<F>>,
+}
+
+impl<A, E, F> MapErr<A, F>
+where
+    A: Action,
+    F: FnMut(A::Error) -> E,
+{
+    pub fn new(action: A, f: F) -> MapErr<A, F> {
+        MapErr {
+            action: action,
+            f: Arc::new(Mutex::new(f)),
+        }
+    }
+}
+
+impl<A, E, F> Action for MapErr<A, F>
+where
+    A: Action,
+    F: FnMut(A::Error) -> E,
+{
+    type Item = A::Item;
+    type Error = E;
+    type Future = MapErrFuture<A::Future, F>;
+
+    fn run(&mut self) -> Self::Future {
+        MapErrFuture {
+            future: self.action.run(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+/// Future produced by the `MapErr` action.
+#[pin_project]
+pub struct MapErrFuture<T, F> {
+    #[pin]
+    future: T,
+    f: Arc<Mutex<F>>,
+}
+
+impl<R, E, E2, T, F> Future for MapErrFuture<T, F>
+where
+    T: Future<Output = Result<R, E>>,
+    F: FnMut(E) -> E2,
+{
+    type Output = Result<R, E2>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.future.poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(item)) => Poll::Ready(Ok(item)),
+            Poll::Ready(Err(err)) => {
+                // Attempts run one after another, so the lock is never contended. A panic in an
+                // earlier call leaves the function as it was, so a poisoned lock is still usable.
+                let mut f = this.f.lock().unwrap_or_else(PoisonError::into_inner);
+                Poll::Ready(Err((*f)(err)))
+            }
+        }
+    }
+}
+
+/// Action that holds a permit of a shared semaphore while each attempt is in flight.
+///
+/// The permit is acquired before the attempt is started, and only then is the action run, so
+/// an attempt has no side effects while it waits for a permit. The permit is released as soon
+/// as the attempt resolves, hence retries that are sleeping between attempts do not hold on to
+/// a permit. The action is shared with the pending attempt, so that every attempt runs the same
+/// action and any state it keeps carries over to the next attempt.
+#[cfg(feature = "tokio")]
+pub struct Limited<A> {
+    action: Arc<Mutex<A>>,
+    permits: Arc<Semaphore>,
+}
+
+#[cfg(feature = "tokio")]
+impl<A: Action> Limited<A> {
+    pub fn new(action: A, permits: Arc<Semaphore>) -> Limited<A> {
+        Limited {
+            action: Arc::new(Mutex::new(action)),
+            permits: permits,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<A: Action> Action for Limited<A> {
+    type Item = A::Item;
+    type Error = A::Error;
+    type Future = LimitedFuture<A>;
+
+    fn run(&mut self) -> Self::Future {
+        LimitedFuture {
+            acquire: Some(Box::pin(self.permits.clone().acquire_owned())),
+            action: self.action.clone(),
+            permit: None,
+            future: None,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+type Acquire = Pin<Box<dyn Future<Output = Result<OwnedSemaphorePermit, AcquireError>> + Send>>;
+
+/// Future produced by the `Limited` action.
+#[cfg(feature = "tokio")]
+#[pin_project]
+pub struct LimitedFuture<A: Action> {
+    acquire: Option<Acquire>,
+    // the action to run once the permit is acquired
+    action: Arc<Mutex<A>>,
+    permit: Option<OwnedSemaphorePermit>,
+    #[pin]
+    future: Option<A::Future>,
+}
+
+#[cfg(feature = "tokio")]
+impl<A: Action> Future for LimitedFuture<A> {
+    type Output = Result<A::Item, A::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if let Some(acquire) = this.acquire.as_mut() {
+            match acquire.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                // A closed semaphore no longer limits anything, so the attempt just proceeds.
+                Poll::Ready(permit) => *this.permit = permit.ok(),
+            }
+            *this.acquire = None;
+            // Attempts run one after another, so the lock is never contended. A panic in an
+            // earlier `run` leaves the action as it was, so a poisoned lock is still usable.
+            let mut action = this.action.lock().unwrap_or_else(PoisonError::into_inner);
+            this.future.set(Some(action.run()));
+        }
+        let future = match this.future.as_mut().as_pin_mut() {
+            Some(future) => future,
+            None => panic!("`LimitedFuture` polled after completion"),
+        };
+        match future.poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(output) => {
+                *this.permit = None;
+                Poll::Ready(output)
+            }
+        }
+    }
+}
+
+/// Action that first sets up a resource, such as a connection, and then uses it.
+///
+/// Every attempt runs the setup anew, so a failure while using the resource leads to a fresh
+/// setup on the next attempt. The resource is moved into `use_`, so that the future it returns
+/// can own the resource and borrow it while in flight, typically with an `async move` block:
+///
+/// ```rust
+/// # use tokio_retry::Retry;
+/// # use tokio_retry::strategy::FixedInterval;
+/// struct Connection;
+///
+/// impl Connection {
+///     async fn query(&mut self) -> Result<u64, std::io::Error> {
+///         Ok(42)
+///     }
+/// }
+///
+/// async fn connect() -> Result<Connection, std::io::Error> {
+///     Ok(Connection)
+/// }
+///
+/// # async fn run() -> Result<(), std::io::Error> {
+/// let answer = Retry::spawn_with_setup(
+///     FixedInterval::from_millis(10).take(3),
+///     connect,
+///     |mut connection: Connection| async move { connection.query().await },
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The resource is dropped along with the future returned by `use_`. The action and every
+/// attempt share `use_`, which is called once the setup of the attempt succeeds.
+///
+/// `use_` cannot borrow the resource instead, as in `FnMut(&mut C) -> UF`: the future it
+/// returns would borrow from the resource, so its type would depend on the lifetime of that
+/// borrow, which a single type parameter `UF` cannot express without boxing the future. The
+/// resource would also have to be stored next to the future that borrows it.
+pub struct WithSetup<S, U> {
+    setup: S,
+    use_: Arc<Mutex<U>>,
+}
+
+impl<S, U> WithSetup<S, U> {
+    pub fn new(setup: S, use_: U) -> WithSetup<S, U> {
+        WithSetup {
+            setup: setup,
+            use_: Arc::new(Mutex::new(use_)),
+        }
+    }
+}
+
+impl<C, T, E, S, SF, U, UF> Action for WithSetup<S, U>
+where
+    S: FnMut() -> SF,
+    SF: Future<Output = Result<C, E>>,
+    U: FnMut(C) -> UF,
+    UF: Future<Output = Result<T, E>>,
+{
+    type Item = T;
+    type Error = E;
+    type Future = WithSetupFuture<SF, UF, U>;
+
+    fn run(&mut self) -> Self::Future {
+        WithSetupFuture {
+            state: SetupState::Setup((self.setup)()),
+            use_: self.use_.clone(),
+        }
+    }
+}
+
+#[pin_project(project = SetupStateProj)]
+enum SetupState<SF, UF> {
+    Setup(#[pin] SF),
+    Use(#[pin] UF),
+}
+
+/// Future produced by the `WithSetup` action.
+#[pin_project]
+pub struct WithSetupFuture<SF, UF, U> {
+    #[pin]
+    state: SetupState<SF, UF>,
+    use_: Arc<Mutex<U>>,
+}
+
+impl<C, T, E, SF, UF, U> Future for WithSetupFuture<SF, UF, U>
+where
+    SF: Future<Output = Result<C, E>>,
+    U: FnMut(C) -> UF,
+    UF: Future<Output = Result<T, E>>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            let future = match this.state.as_mut().project() {
+                SetupStateProj::Setup(future) => match future.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Ready(Ok(resource)) => {
+                        // Attempts run one after another, so the lock is never contended. A
+                        // panic in an earlier call leaves the function as it was, so a poisoned
+                        // lock is still usable.
+                        let mut use_ = this.use_.lock().unwrap_or_else(PoisonError::into_inner);
+                        (*use_)(resource)
+                    }
+                },
+                SetupStateProj::Use(future) => return future.poll(cx),
+            };
+            this.state.set(SetupState::Use(future));
+        }
+    }
+}
+
+/// Action that turns a panic of an attempt into an error.
+///
+/// The caught panic payload is converted into the action's error by the `map_panic` function,
+/// so that the panic can be retried like any other error. Panics are caught both when the
+/// action is run and while its future is polled.
+///
+/// The action and its futures need not be `UnwindSafe`. A future that panicked is dropped
+/// without being polled again, so any state it left broken is never observed through it. The
+/// action, and state shared with it, is used again by the next attempt, the same as after an
+/// error, so an action that keeps state should not rely on invariants that a panic could break,
+/// just like the data behind a poisoned `Mutex`.
+///
+/// The action and every attempt share `map_panic`, which is called by whichever of them caught
+/// the panic.
+pub struct CatchUnwind<A, M> {
+    action: A,
+    map_panic: Arc<Mutex<M>>,
+}
+
+impl<A, M> CatchUnwind<A, M>
+where
+    A: Action,
+    M: FnMut(Box<dyn Any + Send>) -> A::Error,
+{
+    pub fn new(action: A, map_panic: M) -> CatchUnwind<A, M> {
+        CatchUnwind {
+            action: action,
+            map_panic: Arc::new(Mutex::new(map_panic)),
+        }
+    }
+}
+
+impl<A, M> Action for CatchUnwind<A, M>
+where
+    A: Action,
+    M: FnMut(Box<dyn Any + Send>) -> A::Error,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+    type Future = CatchUnwindFuture<A, M>;
+
+    fn run(&mut self) -> Self::Future {
+        let action = &mut self.action;
+        let state = match panic::catch_unwind(AssertUnwindSafe(|| action.run())) {
+            Ok(future) => CatchUnwindState::Running(future),
+            Err(payload) => CatchUnwindState::Panicked(Some(map_panic(&self.map_panic, payload))),
+        };
+        CatchUnwindFuture {
+            state: state,
+            map_panic: self.map_panic.clone(),
+        }
+    }
+}
+
+fn map_panic<E, M>(map_panic: &Mutex<M>, payload: Box<dyn Any + Send>) -> E
+where
+    M: FnMut(Box<dyn Any + Send>) -> E,
+{
+    // A panic in an earlier call leaves the function as it was, so a poisoned lock is still
+    // usable.
+    let mut map_panic = map_panic.lock().unwrap_or_else(PoisonError::into_inner);
+    (*map_panic)(payload)
+}
+
+#[pin_project(project = CatchUnwindStateProj)]
+enum CatchUnwindState<T, E> {
+    Running(#[pin] T),
+    // the error that a panic of `Action::run` was converted into
+    Panicked(Option<E>),
+}
+
+/// Future produced by the `CatchUnwind` action.
+#[pin_project]
+pub struct CatchUnwindFuture<A: Action, M> {
+    #[pin]
+    state: CatchUnwindState<A::Future, A::Error>,
+    map_panic: Arc<Mutex<M>>,
+}
+
+impl<A, M> Future for CatchUnwindFuture<A, M>
+where
+    A: Action,
+    M: FnMut(Box<dyn Any + Send>) -> A::Error,
+{
+    type Output = Result<A::Item, A::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.state.project() {
+            CatchUnwindStateProj::Running(future) => {
+                match panic::catch_unwind(AssertUnwindSafe(|| future.poll(cx))) {
+                    Ok(poll) => poll,
+                    Err(payload) => Poll::Ready(Err(map_panic(this.map_panic, payload))),
+                }
+            }
+            CatchUnwindStateProj::Panicked(err) => match err.take() {
+                Some(err) => Poll::Ready(Err(err)),
+                None => panic!("`CatchUnwindFuture` polled after completion"),
+            },
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use pin_project::pin_project;
+
+use crate::action::Action;
+use crate::error::Error;
+use crate::sleeper::{Clock, RealClock};
+
+/// A circuit breaker shared between retries, which skips attempts while a backend is known to be down.
+///
+/// The breaker opens once `threshold` consecutive attempts have failed, and stays open for
+/// `open_duration`. While it is open, attempts fail immediately with `Error::CircuitOpen`.
+/// Afterwards, attempts are let through again: a success closes the breaker, while another
+/// failure opens it for another `open_duration`.
+///
+/// Time is measured with the `tokio` timer, unless another clock is set with `with_clock`.
+///
+/// Clones of a breaker share the same state. See `Retry::spawn_with_breaker`.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker<C = RealClock> {
+    state: Arc<Mutex<BreakerState>>,
+    threshold: usize,
+    open_duration: Duration,
+    clock: C,
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    failures: usize,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: usize, open_duration: Duration) -> CircuitBreaker {
+        CircuitBreaker {
+            state: Arc::new(Mutex::new(BreakerState {
+                failures: 0,
+                opened_at: None,
+            })),
+            threshold: threshold,
+            open_duration: open_duration,
+            clock: RealClock,
+        }
+    }
+}
+
+impl<C: Clock> CircuitBreaker<C> {
+    /// Measures time with the given clock, for instance the virtual clock of a simulation.
+    ///
+    /// The returned breaker shares its state with this one.
+    pub fn with_clock<C2: Clock>(self, clock: C2) -> CircuitBreaker<C2> {
+        CircuitBreaker {
+            state: self.state,
+            threshold: self.threshold,
+            open_duration: self.open_duration,
+            clock: clock,
+        }
+    }
+
+    /// Whether attempts are currently being skipped.
+    pub fn is_open(&self) -> bool {
+        match self.state.lock().unwrap().opened_at {
+            Some(opened_at) => {
+                self.clock.now().saturating_duration_since(opened_at) < self.open_duration
+            }
+            None => false,
+        }
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.failures = 0;
+        state.opened_at = None;
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.failures += 1;
+        if state.failures >= self.threshold {
+            state.opened_at = Some(self.clock.now());
+        }
+    }
+}
+
+/// Action that consults a `CircuitBreaker` before every attempt.
+///
+/// Errors of the wrapped action are reported as `Error::OperationError`.
+pub struct BreakerAction<A, C = RealClock> {
+    action: A,
+    breaker: CircuitBreaker<C>,
+}
+
+impl<A: Action, C: Clock> BreakerAction<A, C> {
+    pub fn new(action: A, breaker: CircuitBreaker<C>) -> BreakerAction<A, C> {
+        BreakerAction {
+            action: action,
+            breaker: breaker,
+        }
+    }
+}
+
+impl<A: Action, C: Clock + Clone> Action for BreakerAction<A, C> {
+    type Item = A::Item;
+    type Error = Error<A::Error>;
+    type Future = BreakerFuture<A::Future, C>;
+
+    fn run(&mut self) -> Self::Future {
+        let future = if self.breaker.is_open() {
+            None
+        } else {
+            Some(self.action.run())
+        };
+        BreakerFuture {
+            future: future,
+            breaker: self.breaker.clone(),
+        }
+    }
+}
+
+/// Future produced by the `BreakerAction` action.
+#[pin_project]
+pub struct BreakerFuture<T, C = RealClock> {
+    #[pin]
+    future: Option<T>,
+    breaker: CircuitBreaker<C>,
+}
+
+impl<R, E, T, C> Future for BreakerFuture<T, C>
+where
+    T: Future<Output = Result<R, E>>,
+    C: Clock,
+{
+    type Output = Result<R, Error<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.future.as_pin_mut() {
+            None => Poll::Ready(Err(Error::CircuitOpen)),
+            Some(future) => match future.poll(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(Ok(item)) => {
+                    this.breaker.record_success();
+                    Poll::Ready(Ok(item))
+                }
+                Poll::Ready(Err(err)) => {
+                    this.breaker.record_failure();
+                    Poll::Ready(Err(Error::OperationError(err)))
+                }
+            },
+        }
+    }
+}
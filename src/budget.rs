@@ -0,0 +1,134 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::condition::{Condition, RetryDecision};
+use crate::notify::{GiveupReason, Notify};
+
+/// A token bucket shared between retries, which caps the rate of retries across all of them.
+///
+/// Every retry withdraws a token, and every success deposits `ratio` tokens, up to the initial
+/// number of tokens. Once the bucket runs dry, errors are no longer retried until enough attempts
+/// have succeeded again. The token is withdrawn when the error is checked, under the same lock as
+/// the check, so that concurrent retries cannot overdraw the bucket, and refunded if the strategy
+/// turns out to be exhausted. This keeps a failing backend from being flooded with retries, similar to
+/// the retry throttling of gRPC.
+///
+/// Clones of a budget share the same bucket. See `Retry::spawn_with_budget`.
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    tokens: Arc<Mutex<f64>>,
+    max_tokens: f64,
+    ratio: f64,
+}
+
+impl RetryBudget {
+    /// Creates a full bucket of `tokens`, which is refilled by `ratio` tokens per success.
+    pub fn new(tokens: f64, ratio: f64) -> RetryBudget {
+        RetryBudget {
+            tokens: Arc::new(Mutex::new(tokens)),
+            max_tokens: tokens,
+            ratio: ratio,
+        }
+    }
+
+    /// The number of tokens currently left in the bucket.
+    pub fn tokens(&self) -> f64 {
+        *self.tokens.lock().unwrap()
+    }
+
+    /// Withdraws a token if one is left, and returns whether it did.
+    fn try_withdraw(&self) -> bool {
+        let mut tokens = self.tokens.lock().unwrap();
+        if *tokens < 1.0 {
+            return false;
+        }
+        *tokens -= 1.0;
+        true
+    }
+
+    fn deposit(&self, amount: f64) {
+        let mut tokens = self.tokens.lock().unwrap();
+        *tokens = f64::min(*tokens + amount, self.max_tokens);
+    }
+}
+
+impl<E> Condition<E> for RetryBudget {
+    fn should_retry(&mut self, _error: &E) -> bool {
+        self.try_withdraw()
+    }
+
+    fn decide(&mut self, _error: &E) -> RetryDecision {
+        if self.try_withdraw() {
+            RetryDecision::Continue
+        } else {
+            RetryDecision::StopExhausted
+        }
+    }
+}
+
+impl<E> Notify<E> for RetryBudget {
+    fn notify(&mut self, _error: &E, _duration: Duration) {}
+
+    fn success(&mut self, _attempts: usize) {
+        self.deposit(self.ratio)
+    }
+
+    fn give_up(&mut self, _attempts: usize, reason: GiveupReason) {
+        match reason {
+            // no token was withdrawn for the last error
+            GiveupReason::ConditionFalse | GiveupReason::BudgetExhausted => {}
+            // the token withdrawn for the last error was not spent on a retry
+            GiveupReason::StrategyExhausted | GiveupReason::MaxRetries | GiveupReason::Deadline => {
+                self.deposit(1.0)
+            }
+        }
+    }
+}
+
+#[test]
+fn withdraws_until_empty() {
+    let mut budget = RetryBudget::new(2.0, 0.5);
+
+    for _ in 0..2 {
+        assert_eq!(
+            Condition::<()>::decide(&mut budget, &()),
+            RetryDecision::Continue
+        );
+    }
+    assert_eq!(
+        Condition::<()>::decide(&mut budget, &()),
+        RetryDecision::StopExhausted
+    );
+    assert_eq!(budget.tokens(), 0.0);
+}
+
+#[test]
+fn refunds_token_when_strategy_is_exhausted() {
+    let mut budget = RetryBudget::new(1.0, 0.5);
+    assert!(Condition::<()>::should_retry(&mut budget, &()));
+
+    Notify::<()>::give_up(&mut budget, 2, GiveupReason::StrategyExhausted);
+    assert_eq!(budget.tokens(), 1.0);
+}
+
+#[test]
+fn does_not_refund_token_when_budget_is_exhausted() {
+    let mut budget = RetryBudget::new(1.0, 0.5);
+    assert!(Condition::<()>::should_retry(&mut budget, &()));
+    assert!(!Condition::<()>::should_retry(&mut budget, &()));
+
+    Notify::<()>::give_up(&mut budget, 2, GiveupReason::BudgetExhausted);
+    assert_eq!(budget.tokens(), 0.0);
+}
+
+#[test]
+fn deposits_up_to_initial_tokens() {
+    let mut budget = RetryBudget::new(1.0, 0.5);
+    assert!(Condition::<()>::should_retry(&mut budget, &()));
+
+    Notify::<()>::success(&mut budget, 2);
+    assert_eq!(budget.tokens(), 0.5);
+    Notify::<()>::success(&mut budget, 1);
+    Notify::<()>::success(&mut budget, 1);
+    assert_eq!(budget.tokens(), 1.0);
+}
@@ -0,0 +1,121 @@
+use std::iter::{self, Empty, IntoIterator, Iterator, Take};
+use std::time::Duration;
+
+use super::action::Action;
+use super::condition::{Always, Condition};
+use super::future::RetryIf;
+use super::notify::Notify;
+use super::sleeper::TokioSleeper;
+use super::strategy::{MaxDelay, StrategyExt};
+
+/// Builder for configuring a retry step by step.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tokio_retry::RetryBuilder;
+/// # use tokio_retry::strategy::ExponentialBackoff;
+/// # async fn action() -> Result<u64, std::io::Error> { Ok(42) }
+/// # async fn run() -> Result<u64, std::io::Error> {
+/// let result = RetryBuilder::new()
+///     .strategy(ExponentialBackoff::from_millis(10))
+///     .max_delay(Duration::from_secs(1))
+///     .max_retries(5)
+///     .retry_if(|err: &std::io::Error| err.kind() == std::io::ErrorKind::TimedOut)
+///     .notify(|err: &std::io::Error, duration| println!("retrying in {:?}: {}", duration, err))
+///     .spawn(action)
+///     .await?;
+/// # Ok(result)
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryBuilder<I, C, N> {
+    strategy: I,
+    max_delay: Duration,
+    max_retries: usize,
+    condition: C,
+    notify: N,
+}
+
+impl RetryBuilder<Empty<Duration>, Always, ()> {
+    /// Constructs a new builder, which does not retry until a strategy is set.
+    pub fn new() -> RetryBuilder<Empty<Duration>, Always, ()> {
+        RetryBuilder {
+            strategy: iter::empty(),
+            max_delay: Duration::MAX,
+            max_retries: usize::MAX,
+            condition: Always,
+            notify: (),
+        }
+    }
+}
+
+impl<I, C, N> RetryBuilder<I, C, N>
+where
+    I: Iterator<Item = Duration>,
+{
+    /// Sets the retry strategy.
+    pub fn strategy<T: IntoIterator<Item = Duration>>(
+        self,
+        strategy: T,
+    ) -> RetryBuilder<T::IntoIter, C, N> {
+        RetryBuilder {
+            strategy: strategy.into_iter(),
+            max_delay: self.max_delay,
+            max_retries: self.max_retries,
+            condition: self.condition,
+            notify: self.notify,
+        }
+    }
+
+    /// Caps every delay of the strategy. No retry delay will be longer than this `Duration`.
+    pub fn max_delay(mut self, max_delay: Duration) -> RetryBuilder<I, C, N> {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Limits the number of retries, regardless of the strategy.
+    pub fn max_retries(mut self, max_retries: usize) -> RetryBuilder<I, C, N> {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Only retries errors that satisfy the condition.
+    pub fn retry_if<C2>(self, condition: C2) -> RetryBuilder<I, C2, N> {
+        RetryBuilder {
+            strategy: self.strategy,
+            max_delay: self.max_delay,
+            max_retries: self.max_retries,
+            condition: condition,
+            notify: self.notify,
+        }
+    }
+
+    /// Gets notified with the error and the upcoming delay before every retry.
+    pub fn notify<N2>(self, notify: N2) -> RetryBuilder<I, C, N2> {
+        RetryBuilder {
+            strategy: self.strategy,
+            max_delay: self.max_delay,
+            max_retries: self.max_retries,
+            condition: self.condition,
+            notify: notify,
+        }
+    }
+
+    /// Starts retrying the action with this configuration.
+    pub fn spawn<A>(
+        self,
+        action: A,
+    ) -> RetryIf<Take<MaxDelay<I>>, A, C, fn(&A::Item) -> bool, TokioSleeper, N>
+    where
+        A: Action,
+        C: Condition<A::Error>,
+        N: Notify<A::Error>,
+    {
+        let strategy = self
+            .strategy
+            .max_delay(self.max_delay)
+            .take(self.max_retries);
+        RetryIf::spawn_notify(strategy, action, self.condition, self.notify)
+            .max_retries(self.max_retries)
+    }
+}
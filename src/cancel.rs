@@ -0,0 +1,111 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project::pin_project;
+use tokio::time::{sleep_until, Duration, Instant, Sleep};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+use crate::action::Action;
+use crate::error::Error;
+use crate::sleeper::Sleeper;
+
+/// Action that resolves with `Error::Cancelled` instead of running, once a token is cancelled.
+///
+/// Errors of the wrapped action are reported as `Error::OperationError`.
+pub struct CancellableAction<A> {
+    action: A,
+    token: CancellationToken,
+}
+
+impl<A: Action> CancellableAction<A> {
+    pub fn new(action: A, token: CancellationToken) -> CancellableAction<A> {
+        CancellableAction {
+            action: action,
+            token: token,
+        }
+    }
+}
+
+impl<A: Action> Action for CancellableAction<A> {
+    type Item = A::Item;
+    type Error = Error<A::Error>;
+    type Future = CancellableFuture<A::Future>;
+
+    fn run(&mut self) -> Self::Future {
+        let future = if self.token.is_cancelled() {
+            None
+        } else {
+            Some(self.action.run())
+        };
+        CancellableFuture { future: future }
+    }
+}
+
+/// Future produced by the `CancellableAction` action.
+#[pin_project]
+pub struct CancellableFuture<T> {
+    #[pin]
+    future: Option<T>,
+}
+
+impl<R, E, T> Future for CancellableFuture<T>
+where
+    T: Future<Output = Result<R, E>>,
+{
+    type Output = Result<R, Error<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match self.project().future.as_pin_mut() {
+            None => Poll::Ready(Err(Error::Cancelled)),
+            Some(future) => match future.poll(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(result) => Poll::Ready(result.map_err(Error::OperationError)),
+            },
+        }
+    }
+}
+
+/// Sleeper backed by the `tokio` timer, which wakes up early once a token is cancelled.
+#[derive(Debug, Clone)]
+pub struct CancellableSleeper {
+    token: CancellationToken,
+}
+
+impl CancellableSleeper {
+    pub fn new(token: CancellationToken) -> CancellableSleeper {
+        CancellableSleeper { token: token }
+    }
+}
+
+impl Sleeper for CancellableSleeper {
+    type Sleep = CancellableSleep;
+
+    fn sleep(&mut self, duration: Duration) -> CancellableSleep {
+        CancellableSleep {
+            sleep: sleep_until(Instant::now() + duration),
+            cancelled: self.token.clone().cancelled_owned(),
+        }
+    }
+}
+
+/// Future produced by the `CancellableSleeper`.
+#[pin_project]
+pub struct CancellableSleep {
+    #[pin]
+    sleep: Sleep,
+    #[pin]
+    cancelled: WaitForCancellationFutureOwned,
+}
+
+impl Future for CancellableSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let this = self.project();
+        if this.cancelled.poll(cx).is_ready() {
+            return Poll::Ready(());
+        }
+        this.sleep.poll(cx)
+    }
+}
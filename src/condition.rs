@@ -1,6 +1,88 @@
+use std::cmp;
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
 /// Specifies under which conditions a retry is attempted.
 pub trait Condition<E> {
     fn should_retry(&mut self, error: &E) -> bool;
+
+    /// Decides how to proceed after an error.
+    ///
+    /// By default, the retry continues according to the strategy whenever `should_retry` returns `true`.
+    fn decide(&mut self, error: &E) -> RetryDecision {
+        if self.should_retry(error) {
+            RetryDecision::Continue
+        } else {
+            RetryDecision::Stop
+        }
+    }
+
+    /// Decides how to proceed after the failed attempt number `attempt`, starting at `1`, given
+    /// the time `elapsed` since the first attempt started.
+    ///
+    /// This is what a retry calls after every error. The attempts are counted by the retry, and
+    /// the elapsed time is measured with the clock of its sleeper, but only by retries created
+    /// with `RetryIf::spawn_ctx` or `RetryIf::spawn_ctx_with_sleeper`, as the clock may not be
+    /// available, for instance in the browser. Other retries pass a zero `elapsed`.
+    ///
+    /// By default, the context is ignored and `decide` is called.
+    fn decide_after(&mut self, error: &E, attempt: usize, elapsed: Duration) -> RetryDecision {
+        let _ = (attempt, elapsed);
+        self.decide(error)
+    }
+}
+
+/// Decision about how to proceed after an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Do not retry, and resolve with the error as a definitive failure.
+    ///
+    /// A detailed retry reports this as `Error::OperationError`.
+    Stop,
+    /// Do not retry, and resolve with the error as if the strategy were exhausted, for
+    /// instance because an external retry budget ran out.
+    ///
+    /// A detailed retry reports this as `Error::RetriesExhausted`.
+    StopExhausted,
+    /// Retry after the next delay of the strategy.
+    Continue,
+    /// Retry after the given delay, without advancing the strategy.
+    ContinueAfter(Duration),
+}
+
+impl RetryDecision {
+    fn is_stop(&self) -> bool {
+        match *self {
+            RetryDecision::Stop | RetryDecision::StopExhausted => true,
+            RetryDecision::Continue | RetryDecision::ContinueAfter(_) => false,
+        }
+    }
+}
+
+/// Condition that decides how to proceed after an error via a closure returning a `RetryDecision`.
+#[derive(Debug, Clone)]
+pub struct Decide<F> {
+    f: F,
+}
+
+impl<F> Decide<F> {
+    pub fn new(f: F) -> Decide<F> {
+        Decide { f: f }
+    }
+}
+
+impl<E, F: FnMut(&E) -> RetryDecision> Condition<E> for Decide<F> {
+    fn should_retry(&mut self, error: &E) -> bool {
+        match self.decide(error) {
+            RetryDecision::Stop | RetryDecision::StopExhausted => false,
+            RetryDecision::Continue | RetryDecision::ContinueAfter(_) => true,
+        }
+    }
+
+    fn decide(&mut self, error: &E) -> RetryDecision {
+        (self.f)(error)
+    }
 }
 
 impl<E, F: FnMut(&E) -> bool> Condition<E> for F {
@@ -8,3 +90,439 @@ impl<E, F: FnMut(&E) -> bool> Condition<E> for F {
         self(error)
     }
 }
+
+/// Boxed conditions allow selecting a condition at runtime, see `RetryIf::spawn_dyn`.
+///
+/// A generic impl for `Box<C>` would overlap with the impl for closures, so boxes are covered
+/// for each combination of auto traits.
+macro_rules! impl_boxed_condition {
+    ($($bounds:tt)*) => {
+        impl<E> Condition<E> for Box<dyn Condition<E> $($bounds)*> {
+            fn should_retry(&mut self, error: &E) -> bool {
+                (**self).should_retry(error)
+            }
+
+            fn decide(&mut self, error: &E) -> RetryDecision {
+                (**self).decide(error)
+            }
+
+            fn decide_after(
+                &mut self,
+                error: &E,
+                attempt: usize,
+                elapsed: Duration,
+            ) -> RetryDecision {
+                (**self).decide_after(error, attempt, elapsed)
+            }
+        }
+    };
+}
+
+impl_boxed_condition!();
+impl_boxed_condition!(+ Send);
+impl_boxed_condition!(+ Send + Sync);
+
+/// Condition that retries on every error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Always;
+
+impl<E> Condition<E> for Always {
+    fn should_retry(&mut self, _error: &E) -> bool {
+        true
+    }
+}
+
+/// Condition that retries when at least one of several predicates matches the error.
+///
+/// ```rust
+/// # use tokio_retry::RetryOn;
+/// # fn is_unavailable(status: &u16) -> bool { *status == 503 }
+/// # fn is_timeout(status: &u16) -> bool { *status == 504 }
+/// let condition = RetryOn::new().when(is_unavailable).or_when(is_timeout);
+/// ```
+///
+/// Without any predicates, no error is retried.
+pub struct RetryOn<E> {
+    predicates: Vec<Box<dyn Fn(&E) -> bool + Send + Sync>>,
+}
+
+impl<E> RetryOn<E> {
+    pub fn new() -> RetryOn<E> {
+        RetryOn {
+            predicates: Vec::new(),
+        }
+    }
+
+    /// Also retries errors that match the given predicate.
+    pub fn when<F>(mut self, predicate: F) -> RetryOn<E>
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    /// Same as `when`, for call sites that read better with it.
+    pub fn or_when<F>(self, predicate: F) -> RetryOn<E>
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        self.when(predicate)
+    }
+}
+
+impl<E> Default for RetryOn<E> {
+    fn default() -> RetryOn<E> {
+        RetryOn::new()
+    }
+}
+
+impl<E> fmt::Debug for RetryOn<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryOn")
+            .field("predicates", &self.predicates.len())
+            .finish()
+    }
+}
+
+impl<E> Condition<E> for RetryOn<E> {
+    fn should_retry(&mut self, error: &E) -> bool {
+        self.predicates.iter().any(|predicate| predicate(error))
+    }
+}
+
+/// Returns a condition that retries I/O errors of kinds that are usually transient.
+///
+/// These are `TimedOut`, `ConnectionReset`, `ConnectionRefused` and `Interrupted`, use
+/// `IoTransient::with_kind` and `IoTransient::without_kind` to adjust the list.
+///
+/// ```rust
+/// # use std::io::ErrorKind;
+/// # use tokio_retry::io_transient;
+/// let condition = io_transient()
+///     .with_kind(ErrorKind::ConnectionAborted)
+///     .without_kind(ErrorKind::ConnectionRefused);
+/// ```
+pub fn io_transient() -> IoTransient {
+    IoTransient {
+        kinds: vec![
+            io::ErrorKind::TimedOut,
+            io::ErrorKind::ConnectionReset,
+            io::ErrorKind::ConnectionRefused,
+            io::ErrorKind::Interrupted,
+        ],
+    }
+}
+
+/// Condition that retries `io::Error`s of a set of kinds.
+///
+/// See `io_transient`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoTransient {
+    kinds: Vec<io::ErrorKind>,
+}
+
+impl IoTransient {
+    /// Also retries errors of the given kind.
+    pub fn with_kind(mut self, kind: io::ErrorKind) -> IoTransient {
+        if !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        }
+        self
+    }
+
+    /// No longer retries errors of the given kind.
+    pub fn without_kind(mut self, kind: io::ErrorKind) -> IoTransient {
+        self.kinds.retain(|k| *k != kind);
+        self
+    }
+}
+
+impl Condition<io::Error> for IoTransient {
+    fn should_retry(&mut self, error: &io::Error) -> bool {
+        self.kinds.contains(&error.kind())
+    }
+}
+
+/// Condition that retries exactly those errors which the wrapped condition does not retry.
+#[derive(Debug, Clone)]
+pub struct RetryUnless<C> {
+    condition: C,
+}
+
+impl<C> RetryUnless<C> {
+    pub fn new(condition: C) -> RetryUnless<C> {
+        RetryUnless {
+            condition: condition,
+        }
+    }
+}
+
+/// Stops where the wrapped condition would retry, and retries after the next delay of the
+/// strategy where it would stop. A `StopExhausted` decision is kept, since an exhausted budget
+/// is no reason to retry.
+impl<E, C: Condition<E>> Condition<E> for RetryUnless<C> {
+    fn should_retry(&mut self, error: &E) -> bool {
+        !self.condition.should_retry(error)
+    }
+
+    fn decide(&mut self, error: &E) -> RetryDecision {
+        negate(self.condition.decide(error))
+    }
+
+    fn decide_after(&mut self, error: &E, attempt: usize, elapsed: Duration) -> RetryDecision {
+        negate(self.condition.decide_after(error, attempt, elapsed))
+    }
+}
+
+fn negate(decision: RetryDecision) -> RetryDecision {
+    match decision {
+        RetryDecision::Stop => RetryDecision::Continue,
+        RetryDecision::StopExhausted => RetryDecision::StopExhausted,
+        RetryDecision::Continue | RetryDecision::ContinueAfter(_) => RetryDecision::Stop,
+    }
+}
+
+/// Combinators for conditions, available on any `Condition`.
+///
+/// ```rust
+/// # use tokio_retry::ConditionExt;
+/// # fn is_network_error(err: &std::io::Error) -> bool { true }
+/// # fn is_permission_error(err: &std::io::Error) -> bool { false }
+/// let condition = is_network_error.and(|err: &std::io::Error| err.raw_os_error().is_none());
+/// let condition = condition.or(is_permission_error.not());
+/// ```
+pub trait ConditionExt<E>: Condition<E> + Sized {
+    /// Retries errors that satisfy both conditions.
+    ///
+    /// `other` is only consulted if this condition retries the error.
+    fn and<C: Condition<E>>(self, other: C) -> And<Self, C> {
+        And {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Retries errors that satisfy at least one of the conditions.
+    ///
+    /// `other` is only consulted if this condition does not retry the error.
+    fn or<C: Condition<E>>(self, other: C) -> Or<Self, C> {
+        Or {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Retries exactly those errors which this condition does not retry.
+    fn not(self) -> RetryUnless<Self> {
+        RetryUnless::new(self)
+    }
+}
+
+impl<E, C: Condition<E>> ConditionExt<E> for C {}
+
+/// Condition that retries errors which satisfy both of two conditions.
+///
+/// See `ConditionExt::and`.
+#[derive(Debug, Clone)]
+pub struct And<A, B> {
+    first: A,
+    second: B,
+}
+
+/// Stops with the first decision to stop. If both conditions retry, the retry happens after
+/// the larger of their explicit delays, if any.
+impl<E, A: Condition<E>, B: Condition<E>> Condition<E> for And<A, B> {
+    fn should_retry(&mut self, error: &E) -> bool {
+        self.first.should_retry(error) && self.second.should_retry(error)
+    }
+
+    fn decide(&mut self, error: &E) -> RetryDecision {
+        let second = &mut self.second;
+        both(self.first.decide(error), || second.decide(error))
+    }
+
+    fn decide_after(&mut self, error: &E, attempt: usize, elapsed: Duration) -> RetryDecision {
+        let second = &mut self.second;
+        both(self.first.decide_after(error, attempt, elapsed), || {
+            second.decide_after(error, attempt, elapsed)
+        })
+    }
+}
+
+fn both<F: FnOnce() -> RetryDecision>(first: RetryDecision, second: F) -> RetryDecision {
+    if first.is_stop() {
+        return first;
+    }
+    match (first, second()) {
+        (_, second @ RetryDecision::Stop) | (_, second @ RetryDecision::StopExhausted) => second,
+        (RetryDecision::ContinueAfter(a), RetryDecision::ContinueAfter(b)) => {
+            RetryDecision::ContinueAfter(cmp::max(a, b))
+        }
+        (RetryDecision::ContinueAfter(delay), _) | (_, RetryDecision::ContinueAfter(delay)) => {
+            RetryDecision::ContinueAfter(delay)
+        }
+        _ => RetryDecision::Continue,
+    }
+}
+
+/// Condition that retries errors which satisfy at least one of two conditions.
+///
+/// See `ConditionExt::or`.
+#[derive(Debug, Clone)]
+pub struct Or<A, B> {
+    first: A,
+    second: B,
+}
+
+/// Retries with the first decision to retry. If both conditions stop, a `StopExhausted`
+/// decision of either is kept.
+impl<E, A: Condition<E>, B: Condition<E>> Condition<E> for Or<A, B> {
+    fn should_retry(&mut self, error: &E) -> bool {
+        self.first.should_retry(error) || self.second.should_retry(error)
+    }
+
+    fn decide(&mut self, error: &E) -> RetryDecision {
+        let second = &mut self.second;
+        either(self.first.decide(error), || second.decide(error))
+    }
+
+    fn decide_after(&mut self, error: &E, attempt: usize, elapsed: Duration) -> RetryDecision {
+        let second = &mut self.second;
+        either(self.first.decide_after(error, attempt, elapsed), || {
+            second.decide_after(error, attempt, elapsed)
+        })
+    }
+}
+
+fn either<F: FnOnce() -> RetryDecision>(first: RetryDecision, second: F) -> RetryDecision {
+    if !first.is_stop() {
+        return first;
+    }
+    match (first, second()) {
+        (_, second) if !second.is_stop() => second,
+        (RetryDecision::StopExhausted, _) | (_, RetryDecision::StopExhausted) => {
+            RetryDecision::StopExhausted
+        }
+        _ => RetryDecision::Stop,
+    }
+}
+
+/// Specifies whether a retry is attempted, and after which delay.
+///
+/// Given the error and the `default` delay from the strategy, returns the delay before the
+/// next attempt, or `None` to not retry.
+pub trait DelayingCondition<E> {
+    fn next_delay(&mut self, error: &E, default: Duration) -> Option<Duration>;
+}
+
+impl<E, F: FnMut(&E, Duration) -> Option<Duration>> DelayingCondition<E> for F {
+    fn next_delay(&mut self, error: &E, default: Duration) -> Option<Duration> {
+        self(error, default)
+    }
+}
+
+/// Specifies under which conditions a retry is attempted,
+/// given the number of the failed attempt (starting at `1`) and the time elapsed since the first attempt.
+pub trait ContextCondition<E> {
+    fn should_retry(&mut self, error: &E, attempt: usize, elapsed: Duration) -> bool;
+}
+
+impl<E, F: FnMut(&E, usize, Duration) -> bool> ContextCondition<E> for F {
+    fn should_retry(&mut self, error: &E, attempt: usize, elapsed: Duration) -> bool {
+        self(error, attempt, elapsed)
+    }
+}
+
+/// Condition that hands the attempt number and the elapsed time, as tracked by the retry, to a
+/// `ContextCondition`.
+///
+/// See `RetryIf::spawn_ctx`.
+#[derive(Debug, Clone)]
+pub struct WithContext<C> {
+    condition: C,
+}
+
+impl<C> WithContext<C> {
+    pub(crate) fn new(condition: C) -> WithContext<C> {
+        WithContext {
+            condition: condition,
+        }
+    }
+}
+
+/// Outside of a retry, `should_retry` gives the condition attempt `0` and no elapsed time.
+impl<E, C: ContextCondition<E>> Condition<E> for WithContext<C> {
+    fn should_retry(&mut self, error: &E) -> bool {
+        self.condition.should_retry(error, 0, Duration::ZERO)
+    }
+
+    fn decide_after(&mut self, error: &E, attempt: usize, elapsed: Duration) -> RetryDecision {
+        if self.condition.should_retry(error, attempt, elapsed) {
+            RetryDecision::Continue
+        } else {
+            RetryDecision::Stop
+        }
+    }
+}
+
+#[test]
+fn io_transient_retries_transient_kinds_only() {
+    use std::io::ErrorKind;
+
+    let mut condition = io_transient();
+    for kind in &[
+        ErrorKind::TimedOut,
+        ErrorKind::ConnectionReset,
+        ErrorKind::ConnectionRefused,
+        ErrorKind::Interrupted,
+    ] {
+        assert!(condition.should_retry(&io::Error::from(*kind)));
+    }
+    assert!(!condition.should_retry(&io::Error::from(ErrorKind::PermissionDenied)));
+    assert!(!condition.should_retry(&io::Error::from(ErrorKind::NotFound)));
+}
+
+#[test]
+fn io_transient_kinds_can_be_adjusted() {
+    use std::io::ErrorKind;
+
+    let mut condition = io_transient()
+        .with_kind(ErrorKind::ConnectionAborted)
+        .without_kind(ErrorKind::ConnectionRefused);
+
+    assert!(condition.should_retry(&io::Error::from(ErrorKind::ConnectionAborted)));
+    assert!(!condition.should_retry(&io::Error::from(ErrorKind::ConnectionRefused)));
+    assert!(condition.should_retry(&io::Error::from(ErrorKind::TimedOut)));
+    assert_eq!(
+        io_transient().with_kind(ErrorKind::TimedOut),
+        io_transient()
+    );
+}
+
+#[test]
+fn combinators_combine_decisions() {
+    fn decide(decision: RetryDecision) -> Decide<impl FnMut(&()) -> RetryDecision> {
+        Decide::new(move |_: &()| decision)
+    }
+    let after = |millis| RetryDecision::ContinueAfter(Duration::from_millis(millis));
+
+    let mut and = decide(after(100)).and(decide(after(200)));
+    assert_eq!(and.decide(&()), after(200));
+    let mut and = decide(RetryDecision::Continue).and(decide(after(100)));
+    assert_eq!(and.decide(&()), after(100));
+    let mut and = decide(after(100)).and(decide(RetryDecision::StopExhausted));
+    assert_eq!(and.decide(&()), RetryDecision::StopExhausted);
+
+    let mut or = decide(RetryDecision::Stop).or(decide(after(100)));
+    assert_eq!(or.decide(&()), after(100));
+    let mut or = decide(RetryDecision::StopExhausted).or(decide(RetryDecision::Stop));
+    assert_eq!(or.decide(&()), RetryDecision::StopExhausted);
+
+    let mut not = decide(after(100)).not();
+    assert_eq!(not.decide(&()), RetryDecision::Stop);
+    let mut not = decide(RetryDecision::Stop).not();
+    assert_eq!(not.decide(&()), RetryDecision::Continue);
+    let mut not = decide(RetryDecision::StopExhausted).not();
+    assert_eq!(not.decide(&()), RetryDecision::StopExhausted);
+}
@@ -0,0 +1,332 @@
+use std::cmp;
+use std::fmt;
+use std::future::Future;
+use std::iter::{IntoIterator, Iterator};
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use pin_project::pin_project;
+
+use super::action::Action;
+use super::notify::Notify;
+use super::sleeper::Sleeper;
+#[cfg(feature = "tokio")]
+use super::sleeper::TokioSleeper;
+
+#[pin_project(project = RetryStateProj)]
+pub(crate) enum RetryState<A, S>
+where
+    A: Action,
+    S: Sleeper,
+{
+    Running(#[pin] A::Future),
+    Sleeping(#[pin] S::Sleep),
+    // a retry was scheduled without a delay
+    Ready,
+    Idle,
+}
+
+impl<A: Action, S: Sleeper> RetryState<A, S> {
+    fn name(&self) -> &'static str {
+        match self {
+            RetryState::Running(_) => "Running",
+            RetryState::Sleeping(_) => "Sleeping",
+            RetryState::Ready => "Ready",
+            RetryState::Idle => "Idle",
+        }
+    }
+}
+
+/// Low-level state machine that runs attempts of an action and sleeps between them, leaving
+/// it to the caller to decide whether and when to retry.
+///
+/// This is what `Retry` and `RetryIf` are built on. The future of the current attempt, as well
+/// as the sleep between attempts, is stored inline and pinned in place along with the driver,
+/// so that action futures need not be `Unpin` and are never boxed.
+///
+/// The driver allows retry logic to be embedded into a custom future, by stepping it from its `poll`:
+///
+/// 1. `poll_attempt` resolves with the result of the current attempt, first sleeping if a
+///    retry was scheduled.
+/// 2. To retry, call `retry` to schedule the next attempt after the next delay of the strategy,
+///    or `retry_after` to schedule it after a given delay. Then go back to 1.
+///
+/// ```rust,no_run
+/// # use std::future::Future;
+/// # use std::pin::Pin;
+/// # use std::task::{Context, Poll};
+/// # use tokio_retry::{Action, RetryDriver};
+/// # use tokio_retry::strategy::FixedInterval;
+/// fn poll_until_ok<A: Action>(
+///     mut driver: Pin<&mut RetryDriver<FixedInterval, A>>,
+///     cx: &mut Context,
+/// ) -> Poll<Option<A::Item>> {
+///     loop {
+///         match driver.as_mut().poll_attempt(cx) {
+///             Poll::Pending => return Poll::Pending,
+///             Poll::Ready(Ok(item)) => return Poll::Ready(Some(item)),
+///             Poll::Ready(Err(_)) => {
+///                 if driver.as_mut().retry().is_none() {
+///                     return Poll::Ready(None);
+///                 }
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[pin_project]
+pub struct RetryDriver<
+    I,
+    A,
+    #[cfg(feature = "tokio")] S = TokioSleeper,
+    #[cfg(not(feature = "tokio"))] S,
+> where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+{
+    strategy: I,
+    #[pin]
+    state: RetryState<A, S>,
+    action: A,
+    sleeper: S,
+    attempts: usize,
+    // the minimum time between the starts of two attempts
+    min_attempt_spacing: Duration,
+    // when the current or last attempt was started, if attempts are spaced
+    started: Option<Instant>,
+    // whether a panic in `strategy.next()` is treated as exhaustion
+    catch_strategy_panic: bool,
+    // whether the strategy panicked, after which it is considered exhausted
+    strategy_panicked: bool,
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> RetryDriver<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Creates a driver and starts the first attempt at the action.
+    pub fn new<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> RetryDriver<I, A> {
+        RetryDriver::with_sleeper(strategy, action, TokioSleeper)
+    }
+}
+
+impl<I, A, S> RetryDriver<I, A, S>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+{
+    /// Like `RetryDriver::new`, but sleeps between attempts with the given `Sleeper`.
+    pub fn with_sleeper<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        mut action: A,
+        sleeper: S,
+    ) -> RetryDriver<I, A, S> {
+        let future = action.run();
+        RetryDriver::from_state(
+            strategy.into_iter(),
+            RetryState::Running(future),
+            action,
+            sleeper,
+        )
+    }
+
+    pub(crate) fn from_state(
+        strategy: I,
+        state: RetryState<A, S>,
+        action: A,
+        sleeper: S,
+    ) -> RetryDriver<I, A, S> {
+        let attempts = match state {
+            RetryState::Running(_) => 1,
+            RetryState::Sleeping(_) | RetryState::Ready | RetryState::Idle => 0,
+        };
+        RetryDriver {
+            strategy: strategy,
+            state: state,
+            action: action,
+            sleeper: sleeper,
+            attempts: attempts,
+            min_attempt_spacing: Duration::ZERO,
+            started: None,
+            catch_strategy_panic: false,
+            strategy_panicked: false,
+        }
+    }
+
+    /// Ensures that every attempt starts at least `spacing` after the previous attempt started.
+    ///
+    /// Delays are extended as needed, so that an attempt that fails quickly is not retried
+    /// right away, while an attempt that took longer than `spacing` is retried after the
+    /// regular delay only.
+    pub fn min_attempt_spacing(mut self, spacing: Duration) -> RetryDriver<I, A, S> {
+        self.min_attempt_spacing = spacing;
+        if self.attempts > 0 {
+            // the first attempt was started when the driver was created, right before this
+            self.started = Some(self.sleeper.now());
+        }
+        self
+    }
+
+    /// Treats a panic of the strategy while computing the next delay as if the strategy was
+    /// exhausted, instead of propagating the panic to the caller.
+    ///
+    /// The panic is still reported by the panic hook, and the strategy is not asked for another
+    /// delay afterwards. This has no effect when panics abort.
+    pub fn catch_strategy_panic(mut self) -> RetryDriver<I, A, S> {
+        self.catch_strategy_panic = true;
+        self
+    }
+
+    /// Returns the number of attempts started so far.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    pub(crate) fn state_name(&self) -> &'static str {
+        self.state.name()
+    }
+
+    /// Returns the current time of the sleeper.
+    pub(crate) fn now(&self) -> Instant {
+        self.sleeper.now()
+    }
+
+    /// Polls the current attempt, and resolves with its result.
+    ///
+    /// If a retry was scheduled, this first sleeps and then starts the next attempt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called again after an attempt resolved, unless a retry was scheduled since.
+    pub fn poll_attempt(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<A::Item, A::Error>> {
+        self.poll_attempt_notify(cx, &mut ())
+    }
+
+    /// Like `poll_attempt`, but tells `notify` whenever an attempt is started.
+    pub(crate) fn poll_attempt_notify<N: Notify<A::Error>>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        notify: &mut N,
+    ) -> Poll<Result<A::Item, A::Error>> {
+        loop {
+            let mut this = self.as_mut().project();
+            match this.state.as_mut().project() {
+                RetryStateProj::Running(future) => {
+                    let result = match future.poll(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(result) => result,
+                    };
+                    this.state.set(RetryState::Idle);
+                    return Poll::Ready(result);
+                }
+                RetryStateProj::Sleeping(future) => match future.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(_) => this.state.set(RetryState::Ready),
+                },
+                RetryStateProj::Ready => {
+                    *this.attempts += 1;
+                    notify.attempt(*this.attempts);
+                    // the clock is only read when needed, as it may not be available, for
+                    // instance in the browser
+                    if *this.min_attempt_spacing > Duration::ZERO {
+                        *this.started = Some(this.sleeper.now());
+                    }
+                    let future = this.action.run();
+                    this.state.set(RetryState::Running(future));
+                }
+                RetryStateProj::Idle => {
+                    panic!("`RetryDriver::poll_attempt` called after the attempt resolved")
+                }
+            }
+        }
+    }
+
+    /// Schedules the next attempt after the next delay of the strategy, and returns that delay.
+    ///
+    /// Returns `None` without scheduling anything if the strategy is exhausted.
+    pub fn retry(mut self: Pin<&mut Self>) -> Option<Duration> {
+        let duration = self.as_mut().next_delay()?;
+        let duration = self.spaced(duration);
+        self.retry_after(duration);
+        Some(duration)
+    }
+
+    /// Schedules the next attempt after `duration`, without advancing the strategy.
+    ///
+    /// A zero `duration` does not involve the sleeper at all, the next attempt is started
+    /// right away when the driver is polled.
+    pub fn retry_after(self: Pin<&mut Self>, duration: Duration) {
+        let duration = self.spaced(duration);
+        let mut this = self.project();
+        if duration == Duration::ZERO {
+            this.state.set(RetryState::Ready);
+        } else {
+            let future = this.sleeper.sleep(duration);
+            this.state.set(RetryState::Sleeping(future));
+        }
+    }
+}
+
+impl<I, A, S> RetryDriver<I, A, S>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+{
+    /// Advances the strategy, catching its panics if configured to.
+    ///
+    /// Unlike `retry`, this does not schedule anything, and does not apply the minimum
+    /// attempt spacing, which `retry_after` does.
+    pub(crate) fn next_delay(self: Pin<&mut Self>) -> Option<Duration> {
+        let this = self.project();
+        if !*this.catch_strategy_panic {
+            return this.strategy.next();
+        }
+        if *this.strategy_panicked {
+            return None;
+        }
+        let strategy = this.strategy;
+        match panic::catch_unwind(AssertUnwindSafe(|| strategy.next())) {
+            Ok(duration) => duration,
+            Err(_) => {
+                *this.strategy_panicked = true;
+                None
+            }
+        }
+    }
+
+    /// Extends `duration` to the time remaining until the minimum attempt spacing has passed.
+    fn spaced(&self, duration: Duration) -> Duration {
+        match self.started {
+            Some(started) if self.min_attempt_spacing > Duration::ZERO => {
+                let elapsed = self.sleeper.now().saturating_duration_since(started);
+                let remaining = self.min_attempt_spacing.saturating_sub(elapsed);
+                cmp::max(duration, remaining)
+            }
+            _ => duration,
+        }
+    }
+}
+
+impl<I, A, S> fmt::Debug for RetryDriver<I, A, S>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryDriver")
+            .field("state", &format_args!("{}", self.state_name()))
+            .field("attempts", &self.attempts)
+            .finish()
+    }
+}
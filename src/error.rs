@@ -0,0 +1,245 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Error from a retry that tells apart why the retry gave up.
+///
+/// Two errors are equal if they are the same variant with equal contents, so that for example
+/// any two `CircuitOpen` errors are equal, while they never equal an `OperationError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The action failed with an error that was not retried.
+    OperationError(E),
+    /// The retry strategy was exhausted. Carries the last error and the number of attempts.
+    RetriesExhausted(E, usize),
+    /// The attempt was skipped because the circuit breaker is open.
+    CircuitOpen,
+    /// The retry was cancelled before it could complete.
+    Cancelled,
+    /// The retry was aborted by a signal before it could complete.
+    Aborted,
+    /// Every attempt failed. Carries the errors of all attempts in order.
+    AllFailed(Vec<E>),
+}
+
+impl<E> Error<E> {
+    /// Returns the error of the action, discarding why the retry gave up.
+    ///
+    /// Returns `None` if the retry gave up without an error of the action.
+    pub fn into_inner(self) -> Option<E> {
+        match self {
+            Error::OperationError(err) => Some(err),
+            Error::RetriesExhausted(err, _) => Some(err),
+            Error::CircuitOpen => None,
+            Error::Cancelled => None,
+            Error::Aborted => None,
+            Error::AllFailed(errors) => errors.into_iter().last(),
+        }
+    }
+
+    /// Returns the number of attempts that were made before the retry gave up, if known.
+    ///
+    /// This is known when the strategy was exhausted and when every attempt failed, and `None`
+    /// for all other errors, including a non-retried `OperationError`.
+    pub fn attempt(&self) -> Option<usize> {
+        match *self {
+            Error::RetriesExhausted(_, attempts) => Some(attempts),
+            Error::AllFailed(ref errors) => Some(errors.len()),
+            Error::OperationError(_) | Error::CircuitOpen | Error::Cancelled | Error::Aborted => {
+                None
+            }
+        }
+    }
+
+    /// Returns the error of the action, like `into_inner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the retry gave up without an error of the action.
+    pub fn unwrap_operation(self) -> E {
+        match self {
+            Error::OperationError(err) | Error::RetriesExhausted(err, _) => err,
+            Error::CircuitOpen => {
+                panic!("called `Error::unwrap_operation()` on a `CircuitOpen` value")
+            }
+            Error::Cancelled => panic!("called `Error::unwrap_operation()` on a `Cancelled` value"),
+            Error::Aborted => panic!("called `Error::unwrap_operation()` on an `Aborted` value"),
+            Error::AllFailed(errors) => match errors.into_iter().last() {
+                Some(err) => err,
+                None => panic!("called `Error::unwrap_operation()` on an empty `AllFailed` value"),
+            },
+        }
+    }
+
+    /// Returns the error of the action, like `into_inner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with `msg` if the retry gave up without an error of the action.
+    pub fn expect_operation(self, msg: &str) -> E {
+        match self {
+            Error::OperationError(err) | Error::RetriesExhausted(err, _) => err,
+            Error::AllFailed(errors) => match errors.into_iter().last() {
+                Some(err) => err,
+                None => panic!("{}", msg),
+            },
+            Error::CircuitOpen | Error::Cancelled | Error::Aborted => panic!("{}", msg),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::OperationError(ref err) => write!(f, "operation error: {}", err),
+            Error::RetriesExhausted(ref err, attempts) => {
+                write!(f, "retries exhausted after {} attempts: {}", attempts, err)
+            }
+            Error::CircuitOpen => write!(f, "circuit breaker is open"),
+            Error::Cancelled => write!(f, "retry was cancelled"),
+            Error::Aborted => write!(f, "retry was aborted"),
+            Error::AllFailed(ref errors) => match errors.last() {
+                Some(err) => write!(
+                    f,
+                    "all {} attempts failed, last with: {}",
+                    errors.len(),
+                    err
+                ),
+                None => write!(f, "all attempts failed"),
+            },
+        }
+    }
+}
+
+impl<E: error::Error + 'static> error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::OperationError(ref err) => Some(err),
+            Error::RetriesExhausted(ref err, _) => Some(err),
+            Error::CircuitOpen => None,
+            Error::Cancelled => None,
+            Error::Aborted => None,
+            Error::AllFailed(ref errors) => errors.last().map(|err| err as _),
+        }
+    }
+}
+
+impl<E> From<Error<E>> for io::Error
+where
+    E: Into<Box<dyn error::Error + Send + Sync>>,
+{
+    /// Wraps the error of the action into an `io::Error` of kind `Other`, preserving its message.
+    fn from(err: Error<E>) -> io::Error {
+        match err {
+            Error::OperationError(err) | Error::RetriesExhausted(err, _) => {
+                io::Error::new(io::ErrorKind::Other, err)
+            }
+            Error::CircuitOpen => io::Error::new(io::ErrorKind::Other, "circuit breaker is open"),
+            Error::Cancelled => io::Error::new(io::ErrorKind::Other, "retry was cancelled"),
+            Error::Aborted => io::Error::new(io::ErrorKind::Other, "retry was aborted"),
+            Error::AllFailed(errors) => match errors.into_iter().last() {
+                Some(err) => io::Error::new(io::ErrorKind::Other, err),
+                None => io::Error::new(io::ErrorKind::Other, "all attempts failed"),
+            },
+        }
+    }
+}
+
+#[test]
+fn displays_attempts_when_retries_exhausted() {
+    let err = Error::RetriesExhausted("timeout", 3);
+
+    assert_eq!(
+        err.to_string(),
+        "retries exhausted after 3 attempts: timeout"
+    );
+    assert_eq!(
+        Error::OperationError("timeout").to_string(),
+        "operation error: timeout"
+    );
+}
+
+#[test]
+fn exposes_inner_error_as_source() {
+    use std::error::Error as _;
+
+    let err = Error::RetriesExhausted(io::Error::new(io::ErrorKind::Other, "boom"), 3);
+
+    assert_eq!(err.source().unwrap().to_string(), "boom");
+}
+
+#[test]
+fn converts_into_io_error() {
+    let err: io::Error = Error::OperationError("refused").into();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert_eq!(err.to_string(), "refused");
+
+    let err: io::Error = Error::RetriesExhausted("timeout", 3).into();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert_eq!(err.to_string(), "timeout");
+}
+
+#[test]
+fn circuit_open_has_no_inner_error() {
+    let err: Error<&str> = Error::CircuitOpen;
+
+    assert_eq!(err.to_string(), "circuit breaker is open");
+    assert_eq!(err.into_inner(), None);
+}
+
+#[test]
+fn compares_variants_structurally() {
+    assert_eq!(Error::<u64>::CircuitOpen, Error::CircuitOpen);
+    assert_ne!(Error::CircuitOpen, Error::OperationError(42));
+    assert_eq!(Error::OperationError(42), Error::OperationError(42));
+    assert_ne!(Error::OperationError(42), Error::RetriesExhausted(42, 1));
+}
+
+#[test]
+fn clones_when_inner_error_is_clone() {
+    let err = Error::OperationError("refused".to_string());
+
+    assert_eq!(err.clone(), err);
+    assert_eq!(
+        Error::RetriesExhausted(42, 3).clone(),
+        Error::RetriesExhausted(42, 3)
+    );
+}
+
+#[test]
+fn unwraps_operation_error() {
+    assert_eq!(Error::OperationError(42).unwrap_operation(), 42);
+    assert_eq!(Error::RetriesExhausted(42, 3).unwrap_operation(), 42);
+    assert_eq!(Error::OperationError(42).expect_operation("no error"), 42);
+}
+
+#[test]
+#[should_panic(expected = "called `Error::unwrap_operation()` on a `CircuitOpen` value")]
+fn unwrap_operation_panics_without_operation_error() {
+    Error::<u64>::CircuitOpen.unwrap_operation();
+}
+
+#[test]
+#[should_panic(expected = "expected an operation error")]
+fn expect_operation_panics_with_message() {
+    Error::<u64>::Cancelled.expect_operation("expected an operation error");
+}
+
+#[test]
+fn all_failed_reports_last_error() {
+    let err = Error::AllFailed(vec!["refused", "timeout"]);
+
+    assert_eq!(err.to_string(), "all 2 attempts failed, last with: timeout");
+    assert_eq!(err.into_inner(), Some("timeout"));
+}
+
+#[test]
+fn reports_attempt_when_known() {
+    assert_eq!(Error::RetriesExhausted("timeout", 3).attempt(), Some(3));
+    assert_eq!(
+        Error::AllFailed(vec!["refused", "timeout"]).attempt(),
+        Some(2)
+    );
+    assert_eq!(Error::OperationError("timeout").attempt(), None);
+    assert_eq!(Error::<&str>::Cancelled.attempt(), None);
+}
@@ -0,0 +1,117 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::notify::{GiveupReason, Notify};
+
+/// Describes a retry that is about to happen.
+///
+/// See `Retry::spawn_with_events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttemptEvent {
+    /// The number of the attempt that failed, starting at 1.
+    pub attempt: usize,
+    /// The error of the failed attempt, formatted using `Display`.
+    pub error: String,
+    /// The delay before the next attempt.
+    pub delay: Duration,
+}
+
+/// Sends an `AttemptEvent` before every retry, and closes the channel once the retry resolves.
+///
+/// Events are dropped rather than waited for while the channel is full.
+#[derive(Debug)]
+pub struct EventSender {
+    sender: Option<Sender<AttemptEvent>>,
+    attempt: usize,
+}
+
+impl EventSender {
+    pub fn new(sender: Sender<AttemptEvent>) -> EventSender {
+        EventSender {
+            sender: Some(sender),
+            attempt: 0,
+        }
+    }
+}
+
+impl<E: Display> Notify<E> for EventSender {
+    fn notify(&mut self, error: &E, duration: Duration) {
+        if let Some(ref sender) = self.sender {
+            let _ = sender.try_send(AttemptEvent {
+                attempt: self.attempt,
+                error: error.to_string(),
+                delay: duration,
+            });
+        }
+    }
+
+    fn attempt(&mut self, attempt: usize) {
+        self.attempt = attempt;
+    }
+
+    fn success(&mut self, _attempts: usize) {
+        self.sender = None;
+    }
+
+    fn give_up(&mut self, _attempts: usize, _reason: GiveupReason) {
+        self.sender = None;
+    }
+}
+
+/// The current state of a retry.
+///
+/// See `Retry::spawn_with_watch`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetryStatus {
+    /// The number of the current or last attempt, starting at 1.
+    pub attempt: usize,
+    /// The error of the last failed attempt, formatted using `Display`.
+    pub last_error: Option<String>,
+    /// When the next attempt is due, while sleeping before a retry.
+    pub next_retry_at: Option<Instant>,
+}
+
+/// Publishes the `RetryStatus` of a retry on every state transition.
+#[derive(Debug)]
+pub struct WatchSender {
+    sender: watch::Sender<RetryStatus>,
+}
+
+impl WatchSender {
+    pub fn new(sender: watch::Sender<RetryStatus>) -> WatchSender {
+        WatchSender { sender: sender }
+    }
+}
+
+impl<E: Display> Notify<E> for WatchSender {
+    fn notify(&mut self, error: &E, duration: Duration) {
+        let next_retry_at = Instant::now() + duration;
+        self.sender.send_modify(|status| {
+            status.last_error = Some(error.to_string());
+            status.next_retry_at = Some(next_retry_at);
+        });
+    }
+
+    fn attempt(&mut self, attempt: usize) {
+        self.sender.send_modify(|status| {
+            status.attempt = attempt;
+            status.next_retry_at = None;
+        });
+    }
+
+    fn success(&mut self, _attempts: usize) {
+        self.sender.send_modify(|status| {
+            status.last_error = None;
+            status.next_retry_at = None;
+        });
+    }
+
+    fn give_up(&mut self, _attempts: usize, _reason: GiveupReason) {
+        self.sender
+            .send_modify(|status| status.next_retry_at = None);
+    }
+}
@@ -1,177 +1,2078 @@
+use std::any::Any;
 use std::cmp;
-use std::error;
 use std::fmt;
 use std::future::Future;
-use std::iter::{IntoIterator, Iterator};
+use std::iter::{self, IntoIterator, Iterator, Map};
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime};
 
 use pin_project::pin_project;
-use tokio::time::{sleep_until, Duration, Instant, Sleep};
+#[cfg(feature = "tokio")]
+use tokio::runtime::Handle;
+#[cfg(feature = "tokio")]
+use tokio::sync::{mpsc, watch, Semaphore};
+#[cfg(feature = "tokio")]
+use tokio::time::{Interval, MissedTickBehavior};
+#[cfg(feature = "cancel")]
+use tokio_util::sync::CancellationToken;
 
-use super::action::Action;
-use super::condition::Condition;
+#[cfg(feature = "tokio")]
+use super::action::Limited;
+use super::action::{
+    Action, ActionWithAttempt, CatchUnwind, Fallbacks, MapErr, SyncAction, WithAttempt, WithSetup,
+};
+use super::breaker::{BreakerAction, CircuitBreaker};
+use super::budget::RetryBudget;
+#[cfg(feature = "cancel")]
+use super::cancel::{CancellableAction, CancellableSleeper};
+use super::condition::{
+    Condition, ContextCondition, Decide, DelayingCondition, RetryDecision, RetryUnless, WithContext,
+};
+use super::driver::{RetryDriver, RetryState};
+use super::error::Error;
+#[cfg(feature = "tokio")]
+use super::events::{AttemptEvent, EventSender, RetryStatus, WatchSender};
+use super::notify::{GiveupReason, Notify};
+use super::observer::{Observed, RetryObserver};
+use super::plan::{Policy, RetryPolicy};
+use super::report::{CollectErrors, ErrorHistory, Reported, ReportingAction};
+use super::sleeper::{Clock, RealClock, Sleeper};
+#[cfg(feature = "tokio")]
+use super::sleeper::{HandleSleeper, TokioSleeper, YieldingSleeper};
+use super::strategy::{AdaptiveBackoff, Deadline, SystemDeadline};
+#[cfg(feature = "tracing")]
+use super::trace::Traced;
 
-#[pin_project(project = RetryStateProj)]
-enum RetryState<A>
+enum RetryOutcome<A>
 where
     A: Action,
 {
-    Running(#[pin] A::Future),
-    Sleeping(#[pin] Sleep),
+    /// The result was accepted, or the condition declined to retry it.
+    Done(Result<A::Item, A::Error>),
+    /// The strategy was exhausted while the result was still retryable.
+    Exhausted(Result<A::Item, A::Error>),
 }
 
-impl<A: Action> RetryState<A> {
-    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> RetryFuturePoll<A> {
-        match self.project() {
-            RetryStateProj::Running(future) => RetryFuturePoll::Running(future.poll(cx)),
-            RetryStateProj::Sleeping(future) => RetryFuturePoll::Sleeping(future.poll(cx)),
+/// Future that drives multiple attempts at an action via a retry strategy.
+///
+/// A retry is `Send` exactly when its strategy, action and the futures of the action are, and
+/// it does not need to be `'static`. So a retry of an action that is not `Send`, for instance one
+/// holding an `Rc`, can be awaited directly or spawned on a `tokio::task::LocalSet`, while a
+/// retry of a `Send` action can be passed to `tokio::spawn` as is. No part of a retry is boxed.
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct Retry<
+    I,
+    A,
+    #[cfg(feature = "tokio")] S = TokioSleeper,
+    #[cfg(not(feature = "tokio"))] S,
+    N = (),
+> where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+    N: Notify<A::Error>,
+{
+    #[pin]
+    retry_if: RetryIf<I, A, fn(&A::Error) -> bool, fn(&A::Item) -> bool, S, N>,
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    pub fn spawn<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> Retry<I, A> {
+        Retry::spawn_with_sleeper(strategy, action, TokioSleeper)
+    }
+
+    /// Retries each of `actions` concurrently, each one with a fresh strategy from
+    /// `strategy_factory`, and resolves with all of their results once every action is done.
+    ///
+    /// The results are in the same order as `actions`.
+    pub fn spawn_all<T, F>(
+        mut strategy_factory: F,
+        actions: Vec<A>,
+    ) -> RetryAll<Detailed<RetryIf<I, A, fn(&A::Error) -> bool, fn(&A::Item) -> bool>>>
+    where
+        T: IntoIterator<IntoIter = I, Item = Duration>,
+        F: FnMut() -> T,
+    {
+        RetryAll::new(
+            actions
+                .into_iter()
+                .map(|action| Retry::spawn(strategy_factory(), action).detailed())
+                .collect(),
+        )
+    }
+
+    /// Like `Retry::spawn`, but sleeps on the timer of the runtime behind `handle`.
+    ///
+    /// This allows the retry to be polled from threads outside of that runtime, for instance
+    /// by a library that owns a dedicated runtime.
+    pub fn spawn_on_handle<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        handle: &Handle,
+        strategy: T,
+        action: A,
+    ) -> Retry<I, A, HandleSleeper> {
+        Retry::spawn_with_sleeper(strategy, action, HandleSleeper::new(handle.clone()))
+    }
+
+    /// Like `Retry::spawn`, but yields to the scheduler once after each delay before retrying.
+    ///
+    /// See `YieldingSleeper`.
+    pub fn spawn_fair<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> Retry<I, A, YieldingSleeper> {
+        Retry::spawn_with_sleeper(strategy, action, YieldingSleeper::default())
+    }
+
+    /// Like `Retry::spawn`, but resolves with a detailed `Error` on failure, and on success also
+    /// with the time elapsed since the retry was created, including all attempts and delays.
+    pub fn spawn_timed<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> Timed<Detailed<RetryIf<I, A, fn(&A::Error) -> bool, fn(&A::Item) -> bool>>> {
+        Retry::spawn(strategy, action).timed()
+    }
+
+    /// Like `Retry::spawn`, but gives up after at most `max_retries` retries, whatever the
+    /// strategy yields, and then resolves with `Error::RetriesExhausted`.
+    ///
+    /// This is a safety net against accidentally retrying forever with an unbounded strategy,
+    /// see `RetryIf::max_retries`.
+    pub fn spawn_bounded<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        max_retries: usize,
+    ) -> Detailed<RetryIf<I, A, fn(&A::Error) -> bool, fn(&A::Item) -> bool>> {
+        Retry::spawn(strategy, action)
+            .retry_if
+            .max_retries(max_retries)
+            .detailed()
+    }
+
+    /// Like `Retry::spawn`, but takes a fresh strategy from `policy`.
+    pub fn spawn_policy<P: RetryPolicy<Iter = I> + ?Sized>(policy: &P, action: A) -> Retry<I, A> {
+        Retry::spawn(policy.iter(), action)
+    }
+
+    /// Like `Retry::spawn`, but borrows the strategy as a template and leaves it untouched.
+    ///
+    /// The template is cloned once, when the retry is created, and not for every attempt,
+    /// so each retry created from the same template follows the full schedule from the start.
+    pub fn spawn_reusable<T: IntoIterator<IntoIter = I, Item = Duration> + Clone>(
+        strategy: &T,
+        action: A,
+    ) -> Retry<I, A> {
+        Retry::spawn(strategy.clone(), action)
+    }
+
+    /// Like `Retry::spawn`, but sleeps for the `initial` duration before the first attempt.
+    ///
+    /// The strategy only governs the delays between retries, it is not advanced by the initial sleep.
+    pub fn spawn_after<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        initial: Duration,
+        strategy: T,
+        action: A,
+    ) -> Retry<I, A> {
+        let mut sleeper = TokioSleeper;
+        Retry {
+            retry_if: RetryIf::new(
+                strategy.into_iter(),
+                RetryState::Sleeping(sleeper.sleep(initial)),
+                action,
+                (|_| true) as fn(&A::Error) -> bool,
+                (|_| false) as fn(&A::Item) -> bool,
+                sleeper,
+                (),
+            ),
+        }
+    }
+
+    /// Resumes a retry that was interrupted while sleeping, for instance by a process restart.
+    ///
+    /// The retry first sleeps for the remaining `initial_sleep`, then attempts the action. The
+    /// strategy is expected to be positioned where the interrupted retry left off (see
+    /// `ExponentialBackoff::with_current`), and governs the delays between the retries after that.
+    pub fn resume<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        initial_sleep: Duration,
+    ) -> Retry<I, A> {
+        Retry::spawn_after(initial_sleep, strategy, action)
+    }
+}
+
+impl<I, A, S> Retry<I, A, S>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+{
+    /// Like `Retry::spawn`, but uses the given sleeper for the delays between attempts.
+    pub fn spawn_with_sleeper<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        mut action: A,
+        sleeper: S,
+    ) -> Retry<I, A, S> {
+        let future = action.run();
+        Retry {
+            retry_if: RetryIf::new(
+                strategy.into_iter(),
+                RetryState::Running(future),
+                action,
+                (|_| true) as fn(&A::Error) -> bool,
+                (|_| false) as fn(&A::Item) -> bool,
+                sleeper,
+                (),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but only retries while `budget` has tokens left, and otherwise
+    /// resolves with the last error.
+    ///
+    /// The budget can be shared between many retries to cap their combined rate of retries.
+    pub fn spawn_with_budget<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        budget: RetryBudget,
+    ) -> RetryIf<I, A, RetryBudget, fn(&A::Item) -> bool, TokioSleeper, RetryBudget> {
+        RetryIf::spawn_notify(strategy, action, budget.clone(), budget)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<A> Retry<AdaptiveBackoff, A, TokioSleeper, AdaptiveBackoff>
+where
+    A: Action,
+{
+    /// Like `Retry::spawn`, but sleeps for the current delay of `backoff` between attempts,
+    /// and reports the outcomes back to it.
+    ///
+    /// Every retried error grows the delay of the backoff, and the eventual success decays it,
+    /// so that retries sharing a clone of the backoff adapt to the health of the service.
+    pub fn spawn_adaptive(
+        backoff: AdaptiveBackoff,
+        action: A,
+    ) -> Retry<AdaptiveBackoff, A, TokioSleeper, AdaptiveBackoff> {
+        Retry::spawn_notify(backoff.clone(), action, backoff)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<Deadline<I>, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but gives up with the last error once `deadline` has passed.
+    ///
+    /// The deadline is checked whenever an attempt fails, before sleeping, so an attempt that
+    /// runs past the deadline is not retried. A delay that would end after the deadline is
+    /// shortened to end right at it.
+    pub fn spawn_with_deadline<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        deadline: tokio::time::Instant,
+    ) -> Retry<Deadline<I>, A> {
+        let deadline = deadline.into_std();
+        let retry = Retry::spawn(Deadline::until(strategy.into_iter(), deadline), action);
+        Retry {
+            retry_if: retry.retry_if.with_deadline(deadline),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<SystemDeadline<I>, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn_with_deadline`, but for a wall-clock deadline, such as the expiry
+    /// time of a request received from upstream.
+    ///
+    /// The time remaining until `deadline` is computed from the system clock whenever an attempt
+    /// fails, so changes to the system clock are taken into account. The action is always
+    /// attempted once, even if `deadline` has already passed, but never retried in that case.
+    pub fn spawn_before<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        deadline: SystemTime,
+    ) -> Retry<SystemDeadline<I>, A> {
+        let strategy = SystemDeadline::new(strategy.into_iter(), deadline);
+        let remaining = strategy.remaining().unwrap_or(Duration::ZERO);
+        let retry = Retry::spawn(strategy, action);
+        let deadline = retry.retry_if.driver.now() + remaining;
+        Retry {
+            retry_if: retry.retry_if.with_deadline(deadline),
+        }
+    }
+}
+
+impl<I, A, S> Retry<SystemDeadline<I, S>, A, S>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper + Clock + Clone,
+{
+    /// Like `Retry::spawn_before`, but sleeps with the given sleeper, and measures the time
+    /// remaining until `deadline` with it as well, once it was read from the system clock.
+    pub fn spawn_before_with_sleeper<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        deadline: SystemTime,
+        sleeper: S,
+    ) -> Retry<SystemDeadline<I, S>, A, S> {
+        let strategy =
+            SystemDeadline::new(strategy.into_iter(), deadline).with_clock(sleeper.clone());
+        let remaining = strategy.remaining().unwrap_or(Duration::ZERO);
+        let retry = Retry::spawn_with_sleeper(strategy, action, sleeper);
+        let deadline = retry.retry_if.driver.now() + remaining;
+        Retry {
+            retry_if: retry.retry_if.with_deadline(deadline),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but resolves with the error right away when `is_stop` returns
+    /// `true` for it, and retries all other errors.
+    ///
+    /// This is `RetryIf::spawn` with the condition negated, for call sites that reserve one
+    /// error to mean "stop".
+    pub fn spawn_stop_on<T, F>(strategy: T, action: A, is_stop: F) -> RetryIf<I, A, RetryUnless<F>>
+    where
+        T: IntoIterator<IntoIter = I, Item = Duration>,
+        F: FnMut(&A::Error) -> bool,
+    {
+        RetryIf::spawn(strategy, action, RetryUnless::new(is_stop))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, F> Retry<Map<I, F>, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    F: FnMut(Duration) -> Duration,
+{
+    /// Like `Retry::spawn`, but calls `hook` with each delay of the strategy, and sleeps for the
+    /// delay it returns instead.
+    ///
+    /// This is handy in tests, to record the intended delays while not sleeping at all:
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use tokio_retry::Retry;
+    /// # use tokio_retry::strategy::ExponentialBackoff;
+    /// # async fn run() {
+    /// let mut delays = Vec::new();
+    /// let hook = |delay| {
+    ///     delays.push(delay);
+    ///     Duration::ZERO
+    /// };
+    /// let _ = Retry::spawn_with_delay_hook(ExponentialBackoff::from_millis(10).take(3), || async {
+    ///     Err::<(), ()>(())
+    /// }, hook).await;
+    /// # }
+    /// ```
+    pub fn spawn_with_delay_hook<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        hook: F,
+    ) -> Retry<Map<I, F>, A> {
+        Retry::spawn(strategy.into_iter().map(hook), action)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, BreakerAction<A>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but consults `breaker` before every attempt, and resolves with
+    /// `Error::CircuitOpen` instead of running the action while the breaker is open.
+    ///
+    /// Errors of the action are reported as `Error::OperationError`.
+    pub fn spawn_with_breaker<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        breaker: CircuitBreaker,
+    ) -> RetryIf<I, BreakerAction<A>, fn(&Error<A::Error>) -> bool> {
+        RetryIf::spawn(
+            strategy,
+            BreakerAction::new(action, breaker),
+            (|err| match *err {
+                Error::CircuitOpen => false,
+                _ => true,
+            }) as fn(&Error<A::Error>) -> bool,
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but resolves with `Error::Aborted` as soon as `signal` resolves,
+    /// dropping the current attempt or delay, for instance on shutdown.
+    ///
+    /// Errors of the action are reported like with `Retry::detailed`.
+    pub fn spawn_until_signal<T, G>(
+        strategy: T,
+        action: A,
+        signal: G,
+    ) -> UntilSignal<Detailed<RetryIf<I, A, fn(&A::Error) -> bool, fn(&A::Item) -> bool>>, G>
+    where
+        T: IntoIterator<IntoIter = I, Item = Duration>,
+        G: Future<Output = ()>,
+    {
+        UntilSignal {
+            inner: Retry::spawn(strategy, action).detailed(),
+            signal: signal,
+        }
+    }
+}
+
+#[cfg(feature = "cancel")]
+impl<I, A> Retry<I, CancellableAction<A>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but resolves with `Error::Cancelled` once `token` is cancelled,
+    /// cutting short the current delay instead of waiting for it to end.
+    ///
+    /// An attempt that is already running when the token is cancelled is allowed to complete.
+    /// Errors of the action are reported as `Error::OperationError`.
+    pub fn spawn_cancellable<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        token: CancellationToken,
+    ) -> RetryIf<
+        I,
+        CancellableAction<A>,
+        fn(&Error<A::Error>) -> bool,
+        fn(&A::Item) -> bool,
+        CancellableSleeper,
+    > {
+        let mut action = CancellableAction::new(action, token.clone());
+        let future = action.run();
+        RetryIf::new(
+            strategy.into_iter(),
+            RetryState::Running(future),
+            action,
+            (|err| match *err {
+                Error::Cancelled => false,
+                _ => true,
+            }) as fn(&Error<A::Error>) -> bool,
+            (|_| false) as fn(&A::Item) -> bool,
+            CancellableSleeper::new(token),
+            (),
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A, TokioSleeper, EventSender>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    A::Error: fmt::Display,
+{
+    /// Like `Retry::spawn`, but also returns a channel that receives an `AttemptEvent` before
+    /// every retry, for instance to display the progress of the retry.
+    ///
+    /// The channel is closed once the retry resolves. It buffers up to 32 events, and further
+    /// events are dropped until the receiver catches up.
+    pub fn spawn_with_events<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> (
+        Retry<I, A, TokioSleeper, EventSender>,
+        mpsc::Receiver<AttemptEvent>,
+    ) {
+        let (sender, receiver) = mpsc::channel(32);
+        (
+            Retry::spawn_notify(strategy, action, EventSender::new(sender)),
+            receiver,
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A, TokioSleeper, WatchSender>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    A::Error: fmt::Display,
+{
+    /// Like `Retry::spawn`, but also returns a watch that holds the latest `RetryStatus`,
+    /// updated whenever an attempt starts or fails and once the retry resolves.
+    ///
+    /// Unlike the channel of `Retry::spawn_with_events`, the watch never builds up a backlog,
+    /// which suits health checks that only care about the current state of a long retry.
+    pub fn spawn_with_watch<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> (
+        Retry<I, A, TokioSleeper, WatchSender>,
+        watch::Receiver<RetryStatus>,
+    ) {
+        let (sender, receiver) = watch::channel(RetryStatus::default());
+        (
+            Retry::spawn_notify(strategy, action, WatchSender::new(sender)),
+            receiver,
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, N> Retry<I, A, TokioSleeper, N>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    N: Notify<A::Error>,
+{
+    /// Like `Retry::spawn`, but calls `notify` with the error and the upcoming delay
+    /// before every retry.
+    pub fn spawn_notify<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        mut action: A,
+        notify: N,
+    ) -> Retry<I, A, TokioSleeper, N> {
+        let future = action.run();
+        Retry {
+            retry_if: RetryIf::new(
+                strategy.into_iter(),
+                RetryState::Running(future),
+                action,
+                (|_| true) as fn(&A::Error) -> bool,
+                (|_| false) as fn(&A::Item) -> bool,
+                TokioSleeper,
+                notify,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, O> Retry<I, A, TokioSleeper, Observed<O>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    O: RetryObserver<A::Error>,
+{
+    /// Like `Retry::spawn`, but reports every attempt, retry, success and give-up to `observer`.
+    pub fn spawn_observed<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        observer: O,
+    ) -> Retry<I, A, TokioSleeper, Observed<O>> {
+        Retry::spawn_notify(strategy, action, Observed::new(observer))
+    }
+}
+
+#[cfg(all(feature = "tracing", feature = "tokio"))]
+impl<I, A> Retry<I, A, TokioSleeper, Traced>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    A::Error: fmt::Display,
+{
+    /// Like `Retry::spawn`, but records every retry and the eventual success as `tracing` events
+    /// within a new `retry` span (see `Traced`).
+    pub fn spawn_traced<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> Retry<I, A, TokioSleeper, Traced> {
+        Retry::spawn_notify(strategy, action, Traced::default())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, F> Retry<I, Fallbacks<A, F>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    F: Fn(&A::Error) -> Option<A::Item>,
+{
+    /// Like `Retry::spawn`, but resolves immediately with a fallback item whenever
+    /// `fallbacks` returns `Some` for an error, instead of retrying.
+    pub fn spawn_with_fallbacks<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        fallbacks: F,
+    ) -> Retry<I, Fallbacks<A, F>> {
+        Retry::spawn(strategy, Fallbacks::new(action, fallbacks))
+    }
+}
+
+impl<I, A, S, N> Retry<I, A, S, N>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+    N: Notify<A::Error>,
+{
+    /// Resolves with an `Error` that tells apart errors which were not retried
+    /// from errors which exhausted the retry strategy.
+    pub fn detailed(
+        self,
+    ) -> Detailed<RetryIf<I, A, fn(&A::Error) -> bool, fn(&A::Item) -> bool, S, N>> {
+        self.retry_if.detailed()
+    }
+
+    /// Resolves like `detailed`, and on success also with the time elapsed since this was
+    /// called, see `RetryIf::timed`.
+    pub fn timed(
+        self,
+    ) -> Timed<Detailed<RetryIf<I, A, fn(&A::Error) -> bool, fn(&A::Item) -> bool, S, N>>> {
+        self.retry_if.timed()
+    }
+
+    /// Gives up after at most `max_retries` retries, see `RetryIf::max_retries`.
+    pub fn max_retries(self, max_retries: usize) -> Retry<I, A, S, N> {
+        Retry {
+            retry_if: self.retry_if.max_retries(max_retries),
+        }
+    }
+
+    /// Ensures that every attempt starts at least `spacing` after the previous attempt started,
+    /// see `RetryIf::min_attempt_spacing`.
+    pub fn min_attempt_spacing(self, spacing: Duration) -> Retry<I, A, S, N> {
+        Retry {
+            retry_if: self.retry_if.min_attempt_spacing(spacing),
+        }
+    }
+
+    /// Gives up with the last error when the strategy panics, see `RetryIf::catch_strategy_panic`.
+    pub fn catch_strategy_panic(self) -> Retry<I, A, S, N> {
+        Retry {
+            retry_if: self.retry_if.catch_strategy_panic(),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<A> Retry<Box<dyn Iterator<Item = Duration> + Send + Sync>, A>
+where
+    A: Action,
+{
+    /// Starts retrying the action as configured by `policy`, with a fresh strategy from the policy.
+    pub fn spawn_with_policy(
+        policy: &Policy<A::Error>,
+        action: A,
+    ) -> RetryIf<
+        Box<dyn Iterator<Item = Duration> + Send + Sync>,
+        A,
+        Policy<A::Error>,
+        fn(&A::Item) -> bool,
+        TokioSleeper,
+        Policy<A::Error>,
+    > {
+        let retry =
+            RetryIf::spawn_notify(policy.strategy(), action, policy.clone(), policy.clone());
+        match policy.retry_limit() {
+            Some(max_retries) => retry.max_retries(max_retries),
+            None => retry,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<A: Action> Retry<iter::Empty<Duration>, A> {
+    /// Runs the action on every tick of an interval of `period`, until it succeeds or it has
+    /// been attempted `max_ticks` times, and resolves with the last error in the latter case.
+    ///
+    /// Unlike a retry strategy, this keeps a steady cadence regardless of how long attempts
+    /// take. The first attempt starts right away, and at least one attempt is made. Ticks missed
+    /// while an attempt runs are handled according to `Ticking::missed_tick_behavior`, which
+    /// defaults to `MissedTickBehavior::Burst`.
+    pub fn spawn_interval(period: Duration, max_ticks: usize, action: A) -> Ticking<A> {
+        Ticking {
+            action: action,
+            period: period,
+            missed_tick_behavior: MissedTickBehavior::Burst,
+            interval: None,
+            ticks: cmp::max(max_ticks, 1),
+            running: None,
+        }
+    }
+
+    /// Runs the action, and starts up to `max_hedges` additional concurrent attempts, one every
+    /// `hedge_delay` for as long as no attempt succeeded, instead of waiting for an attempt to
+    /// fail before retrying.
+    ///
+    /// Resolves with the first successful result, dropping and thereby cancelling all other
+    /// attempts still in flight. When an attempt fails while no other one is in flight, the next
+    /// hedge is started right away. If every attempt fails, resolves with the last error.
+    pub fn spawn_hedged(hedge_delay: Duration, max_hedges: usize, action: A) -> Hedged<A> {
+        Hedged::new(hedge_delay, max_hedges, action, TokioSleeper)
+    }
+}
+
+impl<A: Action, S: Sleeper> Retry<iter::Empty<Duration>, A, S> {
+    /// Like `Retry::spawn_hedged`, but waits for the `hedge_delay` with the given sleeper.
+    pub fn spawn_hedged_with_sleeper(
+        hedge_delay: Duration,
+        max_hedges: usize,
+        action: A,
+        sleeper: S,
+    ) -> Hedged<A, S> {
+        Hedged::new(hedge_delay, max_hedges, action, sleeper)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, ErrorHistory<A>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    A::Error: Clone,
+{
+    /// Like `Retry::spawn`, but once the retry gives up, resolves with `Error::AllFailed`
+    /// carrying the errors of all attempts in order, rather than with the last error only.
+    pub fn spawn_collect_errors<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> CollectErrors<Retry<I, ErrorHistory<A>>, A::Error> {
+        let action = ErrorHistory::new(action);
+        let errors = action.errors();
+        CollectErrors::new(Retry::spawn(strategy, action), errors)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, ReportingAction<A>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    A::Error: fmt::Display,
+{
+    /// Like `Retry::spawn`, but resolves with a detailed `Error` on failure, along with a
+    /// `RetryReport` of the timing and outcome of every attempt.
+    ///
+    /// Timing is only recorded for retries created with this constructor.
+    pub fn spawn_report<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> Reported<
+        Detailed<RetryIf<I, ReportingAction<A>, fn(&A::Error) -> bool, fn(&A::Item) -> bool>>,
+    > {
+        let action = ReportingAction::new(action);
+        let state = action.state();
+        Reported::new(Retry::spawn(strategy, action).detailed(), state)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, Limited<A>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but holds a permit of `permits` for the duration of every attempt,
+    /// which bounds the number of attempts in flight across all retries sharing the semaphore.
+    ///
+    /// Every attempt is only started once it holds a permit, and the permit is released while
+    /// sleeping between attempts.
+    pub fn spawn_limited<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        permits: Arc<Semaphore>,
+    ) -> Retry<I, Limited<A>> {
+        Retry::spawn(strategy, Limited::new(action, permits))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, E, F> Retry<I, MapErr<A, F>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    F: FnMut(A::Error) -> E,
+{
+    /// Like `Retry::spawn`, but maps every error of the action with `f`, so that the retry
+    /// resolves with the mapped error.
+    pub fn spawn_map_err<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        f: F,
+    ) -> Retry<I, MapErr<A, F>> {
+        Retry::spawn(strategy, MapErr::new(action, f))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, WithAttempt<A>>
+where
+    I: Iterator<Item = Duration>,
+    A: ActionWithAttempt,
+{
+    /// Like `Retry::spawn`, but passes the zero-based attempt number to the action.
+    pub fn spawn_with_attempt<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> Retry<I, WithAttempt<A>> {
+        Retry::spawn(strategy, WithAttempt::new(action))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, M> Retry<I, CatchUnwind<A, M>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    M: FnMut(Box<dyn Any + Send>) -> A::Error,
+{
+    /// Like `Retry::spawn`, but a panic of an attempt, either when the action is run or while
+    /// its future is polled, is caught and converted into an error by `map_panic`, and then
+    /// retried like any other error.
+    ///
+    /// See `CatchUnwind` for why the action need not be `UnwindSafe`.
+    pub fn spawn_catch_unwind<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        map_panic: M,
+    ) -> Retry<I, CatchUnwind<A, M>> {
+        Retry::spawn(strategy, CatchUnwind::new(action, map_panic))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, S, U> Retry<I, WithSetup<S, U>>
+where
+    I: Iterator<Item = Duration>,
+    WithSetup<S, U>: Action,
+{
+    /// Like `Retry::spawn`, but every attempt first runs `setup` to obtain a resource, such as
+    /// a connection, and then moves it into `use_`.
+    ///
+    /// Whether `setup` or `use_` fails, the next attempt starts over with a fresh `setup`.
+    pub fn spawn_with_setup<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        setup: S,
+        use_: U,
+    ) -> Retry<I, WithSetup<S, U>> {
+        Retry::spawn(strategy, WithSetup::new(setup, use_))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but awaits `cleanup` with the error after every failed attempt that
+    /// is going to be retried, before sleeping until the next attempt.
+    ///
+    /// This lets an attempt that left partial state behind, such as a half-open connection or a
+    /// temporary file, be cleaned up first. `cleanup` does not run after a success, nor after
+    /// the last failed attempt.
+    pub fn spawn_with_cleanup<T, F, Fut>(
+        strategy: T,
+        action: A,
+        cleanup: F,
+    ) -> WithCleanup<I, A, F, Fut>
+    where
+        T: IntoIterator<IntoIter = I, Item = Duration>,
+        F: FnMut(&A::Error) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        WithCleanup {
+            driver: RetryDriver::new(strategy, action),
+            cleanup: cleanup,
+            cleaning: None,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> Retry<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `Retry::spawn`, but also retries when `predicate` asks to for a successful `Item`,
+    /// after the delay it returns, for instance a "not before" time sent by a server.
+    ///
+    /// `predicate` returns `None` to accept the item. Every retry, whether after an error or
+    /// after an item, consumes a delay of the strategy, and once the strategy is exhausted
+    /// the future resolves with the last result, even an item that asked for a retry.
+    pub fn spawn_on_with_delay<T, F>(strategy: T, action: A, predicate: F) -> OkDelaying<I, A, F>
+    where
+        T: IntoIterator<IntoIter = I, Item = Duration>,
+        F: FnMut(&A::Item) -> Option<Duration>,
+    {
+        OkDelaying {
+            driver: RetryDriver::new(strategy, action),
+            predicate: predicate,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, F, R, E> Retry<I, SyncAction<F>>
+where
+    I: Iterator<Item = Duration>,
+    F: FnMut() -> Result<R, E>,
+{
+    /// Like `Retry::spawn`, but for a synchronous, fallible closure.
+    ///
+    /// The closure runs directly on the task that polls the retry, which avoids the overhead of
+    /// spawning but blocks the executor thread for as long as the closure runs. For truly blocking
+    /// work, use `Retry::spawn` with an action that runs the closure via `tokio::task::spawn_blocking`.
+    pub fn spawn_fn_sync<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        f: F,
+    ) -> Retry<I, SyncAction<F>> {
+        Retry::spawn(strategy, SyncAction::new(f))
+    }
+}
+
+impl<I, A, S, N> Future for Retry<I, A, S, N>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+    N: Notify<A::Error>,
+{
+    type Output = Result<A::Item, A::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        this.retry_if.poll(cx)
+    }
+}
+
+impl<I, A, S, N> fmt::Debug for Retry<I, A, S, N>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    S: Sleeper,
+    N: Notify<A::Error>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field(
+                "state",
+                &format_args!("{}", self.retry_if.driver.state_name()),
+            )
+            .field("attempts", &self.retry_if.driver.attempts())
+            .finish()
+    }
+}
+
+/// Drives multiple attempts at an action via a retry strategy, like `Retry::spawn`, and resolves
+/// with an `Error` that tells apart errors which were not retried from errors which exhausted
+/// the strategy, like `Retry::detailed`.
+///
+/// The returned future hides the concrete `Retry` type, and is `Send` whenever the strategy and the
+/// action are, so that it can be passed to `tokio::spawn` or returned from functions directly.
+#[cfg(feature = "tokio")]
+pub fn retry<T, A>(
+    strategy: T,
+    action: A,
+) -> impl Future<Output = Result<A::Item, Error<A::Error>>> + Send
+where
+    T: IntoIterator<Item = Duration>,
+    T::IntoIter: Send,
+    A: Action + Send,
+    A::Future: Send,
+{
+    Retry::spawn(strategy, action).detailed()
+}
+
+/// Drives multiple attempts at an operation via a retry strategy, and calls `notify` with the
+/// error and the upcoming delay before every retry.
+///
+/// This mirrors `retry_notify` of the `backoff` crate, to ease migrating from it, except that
+/// `notify` borrows the error instead of taking it, and that the strategy is any iterator of
+/// delays. Like there, the error tells apart errors which were not retried from errors which
+/// exhausted the strategy:
+///
+/// ```rust,no_run
+/// # use tokio_retry::{retry_notify, Error};
+/// # use tokio_retry::strategy::ExponentialBackoff;
+/// # async fn fetch_url(url: &str) -> Result<String, std::io::Error> { Ok(url.to_string()) }
+/// # async fn run() -> Result<(), Error<std::io::Error>> {
+/// let body = retry_notify(
+///     ExponentialBackoff::from_millis(10).take(5),
+///     || fetch_url("https://www.rust-lang.org"),
+///     |err, dur| println!("Error happened at {:?}: {}", dur, err),
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub fn retry_notify<T, A, N>(
+    strategy: T,
+    operation: A,
+    notify: N,
+) -> impl Future<Output = Result<A::Item, Error<A::Error>>> + Send
+where
+    T: IntoIterator<Item = Duration>,
+    T::IntoIter: Send,
+    A: Action + Send,
+    A::Future: Send,
+    N: FnMut(&A::Error, Duration) + Send,
+{
+    Retry::spawn_notify(strategy, operation, notify).detailed()
+}
+
+/// Future that drives multiple attempts at an action via a retry strategy. Retries are only attempted if
+/// the `Error` returned by the future satisfies a given condition.
+///
+/// Optionally, an `Item` returned by the future can be retried as well, for instance when the
+/// result indicates that a job is still pending (see `RetryIf::spawn_on`).
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct RetryIf<
+    I,
+    A,
+    C,
+    #[cfg(feature = "tokio")] P = fn(&<A as Action>::Item) -> bool,
+    #[cfg(not(feature = "tokio"))] P,
+    #[cfg(feature = "tokio")] S = TokioSleeper,
+    #[cfg(not(feature = "tokio"))] S,
+    N = (),
+> where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    C: Condition<A::Error>,
+    P: Condition<A::Item>,
+    S: Sleeper,
+    N: Notify<A::Error>,
+{
+    #[pin]
+    driver: RetryDriver<I, A, S>,
+    condition: C,
+    ok_condition: P,
+    notify: N,
+    // the cap on retries, enforced by the run loop
+    max_retries: Option<usize>,
+    // the deadline that the strategy enforces, to tell why it was exhausted
+    deadline: Option<Instant>,
+    // when the first attempt started, if the condition is told the elapsed time
+    started: Option<Instant>,
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, C> RetryIf<I, A, C>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    C: Condition<A::Error>,
+{
+    pub fn spawn<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        condition: C,
+    ) -> RetryIf<I, A, C> {
+        RetryIf::spawn_on(
+            strategy,
+            action,
+            condition,
+            (|_| false) as fn(&A::Item) -> bool,
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> RetryIf<I, A, Box<dyn Condition<A::Error> + Send>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `RetryIf::spawn`, but with a boxed condition, so that the condition can be selected
+    /// at runtime, for instance from configuration.
+    pub fn spawn_dyn<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        condition: Box<dyn Condition<A::Error> + Send>,
+    ) -> RetryIf<I, A, Box<dyn Condition<A::Error> + Send>> {
+        RetryIf::spawn(strategy, action, condition)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, F> RetryIf<I, MapErr<A, F>, fn(&Result<A::Error, A::Error>) -> bool>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    F: FnMut(A::Error) -> Result<A::Error, A::Error>,
+{
+    /// Like `RetryIf::spawn`, but the condition takes ownership of each error.
+    ///
+    /// The condition returns `Ok` with a possibly transformed error to retry, or `Err` with a
+    /// possibly transformed error to stop. Either way, the error handed back by the condition is
+    /// the one the retry resolves with, should this have been the last attempt.
+    pub fn spawn_owned<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        condition: F,
+    ) -> Flattened<RetryIf<I, MapErr<A, F>, fn(&Result<A::Error, A::Error>) -> bool>> {
+        Flattened {
+            inner: RetryIf::spawn(
+                strategy,
+                MapErr::new(action, condition),
+                Result::is_ok as fn(&Result<A::Error, A::Error>) -> bool,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<'a, I, A, C> RetryIf<&'a mut I, A, C>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    C: Condition<A::Error>,
+{
+    /// Like `RetryIf::spawn`, but borrows the strategy instead of consuming it.
+    ///
+    /// This lets several sequential actions share a single schedule: each one continues
+    /// where the previous one left off.
+    pub fn spawn_borrowed(
+        strategy: &'a mut I,
+        action: A,
+        condition: C,
+    ) -> RetryIf<&'a mut I, A, C> {
+        RetryIf::spawn(strategy, action, condition)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, C> RetryIf<I, A, WithContext<C>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    C: ContextCondition<A::Error>,
+{
+    /// Like `RetryIf::spawn`, but the condition is also given the number of the failed attempt
+    /// and the time elapsed since the first attempt started.
+    ///
+    /// Both are tracked by the retry, so that every attempt is counted, including attempts that
+    /// were retried because of an `Item`, and time only counts from the first attempt on.
+    pub fn spawn_ctx<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        condition: C,
+    ) -> RetryIf<I, A, WithContext<C>> {
+        RetryIf::spawn(strategy, action, WithContext::new(condition)).measure_elapsed()
+    }
+}
+
+impl<I, A, C, S> RetryIf<I, A, WithContext<C>, fn(&A::Item) -> bool, S>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    C: ContextCondition<A::Error>,
+    S: Sleeper,
+{
+    /// Like `RetryIf::spawn_ctx`, but uses the given sleeper for the delays between attempts,
+    /// which also measures the elapsed time given to the condition.
+    pub fn spawn_ctx_with_sleeper<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        mut action: A,
+        condition: C,
+        sleeper: S,
+    ) -> RetryIf<I, A, WithContext<C>, fn(&A::Item) -> bool, S> {
+        let future = action.run();
+        RetryIf::new(
+            strategy.into_iter(),
+            RetryState::Running(future),
+            action,
+            WithContext::new(condition),
+            (|_| false) as fn(&A::Item) -> bool,
+            sleeper,
+            (),
+        )
+        .measure_elapsed()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, F> RetryIf<I, A, Decide<F>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    F: FnMut(&A::Error) -> RetryDecision,
+{
+    /// Like `RetryIf::spawn`, but the closure decides how to proceed after each error,
+    /// optionally overriding the next delay (see `RetryDecision`).
+    pub fn spawn_decide<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+        decide: F,
+    ) -> RetryIf<I, A, Decide<F>> {
+        RetryIf::spawn(strategy, action, Decide::new(decide))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A> RetryIf<I, A, fn(&A::Error) -> bool>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    /// Like `RetryIf::spawn`, but the condition also chooses the delay before each retry.
+    ///
+    /// After every error, the strategy is advanced as usual, and its delay is passed to the
+    /// condition as the default. The condition returns the delay to sleep instead, which may
+    /// simply be the default, or `None` to not retry. Once the strategy is exhausted, the retry
+    /// gives up without consulting the condition.
+    pub fn spawn_delaying<T, C>(strategy: T, action: A, condition: C) -> Delaying<I, A, C>
+    where
+        T: IntoIterator<IntoIter = I, Item = Duration>,
+        C: DelayingCondition<A::Error>,
+    {
+        Delaying {
+            driver: RetryDriver::new(strategy, action),
+            condition: condition,
         }
     }
 }
 
-enum RetryFuturePoll<A>
+#[cfg(feature = "tokio")]
+impl<I, A, C, N> RetryIf<I, A, C, fn(&A::Item) -> bool, TokioSleeper, N>
 where
+    I: Iterator<Item = Duration>,
     A: Action,
+    C: Condition<A::Error>,
+    N: Notify<A::Error>,
 {
-    Running(Poll<Result<A::Item, A::Error>>),
-    Sleeping(Poll<()>),
+    /// Like `RetryIf::spawn`, but calls `notify` with the error and the upcoming delay
+    /// before every retry.
+    pub fn spawn_notify<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        mut action: A,
+        condition: C,
+        notify: N,
+    ) -> RetryIf<I, A, C, fn(&A::Item) -> bool, TokioSleeper, N> {
+        let future = action.run();
+        RetryIf::new(
+            strategy.into_iter(),
+            RetryState::Running(future),
+            action,
+            condition,
+            (|_| false) as fn(&A::Item) -> bool,
+            TokioSleeper,
+            notify,
+        )
+    }
 }
 
-/// Future that drives multiple attempts at an action via a retry strategy.
-#[pin_project]
-pub struct Retry<I, A>
+#[cfg(feature = "tokio")]
+impl<I, A, C, P> RetryIf<I, A, C, P>
 where
     I: Iterator<Item = Duration>,
     A: Action,
+    C: Condition<A::Error>,
+    P: Condition<A::Item>,
 {
-    #[pin]
-    retry_if: RetryIf<I, A, fn(&A::Error) -> bool>,
+    /// Like `RetryIf::spawn`, but additionally retries when the action succeeds with an `Item`
+    /// that satisfies `ok_condition`.
+    ///
+    /// If the strategy is exhausted while the `Item` still satisfies the condition,
+    /// the future resolves with the last `Ok` value.
+    pub fn spawn_on<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        mut action: A,
+        condition: C,
+        ok_condition: P,
+    ) -> RetryIf<I, A, C, P> {
+        let future = action.run();
+        RetryIf::new(
+            strategy.into_iter(),
+            RetryState::Running(future),
+            action,
+            condition,
+            ok_condition,
+            TokioSleeper,
+            (),
+        )
+    }
 }
 
-impl<I, A> Retry<I, A>
+impl<I, A, C, P, S, N> RetryIf<I, A, C, P, S, N>
 where
     I: Iterator<Item = Duration>,
     A: Action,
+    C: Condition<A::Error>,
+    P: Condition<A::Item>,
+    S: Sleeper,
+    N: Notify<A::Error>,
 {
-    pub fn spawn<T: IntoIterator<IntoIter = I, Item = Duration>>(
-        strategy: T,
+    fn new(
+        strategy: I,
+        state: RetryState<A, S>,
         action: A,
-    ) -> Retry<I, A> {
-        Retry {
-            retry_if: RetryIf::spawn(strategy, action, (|_| true) as fn(&A::Error) -> bool),
+        condition: C,
+        ok_condition: P,
+        sleeper: S,
+        notify: N,
+    ) -> RetryIf<I, A, C, P, S, N> {
+        let mut notify = notify;
+        let driver = RetryDriver::from_state(strategy, state, action, sleeper);
+        if driver.attempts() > 0 {
+            notify.attempt(driver.attempts());
+        }
+        RetryIf {
+            driver: driver,
+            condition: condition,
+            ok_condition: ok_condition,
+            notify: notify,
+            max_retries: None,
+            deadline: None,
+            started: None,
+        }
+    }
+
+    /// Gives up after at most `max_retries` retries, whatever the strategy yields, which is
+    /// reported as `GiveupReason::MaxRetries`.
+    ///
+    /// The retries are counted by the retry itself, so the cap also holds for retries that
+    /// do not advance the strategy, such as after `RetryDecision::ContinueAfter`, and for
+    /// retries of an `Item`.
+    pub fn max_retries(mut self, max_retries: usize) -> RetryIf<I, A, C, P, S, N> {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Records that the strategy ends at `deadline`, so that giving up after it passed is
+    /// reported as `GiveupReason::Deadline`.
+    pub(crate) fn with_deadline(mut self, deadline: Instant) -> RetryIf<I, A, C, P, S, N> {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Measures the time elapsed since the first attempt started, for the condition.
+    ///
+    /// The first attempt is started when the retry is created, right before this.
+    pub(crate) fn measure_elapsed(mut self) -> RetryIf<I, A, C, P, S, N> {
+        self.started = Some(self.driver.now());
+        self
+    }
+
+    /// Resolves with an `Error` that tells apart errors which the condition declined to retry
+    /// from errors which exhausted the retry strategy.
+    pub fn detailed(self) -> Detailed<RetryIf<I, A, C, P, S, N>> {
+        Detailed { inner: self }
+    }
+
+    /// Resolves like `detailed`, and on success also with the time elapsed since this was
+    /// called, which is right after the first attempt started.
+    ///
+    /// Time is measured with the sleeper, like the delays between attempts.
+    pub fn timed(self) -> Timed<Detailed<RetryIf<I, A, C, P, S, N>>> {
+        Timed {
+            start: self.driver.now(),
+            inner: self.detailed(),
+        }
+    }
+
+    /// Ensures that every attempt starts at least `spacing` after the previous attempt started.
+    ///
+    /// When an attempt fails faster than `spacing`, the delay before the next attempt is extended
+    /// to the remainder of `spacing` if needed, while a slow attempt is followed by the regular
+    /// delay only.
+    pub fn min_attempt_spacing(self, spacing: Duration) -> RetryIf<I, A, C, P, S, N> {
+        RetryIf {
+            driver: self.driver.min_attempt_spacing(spacing),
+            condition: self.condition,
+            ok_condition: self.ok_condition,
+            notify: self.notify,
+            max_retries: self.max_retries,
+            deadline: self.deadline,
+            started: self.started,
+        }
+    }
+
+    /// Gives up with the last error when the strategy panics while computing the next delay,
+    /// as if it was exhausted, instead of unwinding out of the retry future.
+    ///
+    /// This guards against faulty custom strategies, for instance a `CustomStrategy` closure.
+    /// The panic is still reported by the panic hook. Without this, a panic of the strategy
+    /// propagates to the task polling the retry.
+    pub fn catch_strategy_panic(self) -> RetryIf<I, A, C, P, S, N> {
+        RetryIf {
+            driver: self.driver.catch_strategy_panic(),
+            condition: self.condition,
+            ok_condition: self.ok_condition,
+            notify: self.notify,
+            max_retries: self.max_retries,
+            deadline: self.deadline,
+            started: self.started,
+        }
+    }
+
+    fn poll_outcome(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<RetryOutcome<A>> {
+        loop {
+            let mut this = self.as_mut().project();
+            let result = match this.driver.as_mut().poll_attempt_notify(cx, this.notify) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => result,
+            };
+            let attempts = this.driver.attempts();
+            // the clock is only read if the elapsed time is measured
+            let elapsed = match *this.started {
+                Some(started) => this.driver.now().saturating_duration_since(started),
+                None => Duration::ZERO,
+            };
+            let retryable = match result {
+                Ok(ref ok) => this.ok_condition.should_retry(ok),
+                Err(ref err) => match this.condition.decide_after(err, attempts, elapsed) {
+                    RetryDecision::Stop => false,
+                    RetryDecision::StopExhausted => {
+                        this.notify.give_up(attempts, GiveupReason::BudgetExhausted);
+                        return Poll::Ready(RetryOutcome::Exhausted(result));
+                    }
+                    RetryDecision::Continue => true,
+                    RetryDecision::ContinueAfter(duration) => {
+                        // the strategy is not advanced, so the limits are checked here
+                        let driver = &this.driver;
+                        let limit =
+                            reached_limit(*this.max_retries, *this.deadline, attempts, || {
+                                driver.now()
+                            });
+                        if let Some(reason) = limit {
+                            this.notify.give_up(attempts, reason);
+                            return Poll::Ready(RetryOutcome::Exhausted(result));
+                        }
+                        this.notify.notify(err, duration);
+                        this.driver.as_mut().retry_after(duration);
+                        continue;
+                    }
+                },
+            };
+            if !retryable {
+                if result.is_ok() {
+                    this.notify.success(attempts);
+                } else {
+                    this.notify.give_up(attempts, GiveupReason::ConditionFalse);
+                }
+                return Poll::Ready(RetryOutcome::Done(result));
+            }
+            let capped = match *this.max_retries {
+                Some(max_retries) => attempts > max_retries,
+                None => false,
+            };
+            let retry = if capped {
+                None
+            } else {
+                this.driver.as_mut().retry()
+            };
+            match retry {
+                None => {
+                    if result.is_ok() {
+                        this.notify.success(attempts);
+                    } else {
+                        let driver = &this.driver;
+                        let reason =
+                            exhausted_reason(*this.max_retries, *this.deadline, attempts, || {
+                                driver.now()
+                            });
+                        this.notify.give_up(attempts, reason);
+                    }
+                    return Poll::Ready(RetryOutcome::Exhausted(result));
+                }
+                Some(duration) => {
+                    if let Err(ref err) = result {
+                        this.notify.notify(err, duration);
+                    }
+                }
+            }
         }
     }
 }
 
-impl<I, A> Future for Retry<I, A>
+/// Tells which limit of the retry was reached after the given number of attempts, if any.
+///
+/// The clock is only read if there is a deadline, as it may not be available, for instance
+/// in the browser.
+fn reached_limit(
+    max_retries: Option<usize>,
+    deadline: Option<Instant>,
+    attempts: usize,
+    now: impl Fn() -> Instant,
+) -> Option<GiveupReason> {
+    match (max_retries, deadline) {
+        (Some(max_retries), _) if attempts > max_retries => Some(GiveupReason::MaxRetries),
+        (_, Some(deadline)) if now() >= deadline => Some(GiveupReason::Deadline),
+        _ => None,
+    }
+}
+
+/// Tells why the strategy was exhausted after the given number of attempts.
+fn exhausted_reason(
+    max_retries: Option<usize>,
+    deadline: Option<Instant>,
+    attempts: usize,
+    now: impl Fn() -> Instant,
+) -> GiveupReason {
+    reached_limit(max_retries, deadline, attempts, now).unwrap_or(GiveupReason::StrategyExhausted)
+}
+
+impl<I, A, C, P, S, N> Future for RetryIf<I, A, C, P, S, N>
 where
     I: Iterator<Item = Duration>,
     A: Action,
+    C: Condition<A::Error>,
+    P: Condition<A::Item>,
+    S: Sleeper,
+    N: Notify<A::Error>,
 {
     type Output = Result<A::Item, A::Error>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
-        let this = self.project();
-        this.retry_if.poll(cx)
+        match self.poll_outcome(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(RetryOutcome::Done(result)) => Poll::Ready(result),
+            Poll::Ready(RetryOutcome::Exhausted(result)) => Poll::Ready(result),
+        }
     }
 }
 
-/// Future that drives multiple attempts at an action via a retry strategy. Retries are only attempted if
-/// the `Error` returned by the future satisfies a given condition.
-#[pin_project]
-pub struct RetryIf<I, A, C>
+impl<I, A, C, P, S, N> fmt::Debug for RetryIf<I, A, C, P, S, N>
 where
     I: Iterator<Item = Duration>,
     A: Action,
     C: Condition<A::Error>,
+    P: Condition<A::Item>,
+    S: Sleeper,
+    N: Notify<A::Error>,
 {
-    strategy: I,
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryIf")
+            .field("state", &format_args!("{}", self.driver.state_name()))
+            .field("attempts", &self.driver.attempts())
+            .finish()
+    }
+}
+
+/// Future that drives a retry, resolving with a detailed `Error` on failure.
+///
+/// See `RetryIf::detailed`.
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct Detailed<F> {
     #[pin]
-    state: RetryState<A>,
-    action: A,
-    condition: C,
+    inner: F,
 }
 
-impl<I, A, C> RetryIf<I, A, C>
+impl<I, A, C, P, S, N> Future for Detailed<RetryIf<I, A, C, P, S, N>>
 where
     I: Iterator<Item = Duration>,
     A: Action,
     C: Condition<A::Error>,
+    P: Condition<A::Item>,
+    S: Sleeper,
+    N: Notify<A::Error>,
 {
-    pub fn spawn<T: IntoIterator<IntoIter = I, Item = Duration>>(
-        strategy: T,
-        mut action: A,
-        condition: C,
-    ) -> RetryIf<I, A, C> {
-        RetryIf {
-            strategy: strategy.into_iter(),
-            state: RetryState::Running(action.run()),
+    type Output = Result<A::Item, Error<A::Error>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut inner = self.project().inner;
+        match inner.as_mut().poll_outcome(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(RetryOutcome::Done(result)) => {
+                Poll::Ready(result.map_err(Error::OperationError))
+            }
+            Poll::Ready(RetryOutcome::Exhausted(result)) => {
+                let attempts = inner.driver.attempts();
+                Poll::Ready(result.map_err(|err| Error::RetriesExhausted(err, attempts)))
+            }
+        }
+    }
+}
+
+/// Future that drives several retries concurrently and resolves with all of their results.
+///
+/// See `Retry::spawn_all`.
+#[must_use = "retry futures do nothing unless awaited"]
+pub struct RetryAll<F: Future> {
+    futures: Vec<Pin<Box<F>>>,
+    outputs: Vec<Option<F::Output>>,
+}
+
+impl<F: Future> RetryAll<F> {
+    fn new(futures: Vec<F>) -> RetryAll<F> {
+        let outputs = futures.iter().map(|_| None).collect();
+        RetryAll {
+            futures: futures.into_iter().map(Box::pin).collect(),
+            outputs: outputs,
+        }
+    }
+}
+
+// The futures are boxed and the outputs are never pinned.
+impl<F: Future> Unpin for RetryAll<F> {}
+
+impl<F: Future> Future for RetryAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut done = true;
+        for (future, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+            if output.is_none() {
+                match future.as_mut().poll(cx) {
+                    Poll::Pending => done = false,
+                    Poll::Ready(result) => *output = Some(result),
+                }
+            }
+        }
+        if done {
+            Poll::Ready(this.outputs.iter_mut().filter_map(Option::take).collect())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Future that runs hedged attempts of an action and resolves with the first success.
+///
+/// See `Retry::spawn_hedged`.
+#[must_use = "retry futures do nothing unless awaited"]
+pub struct Hedged<
+    A: Action,
+    #[cfg(feature = "tokio")] S: Sleeper = TokioSleeper,
+    #[cfg(not(feature = "tokio"))] S: Sleeper,
+> {
+    action: A,
+    in_flight: Vec<Pin<Box<A::Future>>>,
+    hedge_delay: Duration,
+    hedges: usize,
+    sleeper: S,
+    // created on first poll, and again after every hedge, so that the retry can be created
+    // outside of the runtime
+    timer: Option<Pin<Box<S::Sleep>>>,
+    last_error: Option<A::Error>,
+}
+
+impl<A: Action, S: Sleeper> Hedged<A, S> {
+    fn new(hedge_delay: Duration, max_hedges: usize, mut action: A, sleeper: S) -> Hedged<A, S> {
+        let future = action.run();
+        Hedged {
             action: action,
-            condition: condition,
+            in_flight: vec![Box::pin(future)],
+            hedge_delay: hedge_delay,
+            hedges: max_hedges,
+            sleeper: sleeper,
+            timer: None,
+            last_error: None,
+        }
+    }
+}
+
+// The attempts and the timer are boxed.
+impl<A: Action, S: Sleeper> Unpin for Hedged<A, S> {}
+
+impl<A: Action, S: Sleeper> Future for Hedged<A, S> {
+    type Output = Result<A::Item, A::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            let mut i = 0;
+            while i < this.in_flight.len() {
+                match this.in_flight[i].as_mut().poll(cx) {
+                    Poll::Pending => i += 1,
+                    Poll::Ready(Ok(item)) => {
+                        this.in_flight.clear();
+                        return Poll::Ready(Ok(item));
+                    }
+                    Poll::Ready(Err(err)) => {
+                        this.in_flight.swap_remove(i);
+                        this.last_error = Some(err);
+                    }
+                }
+            }
+            if this.hedges == 0 {
+                if this.in_flight.is_empty() {
+                    let err = this
+                        .last_error
+                        .take()
+                        .expect("`Hedged` polled after completion");
+                    return Poll::Ready(Err(err));
+                }
+                return Poll::Pending;
+            }
+            if !this.in_flight.is_empty() {
+                let sleeper = &mut this.sleeper;
+                let hedge_delay = this.hedge_delay;
+                let timer = this
+                    .timer
+                    .get_or_insert_with(|| Box::pin(sleeper.sleep(hedge_delay)));
+                if timer.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+            this.hedges -= 1;
+            this.in_flight.push(Box::pin(this.action.run()));
+            this.timer = None;
         }
     }
+}
+
+/// Future that retries an action and cleans up after every failed attempt that is retried.
+///
+/// See `Retry::spawn_with_cleanup`.
+#[cfg(feature = "tokio")]
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct WithCleanup<I, A, F, Fut>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    #[pin]
+    driver: RetryDriver<I, A>,
+    cleanup: F,
+    #[pin]
+    cleaning: Option<Fut>,
+    // the delay before the next attempt, once the cleanup is done
+    delay: Duration,
+}
 
-    fn attempt(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<A::Item, A::Error>> {
-        let future = {
+#[cfg(feature = "tokio")]
+impl<I, A, F, Fut> Future for WithCleanup<I, A, F, Fut>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    F: FnMut(&A::Error) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Output = Result<A::Item, A::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        loop {
             let mut this = self.as_mut().project();
-            this.action.run()
-        };
-        self.as_mut()
-            .project()
-            .state
-            .set(RetryState::Running(future));
-        self.poll(cx)
-    }
-
-    fn retry(
-        mut self: Pin<&mut Self>,
-        err: A::Error,
-        cx: &mut Context,
-    ) -> Result<Poll<Result<A::Item, A::Error>>, A::Error> {
-        match self.as_mut().project().strategy.next() {
-            None => Err(err),
-            Some(duration) => {
-                let deadline = Instant::now() + duration;
-                let future = sleep_until(deadline);
-                self.as_mut()
-                    .project()
-                    .state
-                    .set(RetryState::Sleeping(future));
-                Ok(self.poll(cx))
+            if let Some(cleaning) = this.cleaning.as_mut().as_pin_mut() {
+                match cleaning.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(()) => {
+                        this.cleaning.set(None);
+                        this.driver.retry_after(*this.delay);
+                        continue;
+                    }
+                }
+            }
+            let err = match this.driver.as_mut().poll_attempt(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(item)) => return Poll::Ready(Ok(item)),
+                Poll::Ready(Err(err)) => err,
+            };
+            match this.driver.next_delay() {
+                None => return Poll::Ready(Err(err)),
+                Some(delay) => {
+                    *this.delay = delay;
+                    let cleaning = (this.cleanup)(&err);
+                    this.cleaning.set(Some(cleaning));
+                }
             }
         }
     }
 }
 
-impl<I, A, C> Future for RetryIf<I, A, C>
+/// Future that retries an action after the delays chosen by a `DelayingCondition`.
+///
+/// See `RetryIf::spawn_delaying`.
+#[cfg(feature = "tokio")]
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct Delaying<I, A, C>
 where
     I: Iterator<Item = Duration>,
     A: Action,
-    C: Condition<A::Error>,
 {
+    #[pin]
+    driver: RetryDriver<I, A>,
+    condition: C,
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, C> Future for Delaying<I, A, C>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    C: DelayingCondition<A::Error>,
+{
+    type Output = Result<A::Item, A::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+            let err = match this.driver.as_mut().poll_attempt(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(item)) => return Poll::Ready(Ok(item)),
+                Poll::Ready(Err(err)) => err,
+            };
+            let delay = this
+                .driver
+                .as_mut()
+                .next_delay()
+                .and_then(|default| this.condition.next_delay(&err, default));
+            match delay {
+                Some(delay) => this.driver.retry_after(delay),
+                None => return Poll::Ready(Err(err)),
+            }
+        }
+    }
+}
+
+/// Future that retries an action after errors, and after items that ask for a delayed retry.
+///
+/// See `Retry::spawn_on_with_delay`.
+#[cfg(feature = "tokio")]
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct OkDelaying<I, A, F>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    #[pin]
+    driver: RetryDriver<I, A>,
+    predicate: F,
+}
+
+#[cfg(feature = "tokio")]
+impl<I, A, F> Future for OkDelaying<I, A, F>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    F: FnMut(&A::Item) -> Option<Duration>,
+{
+    type Output = Result<A::Item, A::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+            let result = match this.driver.as_mut().poll_attempt(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => result,
+            };
+            let requested = match result {
+                Ok(ref item) => match (this.predicate)(item) {
+                    Some(delay) => Some(delay),
+                    None => return Poll::Ready(result),
+                },
+                Err(_) => None,
+            };
+            match this.driver.as_mut().next_delay() {
+                Some(default) => this.driver.retry_after(requested.unwrap_or(default)),
+                None => return Poll::Ready(result),
+            }
+        }
+    }
+}
+
+/// Future that runs an action on the ticks of an interval until it succeeds.
+///
+/// See `Retry::spawn_interval`.
+#[cfg(feature = "tokio")]
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct Ticking<A: Action> {
+    action: A,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+    // created on first poll, so that the retry can be created outside of the runtime
+    interval: Option<Interval>,
+    ticks: usize,
+    #[pin]
+    running: Option<A::Future>,
+}
+
+#[cfg(feature = "tokio")]
+impl<A: Action> Ticking<A> {
+    /// Sets how ticks that were missed while an attempt was running are handled.
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Ticking<A> {
+        self.missed_tick_behavior = behavior;
+        self
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<A: Action> Future for Ticking<A> {
     type Output = Result<A::Item, A::Error>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
-        match self.as_mut().project().state.poll(cx) {
-            RetryFuturePoll::Running(poll_result) => match poll_result {
-                Poll::Ready(Ok(ok)) => Poll::Ready(Ok(ok)),
-                Poll::Pending => Poll::Pending,
-                Poll::Ready(Err(err)) => {
-                    if self.as_mut().project().condition.should_retry(&err) {
-                        match self.retry(err, cx) {
-                            Ok(poll) => poll,
-                            Err(err) => Poll::Ready(Err(err)),
+        loop {
+            let mut this = self.as_mut().project();
+            if let Some(running) = this.running.as_mut().as_pin_mut() {
+                match running.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(item)) => return Poll::Ready(Ok(item)),
+                    Poll::Ready(Err(err)) => {
+                        this.running.set(None);
+                        if *this.ticks == 0 {
+                            return Poll::Ready(Err(err));
                         }
-                    } else {
-                        Poll::Ready(Err(err))
                     }
                 }
-            },
-            RetryFuturePoll::Sleeping(poll_result) => match poll_result {
-                Poll::Pending => Poll::Pending,
-                Poll::Ready(_) => self.attempt(cx),
-            },
+            }
+            let period = *this.period;
+            let missed_tick_behavior = *this.missed_tick_behavior;
+            let interval = this.interval.get_or_insert_with(|| {
+                let mut interval = tokio::time::interval(period);
+                interval.set_missed_tick_behavior(missed_tick_behavior);
+                interval
+            });
+            match interval.poll_tick(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(_) => {
+                    *this.ticks -= 1;
+                    let future = this.action.run();
+                    this.running.set(Some(future));
+                }
+            }
+        }
+    }
+}
+
+/// Future that resolves with the error handed back by an owning condition.
+///
+/// See `RetryIf::spawn_owned`.
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct Flattened<F> {
+    #[pin]
+    inner: F,
+}
+
+impl<T, E, F> Future for Flattened<F>
+where
+    F: Future<Output = Result<T, Result<E, E>>>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match self.project().inner.poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(item)) => Poll::Ready(Ok(item)),
+            Poll::Ready(Err(Ok(err))) | Poll::Ready(Err(Err(err))) => Poll::Ready(Err(err)),
+        }
+    }
+}
+
+/// Future that resolves with the result of a retry, unless a signal resolves first.
+///
+/// See `Retry::spawn_until_signal`.
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct UntilSignal<F, G> {
+    #[pin]
+    inner: F,
+    #[pin]
+    signal: G,
+}
+
+impl<T, E, F, G> Future for UntilSignal<F, G>
+where
+    F: Future<Output = Result<T, Error<E>>>,
+    G: Future<Output = ()>,
+{
+    type Output = Result<T, Error<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Poll::Ready(()) = this.signal.poll(cx) {
+            return Poll::Ready(Err(Error::Aborted));
+        }
+        this.inner.poll(cx)
+    }
+}
+
+/// Future that resolves with the item of a retry along with the time it took.
+///
+/// See `RetryIf::timed`.
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct Timed<F> {
+    #[pin]
+    inner: F,
+    start: Instant,
+}
+
+impl<I, A, C, P, S, N> Future for Timed<Detailed<RetryIf<I, A, C, P, S, N>>>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+    C: Condition<A::Error>,
+    P: Condition<A::Item>,
+    S: Sleeper,
+    N: Notify<A::Error>,
+{
+    type Output = Result<(A::Item, Duration), Error<A::Error>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut inner = this.inner;
+        match inner.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                let now = inner.inner.driver.now();
+                let elapsed = now.saturating_duration_since(*this.start);
+                Poll::Ready(result.map(|item| (item, elapsed)))
+            }
         }
     }
 }
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn tells_why_strategy_was_exhausted() {
+    let deadline = RealClock.now() + Duration::from_secs(1);
+
+    assert_eq!(
+        exhausted_reason(None, None, 3, || RealClock.now()),
+        GiveupReason::StrategyExhausted
+    );
+    assert_eq!(
+        exhausted_reason(Some(2), None, 3, || RealClock.now()),
+        GiveupReason::MaxRetries
+    );
+    assert_eq!(
+        exhausted_reason(Some(5), Some(deadline), 3, || RealClock.now()),
+        GiveupReason::StrategyExhausted
+    );
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+
+    assert_eq!(
+        exhausted_reason(Some(5), Some(deadline), 3, || RealClock.now()),
+        GiveupReason::Deadline
+    );
+}
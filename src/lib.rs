@@ -36,13 +36,142 @@
 //! ```
 
 #![allow(warnings)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 mod action;
+#[cfg(feature = "std")]
+mod breaker;
+#[cfg(feature = "std")]
+mod budget;
+#[cfg(feature = "tokio")]
+mod builder;
+#[cfg(feature = "cancel")]
+mod cancel;
+#[cfg(feature = "std")]
 mod condition;
+#[cfg(feature = "std")]
+mod driver;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "tokio")]
+mod events;
+#[cfg(feature = "std")]
 mod future;
+/// Integration with `tower` services.
+#[cfg(feature = "tower")]
+pub mod middleware;
+#[cfg(feature = "std")]
+mod notify;
+#[cfg(feature = "std")]
+mod observer;
+#[cfg(feature = "std")]
+mod plan;
+pub mod prelude;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod sleeper;
 /// Assorted retry strategies including fixed interval and exponential back-off.
 pub mod strategy;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "tracing")]
+mod trace;
+
+#[cfg(feature = "std")]
+pub use action::{
+    Action, ActionWithAttempt, BoxedAction, CatchUnwind, CatchUnwindFuture, Fallbacks,
+    FallbacksFuture, MapErr, MapErrFuture, SyncAction, WithAttempt, WithSetup, WithSetupFuture,
+};
+#[cfg(feature = "tokio")]
+pub use action::{Limited, LimitedFuture};
+#[cfg(feature = "std")]
+pub use breaker::{BreakerAction, BreakerFuture, CircuitBreaker};
+#[cfg(feature = "std")]
+pub use budget::RetryBudget;
+#[cfg(feature = "tokio")]
+pub use builder::RetryBuilder;
+#[cfg(feature = "cancel")]
+pub use cancel::{CancellableAction, CancellableFuture, CancellableSleep, CancellableSleeper};
+#[cfg(feature = "std")]
+pub use condition::{
+    io_transient, Always, And, Condition, ConditionExt, ContextCondition, Decide,
+    DelayingCondition, IoTransient, Or, RetryDecision, RetryOn, RetryUnless, WithContext,
+};
+#[cfg(feature = "std")]
+pub use driver::RetryDriver;
+#[cfg(feature = "std")]
+pub use error::Error;
+#[cfg(feature = "tokio")]
+pub use events::{AttemptEvent, EventSender, RetryStatus, WatchSender};
+#[cfg(feature = "tokio")]
+pub use future::{retry, retry_notify, Delaying, OkDelaying, Ticking, WithCleanup};
+#[cfg(feature = "std")]
+pub use future::{Detailed, Flattened, Hedged, Retry, RetryAll, RetryIf, Timed, UntilSignal};
+#[cfg(feature = "std")]
+pub use notify::{GiveupReason, Notify};
+#[cfg(feature = "std")]
+pub use observer::{Observed, RetryObserver};
+#[cfg(feature = "std")]
+pub use plan::{Policy, RetryPlan, RetryPolicy};
+#[cfg(feature = "std")]
+pub use report::{
+    AttemptRecord, CollectErrors, ErrorHistory, ErrorHistoryFuture, Reported, ReportingAction,
+    ReportingFuture, RetryReport,
+};
+#[cfg(feature = "async-std")]
+pub use sleeper::AsyncStdSleeper;
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub use sleeper::GlooSleeper;
+#[cfg(feature = "std")]
+pub use sleeper::{Clock, RealClock, Sleeper, YieldingSleep, YieldingSleeper};
+#[cfg(feature = "tokio")]
+pub use sleeper::{HandleSleeper, TokioSleeper};
+#[cfg(feature = "stream")]
+pub use stream::{delay_stream, retry_stream_items, DelayStream, RetryItems, RetryStream};
+#[cfg(feature = "tracing")]
+pub use trace::Traced;
 
-pub use action::Action;
-pub use condition::Condition;
-pub use future::{Retry, RetryIf};
+/// Retries a fallible async block with a strategy, and awaits the result.
+///
+/// `retry!(strategy, { body })` expands to `Retry::spawn(strategy, || async move { body }).await`,
+/// and `retry!(strategy, if condition, { body })` to the same with `RetryIf::spawn`, so it must be
+/// used in an async context. The block is run once per attempt, hence it may only move values
+/// it can copy, such as references:
+///
+/// ```rust,no_run
+/// # use tokio_retry::retry;
+/// # use tokio_retry::strategy::FixedInterval;
+/// # async fn fetch(url: &str) -> Result<String, std::io::Error> { Ok(url.to_string()) }
+/// # async fn run() -> Result<(), std::io::Error> {
+/// let url = String::from("https://example.com");
+/// let url = &url;
+///
+/// let body = retry!(FixedInterval::from_millis(10).take(3), {
+///     fetch(url).await
+/// })?;
+///
+/// let body = retry!(
+///     FixedInterval::from_millis(10).take(3),
+///     if |e: &std::io::Error| e.kind() == std::io::ErrorKind::TimedOut,
+///     { fetch(url).await }
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Like any future, the retry is cancelled by dropping it, for instance when it loses a
+/// `select!`, also while sleeping between attempts.
+#[cfg(feature = "tokio")]
+#[macro_export]
+macro_rules! retry {
+    ($strategy:expr, if $condition:expr, $body:block) => {
+        $crate::RetryIf::spawn($strategy, || async move $body, $condition).await
+    };
+    ($strategy:expr, $body:block) => {
+        $crate::Retry::spawn($strategy, || async move $body).await
+    };
+}
@@ -0,0 +1,166 @@
+use std::iter::IntoIterator;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tower::util::{Oneshot, ServiceExt};
+use tower::{Layer, Service};
+
+use super::action::Action;
+use super::future::Retry;
+
+/// Layer that wraps services in a `RetryService`.
+///
+/// The strategy factory is called once per request, so that each request
+/// is retried according to a fresh strategy.
+#[derive(Debug, Clone)]
+pub struct RetryLayer<T> {
+    strategy: T,
+}
+
+impl<T> RetryLayer<T> {
+    pub fn new(strategy: T) -> RetryLayer<T> {
+        RetryLayer { strategy: strategy }
+    }
+}
+
+impl<T: Clone, S> Layer<S> for RetryLayer<T> {
+    type Service = RetryService<T, S>;
+
+    fn layer(&self, service: S) -> RetryService<T, S> {
+        RetryService::new(self.strategy.clone(), service)
+    }
+}
+
+/// Service that retries failed requests to the wrapped service.
+///
+/// Requests must be `Clone`, since every attempt needs its own request. For requests that cannot
+/// be cloned, see `RetryService::with_request_factory`.
+#[derive(Debug, Clone)]
+pub struct RetryService<T, S> {
+    strategy: T,
+    service: S,
+}
+
+impl<T, S> RetryService<T, S> {
+    pub fn new(strategy: T, service: S) -> RetryService<T, S> {
+        RetryService {
+            strategy: strategy,
+            service: service,
+        }
+    }
+}
+
+impl<T, S> RetryService<T, S> {
+    /// Constructs a service that is called with request factories rather than requests.
+    ///
+    /// The factory is called once per attempt, including the first, so that every attempt gets
+    /// a freshly built request and requests need not be `Clone`.
+    pub fn with_request_factory(strategy: T, service: S) -> FactoryRetryService<T, S> {
+        FactoryRetryService {
+            strategy: strategy,
+            service: service,
+        }
+    }
+}
+
+impl<T, I, S, R> Service<R> for RetryService<T, S>
+where
+    T: Fn() -> I,
+    I: IntoIterator<Item = Duration>,
+    S: Service<R> + Clone,
+    R: Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Retry<I::IntoIter, ServiceAction<S, R>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), S::Error>> {
+        // every attempt drives the readiness of its own clone of the service
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let action = ServiceAction {
+            service: self.service.clone(),
+            request: request,
+        };
+        Retry::spawn((self.strategy)(), action)
+    }
+}
+
+/// Action that calls a service with a clone of the request.
+#[derive(Debug, Clone)]
+pub struct ServiceAction<S, R> {
+    service: S,
+    request: R,
+}
+
+impl<S, R> Action for ServiceAction<S, R>
+where
+    S: Service<R> + Clone,
+    R: Clone,
+{
+    type Future = Oneshot<S, R>;
+    type Item = S::Response;
+    type Error = S::Error;
+
+    fn run(&mut self) -> Self::Future {
+        self.service.clone().oneshot(self.request.clone())
+    }
+}
+
+/// Service that retries failed requests to the wrapped service, building the request for every
+/// attempt with a factory.
+///
+/// See `RetryService::with_request_factory`.
+#[derive(Debug, Clone)]
+pub struct FactoryRetryService<T, S> {
+    strategy: T,
+    service: S,
+}
+
+impl<T, I, S, F, R> Service<F> for FactoryRetryService<T, S>
+where
+    T: Fn() -> I,
+    I: IntoIterator<Item = Duration>,
+    S: Service<R> + Clone,
+    F: FnMut() -> R,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Retry<I::IntoIter, FactoryServiceAction<S, F>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), S::Error>> {
+        // every attempt drives the readiness of its own clone of the service
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, factory: F) -> Self::Future {
+        let action = FactoryServiceAction {
+            service: self.service.clone(),
+            factory: factory,
+        };
+        Retry::spawn((self.strategy)(), action)
+    }
+}
+
+/// Action that calls a service with a request built by a factory.
+#[derive(Debug, Clone)]
+pub struct FactoryServiceAction<S, F> {
+    service: S,
+    factory: F,
+}
+
+impl<S, F, R> Action for FactoryServiceAction<S, F>
+where
+    S: Service<R> + Clone,
+    F: FnMut() -> R,
+{
+    type Future = Oneshot<S, R>;
+    type Item = S::Response;
+    type Error = S::Error;
+
+    fn run(&mut self) -> Self::Future {
+        self.service.clone().oneshot((self.factory)())
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+/// Gets notified about every retry, with the error that caused it and the delay before the next attempt.
+pub trait Notify<E> {
+    fn notify(&mut self, error: &E, duration: Duration);
+
+    /// Called before every attempt, with the number of the attempt starting at 1.
+    fn attempt(&mut self, _attempt: usize) {}
+
+    /// Called once the action has succeeded, with the total number of attempts it took.
+    fn success(&mut self, _attempts: usize) {}
+
+    /// Called once the retry resolves with an error, with the total number of attempts made
+    /// and the reason for giving up.
+    fn give_up(&mut self, _attempts: usize, _reason: GiveupReason) {}
+}
+
+/// The reason why a retry gave up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GiveupReason {
+    /// The strategy did not yield another delay.
+    ///
+    /// This is also reported for limits that are part of the strategy itself, such as
+    /// `Iterator::take` or `StrategyExt::deadline`.
+    StrategyExhausted,
+    /// The maximum number of retries was reached, see for instance `Retry::spawn_bounded`.
+    MaxRetries,
+    /// The deadline passed, see `Retry::spawn_with_deadline`.
+    Deadline,
+    /// The condition declined to retry the error.
+    ConditionFalse,
+    /// A retry budget ran out, or another condition decided `RetryDecision::StopExhausted`.
+    BudgetExhausted,
+}
+
+impl<E, F: FnMut(&E, Duration)> Notify<E> for F {
+    fn notify(&mut self, error: &E, duration: Duration) {
+        self(error, duration)
+    }
+}
+
+impl<E> Notify<E> for () {
+    fn notify(&mut self, _error: &E, _duration: Duration) {}
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use crate::notify::{GiveupReason, Notify};
+
+/// Observes the progress of a retry, for instance to feed a metrics system.
+///
+/// All methods have no-op default implementations, so that observers only need to implement the
+/// events they are interested in. See `Retry::spawn_observed`.
+pub trait RetryObserver<E> {
+    /// Called before every attempt, with the number of the attempt starting at 1.
+    fn on_attempt(&self, _attempt: usize) {}
+
+    /// Called before every retry, with the error that caused it and the delay before the next attempt.
+    fn on_retry(&self, _error: &E, _delay: Duration) {}
+
+    /// Called once the action has succeeded, with the total number of attempts it took.
+    fn on_success(&self, _attempts: usize) {}
+
+    /// Called once the retry resolves with an error, with the total number of attempts made
+    /// and the reason for giving up.
+    fn on_giveup(&self, _attempts: usize, _reason: GiveupReason) {}
+}
+
+impl<E> RetryObserver<E> for () {}
+
+impl<'a, E, O: RetryObserver<E> + ?Sized> RetryObserver<E> for &'a O {
+    fn on_attempt(&self, attempt: usize) {
+        (**self).on_attempt(attempt)
+    }
+
+    fn on_retry(&self, error: &E, delay: Duration) {
+        (**self).on_retry(error, delay)
+    }
+
+    fn on_success(&self, attempts: usize) {
+        (**self).on_success(attempts)
+    }
+
+    fn on_giveup(&self, attempts: usize, reason: GiveupReason) {
+        (**self).on_giveup(attempts, reason)
+    }
+}
+
+/// Adapts a `RetryObserver` to the `Notify` hook driven by the retry futures.
+#[derive(Debug, Clone)]
+pub struct Observed<O> {
+    observer: O,
+}
+
+impl<O> Observed<O> {
+    pub fn new(observer: O) -> Observed<O> {
+        Observed { observer: observer }
+    }
+}
+
+impl<E, O: RetryObserver<E>> Notify<E> for Observed<O> {
+    fn notify(&mut self, error: &E, duration: Duration) {
+        self.observer.on_retry(error, duration)
+    }
+
+    fn attempt(&mut self, attempt: usize) {
+        self.observer.on_attempt(attempt)
+    }
+
+    fn success(&mut self, attempts: usize) {
+        self.observer.on_success(attempts)
+    }
+
+    fn give_up(&mut self, attempts: usize, reason: GiveupReason) {
+        self.observer.on_giveup(attempts, reason)
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::iter::{IntoIterator, Iterator};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::action::Action;
+use crate::condition::Condition;
+#[cfg(feature = "tokio")]
+use crate::future::Retry;
+use crate::notify::Notify;
+use crate::strategy::StrategyExt;
+
+/// A reusable description of a retry, which can be run any number of times.
+///
+/// Every call to `run` starts a fresh `Retry` future from a clone of the strategy and a new action
+/// created by the factory, so each run starts the strategy from the beginning.
+///
+/// ```rust
+/// # use tokio_retry::RetryPlan;
+/// # use tokio_retry::strategy::ExponentialBackoff;
+/// # async fn action() -> Result<(), ()> { Ok(()) }
+/// # async fn example() -> Result<(), ()> {
+/// let plan = RetryPlan::new(ExponentialBackoff::from_millis(10).take(3), || action);
+///
+/// plan.run().await?;
+/// plan.run().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPlan<S, F> {
+    strategy: S,
+    factory: F,
+}
+
+impl<S, F, A> RetryPlan<S, F>
+where
+    S: IntoIterator<Item = Duration> + Clone,
+    F: Fn() -> A,
+    A: Action,
+{
+    pub fn new(strategy: S, factory: F) -> RetryPlan<S, F> {
+        RetryPlan {
+            strategy: strategy,
+            factory: factory,
+        }
+    }
+
+    /// Starts a new retry of this plan.
+    #[cfg(feature = "tokio")]
+    pub fn run(&self) -> Retry<S::IntoIter, A> {
+        Retry::spawn(self.strategy.clone(), (self.factory)())
+    }
+}
+
+/// A retry policy that hands out a fresh strategy for every retry.
+///
+/// Unlike a strategy, which is consumed by a retry, a policy can be implemented once and
+/// shared, for instance behind an `Arc`, to create any number of retries (see
+/// `Retry::spawn_policy`).
+pub trait RetryPolicy {
+    /// The strategy that this policy creates.
+    type Iter: Iterator<Item = Duration>;
+
+    /// Creates a strategy for a new retry, starting from the beginning of the schedule.
+    fn iter(&self) -> Self::Iter;
+}
+
+impl<P: RetryPolicy + ?Sized> RetryPolicy for Arc<P> {
+    type Iter = P::Iter;
+
+    fn iter(&self) -> Self::Iter {
+        (**self).iter()
+    }
+}
+
+/// A complete retry configuration for errors of type `E`, bundling the strategy, the condition
+/// under which to retry, an optional notification and optional caps.
+///
+/// A policy can be shared by cloning it, and every retry started from it with
+/// `Retry::spawn_with_policy` gets a fresh strategy from the factory.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tokio_retry::{Policy, Retry};
+/// # use tokio_retry::strategy::ExponentialBackoff;
+/// # async fn action() -> Result<u64, std::io::Error> { Ok(42) }
+/// # async fn run() -> Result<u64, std::io::Error> {
+/// let policy = Policy::new(|| ExponentialBackoff::from_millis(10))
+///     .retry_if(|err: &std::io::Error| err.kind() == std::io::ErrorKind::TimedOut)
+///     .max_delay(Duration::from_secs(1))
+///     .max_retries(5);
+///
+/// let result = Retry::spawn_with_policy(&policy, action).await?;
+/// # Ok(result)
+/// # }
+/// ```
+pub struct Policy<E> {
+    strategy: Arc<dyn Fn() -> Box<dyn Iterator<Item = Duration> + Send + Sync> + Send + Sync>,
+    condition: Arc<dyn Fn(&E) -> bool + Send + Sync>,
+    notify: Option<Arc<dyn Fn(&E, Duration) + Send + Sync>>,
+    max_retries: Option<usize>,
+    max_delay: Option<Duration>,
+}
+
+impl<E> Policy<E> {
+    /// Constructs a policy that retries every error, with a strategy created by `strategy`.
+    pub fn new<F, T>(strategy: F) -> Policy<E>
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: IntoIterator<Item = Duration>,
+        T::IntoIter: Send + Sync + 'static,
+    {
+        Policy {
+            strategy: Arc::new(move || Box::new(strategy().into_iter())),
+            condition: Arc::new(|_| true),
+            notify: None,
+            max_retries: None,
+            max_delay: None,
+        }
+    }
+
+    /// Only retries errors that satisfy the condition.
+    pub fn retry_if<F>(mut self, condition: F) -> Policy<E>
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        self.condition = Arc::new(condition);
+        self
+    }
+
+    /// Gets notified with the error and the upcoming delay before every retry.
+    pub fn notify<F>(mut self, notify: F) -> Policy<E>
+    where
+        F: Fn(&E, Duration) + Send + Sync + 'static,
+    {
+        self.notify = Some(Arc::new(notify));
+        self
+    }
+
+    /// Limits the number of retries, regardless of the strategy.
+    pub fn max_retries(mut self, max_retries: usize) -> Policy<E> {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Caps every delay of the strategy. No retry delay will be longer than this `Duration`.
+    pub fn max_delay(mut self, max_delay: Duration) -> Policy<E> {
+        self.max_delay = Some(max_delay);
+        self
+    }
+
+    /// The maximum number of retries, if limited.
+    pub(crate) fn retry_limit(&self) -> Option<usize> {
+        self.max_retries
+    }
+
+    /// Creates a strategy for a new retry, with the caps of this policy applied.
+    pub(crate) fn strategy(&self) -> Box<dyn Iterator<Item = Duration> + Send + Sync> {
+        let strategy = (self.strategy)()
+            .max_delay(self.max_delay.unwrap_or(Duration::MAX))
+            .take(self.max_retries.unwrap_or(usize::MAX));
+        Box::new(strategy)
+    }
+}
+
+impl<E> Clone for Policy<E> {
+    fn clone(&self) -> Policy<E> {
+        Policy {
+            strategy: self.strategy.clone(),
+            condition: self.condition.clone(),
+            notify: self.notify.clone(),
+            max_retries: self.max_retries,
+            max_delay: self.max_delay,
+        }
+    }
+}
+
+impl<E> fmt::Debug for Policy<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Policy")
+            .field("max_retries", &self.max_retries)
+            .field("max_delay", &self.max_delay)
+            .finish()
+    }
+}
+
+impl<E> Condition<E> for Policy<E> {
+    fn should_retry(&mut self, error: &E) -> bool {
+        (self.condition)(error)
+    }
+}
+
+impl<E> Notify<E> for Policy<E> {
+    fn notify(&mut self, error: &E, duration: Duration) {
+        if let Some(ref notify) = self.notify {
+            notify(error, duration)
+        }
+    }
+}
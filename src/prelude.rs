@@ -0,0 +1,33 @@
+//! Re-exports the types and traits needed for most retries, for use as a glob import.
+//!
+//! ```rust,no_run
+//! # extern crate tokio;
+//! # extern crate tokio_retry;
+//! #
+//! use std::time::Duration;
+//! use tokio_retry::prelude::*;
+//!
+//! async fn action() -> Result<u64, ()> {
+//!     Err(())
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), ()> {
+//! let retry_strategy = ExponentialBackoff::from_millis(10)
+//!     .deadline(Duration::from_secs(5))
+//!     .map(jitter)
+//!     .take(3);
+//!
+//! let result = Retry::spawn(retry_strategy, action).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "jitter")]
+pub use crate::strategy::{jitter, SeededJitter};
+pub use crate::strategy::{
+    CustomStrategy, ExplicitSchedule, ExponentialBackoff, FibonacciBackoff, FixedInterval,
+    LinearBackoff, StrategyExt,
+};
+#[cfg(feature = "std")]
+pub use crate::{Action, Condition, Error, Retry, RetryIf};
@@ -0,0 +1,269 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use pin_project::pin_project;
+
+use crate::action::Action;
+use crate::error::Error;
+use crate::sleeper::{Clock, RealClock};
+
+/// Timing and outcome of a single attempt of a retry.
+///
+/// See `Retry::spawn_report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttemptRecord {
+    /// The number of the attempt, starting at 1.
+    pub attempt: usize,
+    /// The time between the end of the previous attempt and the start of this one, that is the
+    /// delay slept before this attempt. Zero for the first attempt.
+    pub slept_before: Duration,
+    /// The time the action took, from running it until its future resolved.
+    pub action_took: Duration,
+    /// The outcome of the attempt, with the error formatted using `Display`.
+    pub outcome: Result<(), String>,
+}
+
+/// Breakdown of all attempts of a retry.
+///
+/// See `Retry::spawn_report`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetryReport {
+    /// The attempts in the order they were made.
+    pub attempts: Vec<AttemptRecord>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ReportState {
+    report: RetryReport,
+    last_end: Option<Instant>,
+}
+
+/// Action that records an `AttemptRecord` for every attempt of the wrapped action.
+pub struct ReportingAction<A> {
+    action: A,
+    state: Arc<Mutex<ReportState>>,
+}
+
+impl<A: Action> ReportingAction<A>
+where
+    A::Error: Display,
+{
+    pub(crate) fn new(action: A) -> ReportingAction<A> {
+        ReportingAction {
+            action: action,
+            state: Arc::new(Mutex::new(ReportState::default())),
+        }
+    }
+
+    pub(crate) fn state(&self) -> Arc<Mutex<ReportState>> {
+        self.state.clone()
+    }
+}
+
+impl<A: Action> Action for ReportingAction<A>
+where
+    A::Error: Display,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+    type Future = ReportingFuture<A::Future>;
+
+    fn run(&mut self) -> Self::Future {
+        let start = RealClock.now();
+        let slept_before = match self.state.lock().unwrap().last_end {
+            Some(last_end) => start.saturating_duration_since(last_end),
+            None => Duration::ZERO,
+        };
+        ReportingFuture {
+            future: self.action.run(),
+            state: self.state.clone(),
+            start: start,
+            slept_before: slept_before,
+        }
+    }
+}
+
+/// Future produced by the `ReportingAction` action.
+#[pin_project]
+pub struct ReportingFuture<T> {
+    #[pin]
+    future: T,
+    state: Arc<Mutex<ReportState>>,
+    start: Instant,
+    slept_before: Duration,
+}
+
+impl<R, E, T> Future for ReportingFuture<T>
+where
+    T: Future<Output = Result<R, E>>,
+    E: Display,
+{
+    type Output = Result<R, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = match this.future.poll(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(result) => result,
+        };
+        let end = RealClock.now();
+        let mut state = this.state.lock().unwrap();
+        let attempt = state.report.attempts.len() + 1;
+        state.report.attempts.push(AttemptRecord {
+            attempt: attempt,
+            slept_before: *this.slept_before,
+            action_took: end.saturating_duration_since(*this.start),
+            outcome: match result {
+                Ok(_) => Ok(()),
+                Err(ref err) => Err(err.to_string()),
+            },
+        });
+        state.last_end = Some(end);
+        Poll::Ready(result)
+    }
+}
+
+/// Future that resolves with the result of a retry along with a `RetryReport` of its attempts.
+///
+/// See `Retry::spawn_report`.
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct Reported<F> {
+    #[pin]
+    inner: F,
+    state: Arc<Mutex<ReportState>>,
+}
+
+impl<F> Reported<F> {
+    pub(crate) fn new(inner: F, state: Arc<Mutex<ReportState>>) -> Reported<F> {
+        Reported {
+            inner: inner,
+            state: state,
+        }
+    }
+}
+
+impl<F: Future> Future for Reported<F> {
+    type Output = (F::Output, RetryReport);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.inner.poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                let report = mem::take(&mut this.state.lock().unwrap().report);
+                Poll::Ready((result, report))
+            }
+        }
+    }
+}
+
+/// Action that keeps a clone of the error of every attempt of the wrapped action.
+pub struct ErrorHistory<A: Action> {
+    action: A,
+    errors: Arc<Mutex<Vec<A::Error>>>,
+}
+
+impl<A: Action> ErrorHistory<A>
+where
+    A::Error: Clone,
+{
+    pub(crate) fn new(action: A) -> ErrorHistory<A> {
+        ErrorHistory {
+            action: action,
+            errors: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub(crate) fn errors(&self) -> Arc<Mutex<Vec<A::Error>>> {
+        self.errors.clone()
+    }
+}
+
+impl<A: Action> Action for ErrorHistory<A>
+where
+    A::Error: Clone,
+{
+    type Item = A::Item;
+    type Error = A::Error;
+    type Future = ErrorHistoryFuture<A::Future, A::Error>;
+
+    fn run(&mut self) -> Self::Future {
+        ErrorHistoryFuture {
+            future: self.action.run(),
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+/// Future produced by the `ErrorHistory` action.
+#[pin_project]
+pub struct ErrorHistoryFuture<T, E> {
+    #[pin]
+    future: T,
+    errors: Arc<Mutex<Vec<E>>>,
+}
+
+impl<R, E, T> Future for ErrorHistoryFuture<T, E>
+where
+    T: Future<Output = Result<R, E>>,
+    E: Clone,
+{
+    type Output = Result<R, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = match this.future.poll(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(result) => result,
+        };
+        if let Err(ref err) = result {
+            this.errors.lock().unwrap().push(err.clone());
+        }
+        Poll::Ready(result)
+    }
+}
+
+/// Future that resolves with the item of a retry, or with the errors of all of its attempts.
+///
+/// See `Retry::spawn_collect_errors`.
+#[must_use = "retry futures do nothing unless awaited"]
+#[pin_project]
+pub struct CollectErrors<F, E> {
+    #[pin]
+    inner: F,
+    errors: Arc<Mutex<Vec<E>>>,
+}
+
+impl<F, E> CollectErrors<F, E> {
+    pub(crate) fn new(inner: F, errors: Arc<Mutex<Vec<E>>>) -> CollectErrors<F, E> {
+        CollectErrors {
+            inner: inner,
+            errors: errors,
+        }
+    }
+}
+
+impl<T, E, F> Future for CollectErrors<F, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    type Output = Result<T, Error<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.inner.poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(item)) => Poll::Ready(Ok(item)),
+            Poll::Ready(Err(_)) => {
+                let errors = mem::take(&mut *this.errors.lock().unwrap());
+                Poll::Ready(Err(Error::AllFailed(errors)))
+            }
+        }
+    }
+}
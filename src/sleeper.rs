@@ -0,0 +1,205 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use pin_project::pin_project;
+#[cfg(feature = "tokio")]
+use tokio::runtime::Handle;
+#[cfg(feature = "tokio")]
+use tokio::time::{sleep_until, Sleep};
+
+/// A sleeper provides the delays between attempts of a retry.
+///
+/// The default sleeper is `TokioSleeper`, with the `tokio` feature. Custom sleepers can be used
+/// to run retries on other timers, or to record and skip delays in tests.
+///
+/// The sleeper is also the clock by which a retry measures time, such as the spacing between
+/// attempts, so that a sleeper which advances virtual time instead of sleeping can drive a retry
+/// deterministically, for instance in a simulation.
+pub trait Sleeper {
+    /// The future that this sleeper produces.
+    type Sleep: Future<Output = ()>;
+
+    fn sleep(&mut self, duration: Duration) -> Self::Sleep;
+
+    /// Returns the current time of the timer that this sleeper sleeps on.
+    ///
+    /// Defaults to the time of `RealClock`. A retry only asks for the time when it needs it,
+    /// for `min_attempt_spacing` or a deadline, so that sleepers on platforms without a clock
+    /// work otherwise.
+    fn now(&self) -> Instant {
+        RealClock.now()
+    }
+}
+
+/// A source of the current time, which strategies and the circuit breaker measure time with.
+///
+/// The default clock is `RealClock`. A custom clock, set with `with_clock` on `Deadline`,
+/// `SystemDeadline`, `LimitedBy`, `ResetAfter` or `CircuitBreaker`, lets them run on virtual
+/// time, typically the same as that of a custom `Sleeper`. The run loop itself sleeps and
+/// measures time with its sleeper, so a type that implements both traits drives a retry entirely
+/// on virtual time when passed to `Retry::spawn_with_sleeper`.
+///
+/// The constructors that sleep on the `tokio` timer, such as `Retry::spawn_with_watch` or
+/// `Retry::spawn_report`, also take their timestamps from it.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+}
+
+/// The system clock, which follows the paused clock of the `tokio` timer in tests when the
+/// `tokio` feature is enabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    #[cfg(feature = "tokio")]
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Sleeper backed by the `tokio` timer.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+#[cfg(feature = "tokio")]
+impl Sleeper for TokioSleeper {
+    type Sleep = Sleep;
+
+    fn sleep(&mut self, duration: Duration) -> Sleep {
+        sleep_until(tokio::time::Instant::now() + duration)
+    }
+}
+
+/// Sleeper backed by the timer of a specific `tokio` runtime.
+///
+/// Unlike `TokioSleeper`, this does not depend on the runtime context of the thread polling
+/// the retry, so the retry can be driven from outside of the runtime.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone)]
+pub struct HandleSleeper {
+    handle: Handle,
+}
+
+#[cfg(feature = "tokio")]
+impl HandleSleeper {
+    pub fn new(handle: Handle) -> HandleSleeper {
+        HandleSleeper { handle: handle }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Sleeper for HandleSleeper {
+    type Sleep = Sleep;
+
+    fn sleep(&mut self, duration: Duration) -> Sleep {
+        let _guard = self.handle.enter();
+        sleep_until(tokio::time::Instant::now() + duration)
+    }
+}
+
+/// Sleeper that yields to the scheduler once after each delay of the wrapped sleeper,
+/// before the next attempt is started.
+///
+/// When many retries wake up at the same time, for instance after a shared dependency
+/// recovered, this spreads their next attempts across scheduler ticks instead of starting
+/// them all at once.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YieldingSleeper<
+    #[cfg(feature = "tokio")] S = TokioSleeper,
+    #[cfg(not(feature = "tokio"))] S,
+> {
+    inner: S,
+}
+
+impl<S: Sleeper> YieldingSleeper<S> {
+    pub fn new(inner: S) -> YieldingSleeper<S> {
+        YieldingSleeper { inner: inner }
+    }
+}
+
+impl<S: Sleeper> Sleeper for YieldingSleeper<S> {
+    type Sleep = YieldingSleep<S::Sleep>;
+
+    fn sleep(&mut self, duration: Duration) -> Self::Sleep {
+        YieldingSleep {
+            sleep: self.inner.sleep(duration),
+            slept: false,
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.inner.now()
+    }
+}
+
+/// Future produced by `YieldingSleeper`.
+#[pin_project]
+pub struct YieldingSleep<F> {
+    #[pin]
+    sleep: F,
+    slept: bool,
+}
+
+impl<F: Future<Output = ()>> Future for YieldingSleep<F> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let this = self.project();
+        if *this.slept {
+            return Poll::Ready(());
+        }
+        match this.sleep.poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(()) => {
+                *this.slept = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Sleeper backed by the `async-std` timer, to run retries on the `async-std` runtime.
+#[cfg(feature = "async-std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdSleeper;
+
+#[cfg(feature = "async-std")]
+impl Sleeper for AsyncStdSleeper {
+    type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn sleep(&mut self, duration: Duration) -> Self::Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// Sleeper backed by `gloo-timers`, for use in the browser.
+///
+/// Durations are rounded down to milliseconds and saturate at `u32::MAX` milliseconds. The
+/// browser has no `tokio` timer, so build with `default-features = false` and the `wasm`
+/// feature, which leaves out `TokioSleeper` and the constructors that sleep on it.
+///
+/// There is no clock in the browser either, so neither `min_attempt_spacing` nor a deadline can
+/// be used with this sleeper.
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlooSleeper;
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+impl Sleeper for GlooSleeper {
+    type Sleep = gloo_timers::future::TimeoutFuture;
+
+    fn sleep(&mut self, duration: Duration) -> Self::Sleep {
+        let millis = std::cmp::min(duration.as_millis(), u32::MAX as u128) as u32;
+        gloo_timers::future::TimeoutFuture::new(millis)
+    }
+}
@@ -0,0 +1,165 @@
+use std::cmp;
+use std::fmt;
+use std::iter::Iterator;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::saturating::saturating_scale;
+use crate::notify::{GiveupReason, Notify};
+
+/// A retry strategy whose delay grows with every failure and decays with every success.
+///
+/// Unlike the other strategies, the delay does not depend on the number of attempts of a single
+/// retry, but on the outcomes reported with `on_failure` and `on_success`: each failure multiplies
+/// the delay by the factor, up to the maximum, and each success divides it by the factor, down to
+/// the base. A recovering service thus gets progressively faster retries.
+///
+/// Clones of a backoff share the same delay, so that one backoff can learn from many retries.
+/// Used with `Retry::spawn_adaptive`, the outcomes of attempts are reported automatically.
+#[derive(Debug, Clone)]
+pub struct AdaptiveBackoff {
+    current: Arc<Mutex<Duration>>,
+    base: Duration,
+    max: Duration,
+    factor: f64,
+    // the number of delays left to yield by this handle, if limited
+    remaining: Option<usize>,
+}
+
+impl AdaptiveBackoff {
+    /// Creates a backoff that starts at `base` and doubles on every failure, up to `max`.
+    pub fn new(base: Duration, max: Duration) -> AdaptiveBackoff {
+        AdaptiveBackoff {
+            current: Arc::new(Mutex::new(base)),
+            base: base,
+            max: cmp::max(base, max),
+            factor: 2.0,
+            remaining: None,
+        }
+    }
+
+    /// Sets the factor by which the delay grows on failure and decays on success.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not finite or smaller than 1.
+    #[must_use]
+    pub fn factor(mut self, factor: f64) -> AdaptiveBackoff {
+        assert!(
+            factor.is_finite() && factor >= 1.0,
+            "the factor must be finite and at least 1, got {}",
+            factor
+        );
+        self.factor = factor;
+        self
+    }
+
+    /// Limits this handle to yield at most `n` delays, which is not shared with its clones.
+    #[must_use]
+    pub fn max_retries(mut self, n: usize) -> AdaptiveBackoff {
+        self.remaining = Some(n);
+        self
+    }
+
+    /// The delay that the next retry will sleep for.
+    pub fn current_delay(&self) -> Duration {
+        *self.current.lock().unwrap()
+    }
+
+    /// Grows the delay by the factor, up to the maximum.
+    pub fn on_failure(&self) {
+        let mut current = self.current.lock().unwrap();
+        *current = cmp::min(saturating_scale(*current, self.factor), self.max);
+    }
+
+    /// Decays the delay by the factor, down to the base.
+    pub fn on_success(&self) {
+        let mut current = self.current.lock().unwrap();
+        *current = cmp::max(saturating_scale(*current, 1.0 / self.factor), self.base);
+    }
+}
+
+impl Iterator for AdaptiveBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if let Some(ref mut remaining) = self.remaining {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+        Some(self.current_delay())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+/// Describes the backoff by its current delay, for instance `adaptive(current=200ms, max=10s)`.
+impl fmt::Display for AdaptiveBackoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "adaptive(current={:?}, max={:?})",
+            self.current_delay(),
+            self.max
+        )
+    }
+}
+
+/// Reports every failed attempt as a failure, whether it is retried or the retry gives up after
+/// it, and the eventual success as a success.
+impl<E> Notify<E> for AdaptiveBackoff {
+    fn notify(&mut self, _error: &E, _duration: Duration) {
+        self.on_failure()
+    }
+
+    fn success(&mut self, _attempts: usize) {
+        self.on_success()
+    }
+
+    fn give_up(&mut self, _attempts: usize, _reason: GiveupReason) {
+        self.on_failure()
+    }
+}
+
+#[test]
+fn grows_on_failure_and_decays_on_success() {
+    let backoff = AdaptiveBackoff::new(Duration::from_millis(100), Duration::from_millis(500));
+
+    backoff.on_failure();
+    assert_eq!(backoff.current_delay(), Duration::from_millis(200));
+    backoff.on_failure();
+    backoff.on_failure();
+    assert_eq!(backoff.current_delay(), Duration::from_millis(500));
+
+    backoff.on_success();
+    assert_eq!(backoff.current_delay(), Duration::from_millis(250));
+    backoff.on_success();
+    backoff.on_success();
+    assert_eq!(backoff.current_delay(), Duration::from_millis(100));
+}
+
+#[test]
+fn clones_share_the_delay() {
+    let backoff = AdaptiveBackoff::new(Duration::from_millis(100), Duration::from_secs(1));
+    let mut clone = backoff.clone().max_retries(1);
+
+    backoff.on_failure();
+    assert_eq!(clone.next(), Some(Duration::from_millis(200)));
+    assert_eq!(clone.next(), None);
+    assert_eq!(backoff.clone().take(2).count(), 2);
+}
+
+#[test]
+fn grows_when_giving_up() {
+    let mut backoff = AdaptiveBackoff::new(Duration::from_millis(100), Duration::from_secs(1));
+
+    Notify::<()>::give_up(&mut backoff, 3, GiveupReason::StrategyExhausted);
+    assert_eq!(backoff.current_delay(), Duration::from_millis(200));
+}
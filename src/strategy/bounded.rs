@@ -0,0 +1,97 @@
+use core::cmp;
+use core::iter::Iterator;
+use core::time::Duration;
+
+/// A retry strategy wrapper that ends after a number of retries or once the sum of all delays
+/// would exceed a budget, whichever comes first.
+///
+/// See `StrategyExt::bounded`.
+#[derive(Debug, Clone)]
+pub struct Bounded<I> {
+    inner: I,
+    retries: usize,
+    remaining: Option<Duration>,
+}
+
+impl<I> Bounded<I> {
+    pub(crate) fn new(inner: I, max_retries: usize, max_total_delay: Duration) -> Bounded<I> {
+        Bounded {
+            inner: inner,
+            retries: max_retries,
+            remaining: Some(max_total_delay),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for Bounded<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if self.retries == 0 {
+            return None;
+        }
+        let remaining = self.remaining?;
+        let duration = self.inner.next()?;
+        self.retries -= 1;
+        self.remaining = remaining.checked_sub(duration);
+        self.remaining.map(|_| duration)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(_) => {
+                let upper = match self.inner.size_hint().1 {
+                    Some(upper) => cmp::min(upper, self.retries),
+                    None => self.retries,
+                };
+                (0, Some(upper))
+            }
+            None => (0, Some(0)),
+        }
+    }
+}
+
+#[test]
+fn stops_after_max_retries() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).bounded(2, Duration::from_secs(10));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn stops_before_exceeding_max_total_delay() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).bounded(10, Duration::from_millis(250));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), None);
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn stops_when_both_limits_are_reached_together() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).bounded(3, Duration::from_millis(300));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn zero_retries_returns_none_immediately() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).bounded(0, Duration::from_secs(10));
+
+    assert_eq!(s.size_hint(), (0, Some(0)));
+    assert_eq!(s.next(), None);
+}
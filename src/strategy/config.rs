@@ -0,0 +1,176 @@
+use alloc::boxed::Box;
+use core::iter::Iterator;
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ExponentialBackoff, FibonacciBackoff, FixedInterval};
+
+/// Configuration of a retry strategy, for instance loaded from a config file.
+///
+/// The kind of strategy is given by the `kind` field, next to its parameters:
+///
+/// ```rust
+/// # use tokio_retry::strategy::RetryConfig;
+/// let config: RetryConfig = serde_json::from_str(
+///     r#"{ "kind": "exponential", "base_ms": 10, "factor": 1000, "max_retries": 5 }"#,
+/// ).unwrap();
+///
+/// assert_eq!(config.strategy().count(), 5);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    #[serde(flatten)]
+    pub kind: StrategyConfig,
+    /// Limits the number of retries, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<usize>,
+}
+
+/// The strategies that can be configured through `RetryConfig`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum StrategyConfig {
+    Exponential(ExponentialBackoff),
+    Fibonacci(FibonacciBackoff),
+    Fixed(FixedInterval),
+}
+
+impl RetryConfig {
+    /// Builds the configured strategy.
+    pub fn strategy(&self) -> Box<dyn Iterator<Item = Duration> + Send + Sync> {
+        let strategy: Box<dyn Iterator<Item = Duration> + Send + Sync> = match self.kind {
+            StrategyConfig::Exponential(ref strategy) => Box::new(strategy.clone()),
+            StrategyConfig::Fibonacci(ref strategy) => Box::new(strategy.clone()),
+            StrategyConfig::Fixed(ref strategy) => Box::new(strategy.clone()),
+        };
+        match self.max_retries {
+            Some(max_retries) => Box::new(strategy.take(max_retries)),
+            None => strategy,
+        }
+    }
+}
+
+pub(super) fn default_factor() -> u64 {
+    1
+}
+
+#[test]
+fn round_trips_exponential_backoff() {
+    let strategy = ExponentialBackoff::from_millis(10)
+        .factor(1000)
+        .max_delay(Duration::from_secs(5))
+        .max_retries(4);
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: ExponentialBackoff = serde_json::from_str(&json).unwrap();
+
+    let mut strategy = strategy;
+    for _ in 0..5 {
+        assert_eq!(parsed.next(), strategy.next());
+    }
+    assert_eq!(parsed.next(), None);
+}
+
+#[test]
+fn round_trips_ceiling_of_exponential_backoff() {
+    let strategy = ExponentialBackoff::from_millis(10).with_max(Duration::from_micros(150_500));
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: ExponentialBackoff = serde_json::from_str(&json).unwrap();
+
+    let mut strategy = strategy;
+    for _ in 0..4 {
+        assert_eq!(parsed.next(), strategy.next());
+    }
+    assert_eq!(parsed.current(), Duration::from_micros(150_500));
+}
+
+#[test]
+fn serializes_parameters_instead_of_progress() {
+    let mut strategy = FibonacciBackoff::from_millis(10).factor(2);
+    strategy.next();
+    strategy.next();
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: FibonacciBackoff = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(json, r#"{"base_ms":10,"factor":2}"#);
+    assert_eq!(parsed.next(), Some(Duration::from_millis(20)));
+}
+
+#[test]
+fn round_trips_fixed_interval() {
+    let json = serde_json::to_string(&FixedInterval::from_millis(250)).unwrap();
+    let mut parsed: FixedInterval = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(json, r#"{"interval_ms":250}"#);
+    assert_eq!(parsed.next(), Some(Duration::from_millis(250)));
+}
+
+#[test]
+fn round_trips_retry_config() {
+    let config: RetryConfig =
+        serde_json::from_str(r#"{ "kind": "fibonacci", "base_ms": 10, "max_retries": 3 }"#)
+            .unwrap();
+    let json = serde_json::to_string(&config).unwrap();
+    let parsed: RetryConfig = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"kind":"fibonacci","base_ms":10,"factor":1,"max_retries":3}"#
+    );
+    assert_eq!(
+        parsed.strategy().collect::<alloc::vec::Vec<_>>(),
+        [10, 10, 20]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect::<alloc::vec::Vec<_>>()
+    );
+}
+
+#[test]
+fn round_trips_sub_millisecond_strategies() {
+    let mut strategy = ExponentialBackoff::from_initial_delay(Duration::from_micros(250));
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: ExponentialBackoff = serde_json::from_str(&json).unwrap();
+    for _ in 0..3 {
+        assert_eq!(parsed.next(), strategy.next());
+    }
+
+    let mut strategy = ExponentialBackoff::from_duration(Duration::from_micros(10_500));
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: ExponentialBackoff = serde_json::from_str(&json).unwrap();
+    for _ in 0..3 {
+        assert_eq!(parsed.next(), strategy.next());
+    }
+
+    let mut strategy = FibonacciBackoff::from_micros(300);
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: FibonacciBackoff = serde_json::from_str(&json).unwrap();
+    for _ in 0..3 {
+        assert_eq!(parsed.next(), strategy.next());
+    }
+
+    let json = serde_json::to_string(&FixedInterval::from_micros(500)).unwrap();
+    let mut parsed: FixedInterval = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.next(), Some(Duration::from_micros(500)));
+}
+
+#[test]
+fn round_trips_sub_millisecond_delay_bounds() {
+    let mut strategy = ExponentialBackoff::from_micros(100)
+        .first_delay(Duration::from_micros(50))
+        .max_delay(Duration::from_micros(500));
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: ExponentialBackoff = serde_json::from_str(&json).unwrap();
+    for _ in 0..3 {
+        assert_eq!(parsed.next(), strategy.next());
+    }
+    assert_eq!(parsed.next(), Some(Duration::from_micros(500)));
+
+    let mut strategy = FibonacciBackoff::from_micros(300).max_delay(Duration::from_micros(500));
+    let json = serde_json::to_string(&strategy).unwrap();
+    let mut parsed: FibonacciBackoff = serde_json::from_str(&json).unwrap();
+    for _ in 0..3 {
+        assert_eq!(parsed.next(), strategy.next());
+    }
+    assert_eq!(parsed.next(), Some(Duration::from_micros(500)));
+}
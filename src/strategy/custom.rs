@@ -0,0 +1,58 @@
+use core::fmt;
+use core::iter::Iterator;
+use core::time::Duration;
+
+/// A retry strategy that computes each delay from the zero-based index of the retry.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use tokio_retry::strategy::CustomStrategy;
+/// // 0ms, 50ms, 200ms, 450ms, ...
+/// let strategy = CustomStrategy::new(|n| Duration::from_millis(50 * u64::from(n * n)));
+/// ```
+#[derive(Clone)]
+pub struct CustomStrategy<F> {
+    delay: F,
+    attempt: u32,
+}
+
+impl<F: FnMut(u32) -> Duration> CustomStrategy<F> {
+    pub fn new(delay: F) -> CustomStrategy<F> {
+        CustomStrategy {
+            delay: delay,
+            attempt: 0,
+        }
+    }
+}
+
+impl<F> fmt::Debug for CustomStrategy<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomStrategy")
+            .field("attempt", &self.attempt)
+            .finish()
+    }
+}
+
+impl<F: FnMut(u32) -> Duration> Iterator for CustomStrategy<F> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = (self.delay)(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        Some(duration)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[test]
+fn passes_attempt_index_to_closure() {
+    let mut s = CustomStrategy::new(|n| Duration::from_millis(50 * u64::from(n * n)));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(0)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(200)));
+    assert_eq!(s.next(), Some(Duration::from_millis(450)));
+}
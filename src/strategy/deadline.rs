@@ -0,0 +1,308 @@
+use std::cmp;
+use std::fmt;
+use std::iter::Iterator;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::sleeper::{Clock, RealClock};
+
+/// Returns the delay until `target`, or zero if it has already passed.
+///
+/// Together with `RetryDecision::ContinueAfter`, this lets a condition align the next attempt
+/// with a point in time, for instance one derived from a timestamp sent by the server.
+#[cfg(feature = "tokio")]
+pub fn until_instant(target: tokio::time::Instant) -> Duration {
+    target.saturating_duration_since(tokio::time::Instant::now())
+}
+
+/// A retry strategy wrapper that ends once a deadline has passed.
+///
+/// The delay before the deadline is clamped to the remaining time, so that the final sleep
+/// ends right at the deadline instead of overshooting it.
+///
+/// A deadline given as a maximum duration starts counting down at the first delay, rather than
+/// when the strategy is constructed, so that a strategy can be set up well ahead of its use.
+///
+/// Time is measured with `RealClock`, unless another clock is set with `with_clock`.
+///
+/// See `StrategyExt::deadline` and `Retry::spawn_with_deadline`.
+#[derive(Debug, Clone)]
+pub struct Deadline<I, C = RealClock> {
+    inner: I,
+    // unset until the first delay if the deadline is relative
+    deadline: Option<Instant>,
+    max_duration: Duration,
+    clock: C,
+}
+
+impl<I> Deadline<I> {
+    pub(crate) fn new(inner: I, max_duration: Duration) -> Deadline<I> {
+        Deadline {
+            inner: inner,
+            deadline: None,
+            max_duration: max_duration,
+            clock: RealClock,
+        }
+    }
+
+    pub(crate) fn until(inner: I, deadline: Instant) -> Deadline<I> {
+        Deadline {
+            inner: inner,
+            deadline: Some(deadline),
+            max_duration: Duration::ZERO,
+            clock: RealClock,
+        }
+    }
+}
+
+impl<I, C: Clock> Deadline<I, C> {
+    /// Measures time with the given clock, for instance the virtual clock of a simulation.
+    pub fn with_clock<C2: Clock>(self, clock: C2) -> Deadline<I, C2> {
+        Deadline {
+            inner: self.inner,
+            deadline: self.deadline,
+            max_duration: self.max_duration,
+            clock: clock,
+        }
+    }
+
+    /// Returns the time remaining until the deadline, all of it if the clock has not started.
+    fn remaining(&self) -> Duration {
+        match self.deadline {
+            Some(deadline) => deadline.saturating_duration_since(self.clock.now()),
+            None => self.max_duration,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Duration>, C: Clock> Iterator for Deadline<I, C> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let now = self.clock.now();
+        let max_duration = self.max_duration;
+        let remaining = self
+            .deadline
+            .get_or_insert_with(|| now + max_duration)
+            .checked_duration_since(now)
+            .filter(|remaining| *remaining > Duration::ZERO)?;
+        self.inner
+            .next()
+            .map(|duration| cmp::min(duration, remaining))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+/// Describes the wrapped strategy followed by the time remaining until the deadline, for
+/// instance `fixed(100ms).deadline(remaining=5s)`.
+impl<I: fmt::Display, C: Clock> fmt::Display for Deadline<I, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.deadline(remaining={:?})",
+            self.inner,
+            self.remaining()
+        )
+    }
+}
+
+/// A retry strategy wrapper that ends once a wall-clock deadline has passed.
+///
+/// Like `Deadline`, but the remaining time is computed from the system clock on every delay,
+/// for deadlines that are received as absolute points in time, such as an expiry timestamp.
+/// Once another clock is set with `with_clock`, the remaining time is measured with it instead.
+///
+/// See `Retry::spawn_before`.
+#[derive(Debug, Clone)]
+pub struct SystemDeadline<I, C = RealClock> {
+    inner: I,
+    deadline: SystemTime,
+    // the deadline on the clock set with `with_clock`, if any
+    on_clock: Option<Instant>,
+    clock: C,
+}
+
+impl<I> SystemDeadline<I> {
+    pub(crate) fn new(inner: I, deadline: SystemTime) -> SystemDeadline<I> {
+        SystemDeadline {
+            inner: inner,
+            deadline: deadline,
+            on_clock: None,
+            clock: RealClock,
+        }
+    }
+}
+
+impl<I, C: Clock> SystemDeadline<I, C> {
+    /// Measures time with the given clock, for instance the virtual clock of a simulation.
+    ///
+    /// The deadline is placed on the clock at the time that remains until it now, so later
+    /// changes to the system clock are no longer taken into account.
+    pub fn with_clock<C2: Clock>(self, clock: C2) -> SystemDeadline<I, C2> {
+        let remaining = self.remaining().unwrap_or(Duration::ZERO);
+        SystemDeadline {
+            inner: self.inner,
+            deadline: self.deadline,
+            on_clock: Some(clock.now() + remaining),
+            clock: clock,
+        }
+    }
+
+    /// Returns the time remaining until the deadline, or `None` if it has passed.
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        let remaining = match self.on_clock {
+            Some(deadline) => deadline.checked_duration_since(self.clock.now()),
+            None => self.deadline.duration_since(SystemTime::now()).ok(),
+        };
+        remaining.filter(|remaining| *remaining > Duration::ZERO)
+    }
+}
+
+impl<I: Iterator<Item = Duration>, C: Clock> Iterator for SystemDeadline<I, C> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let remaining = self.remaining()?;
+        self.inner
+            .next()
+            .map(|duration| cmp::min(duration, remaining))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+/// Describes the wrapped strategy followed by the time remaining until the deadline, like
+/// `Deadline` does.
+impl<I: fmt::Display, C: Clock> fmt::Display for SystemDeadline<I, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.deadline(remaining={:?})",
+            self.inner,
+            self.remaining().unwrap_or(Duration::ZERO)
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn returns_none_after_deadline_passes() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(10).deadline(Duration::from_millis(50));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+
+    tokio::time::advance(Duration::from_millis(50)).await;
+    assert_eq!(s.next(), None);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn clamps_final_delay_to_remaining_time() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::new(Duration::from_secs(30)).deadline(Duration::from_secs(32));
+    assert_eq!(s.next(), Some(Duration::from_secs(30)));
+
+    tokio::time::advance(Duration::from_secs(30)).await;
+    assert_eq!(s.next(), Some(Duration::from_secs(2)));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn follows_paused_virtual_clock() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(10).deadline(Duration::from_millis(50));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+
+    tokio::time::advance(Duration::from_millis(49)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(1)));
+
+    tokio::time::advance(Duration::from_millis(1)).await;
+    assert_eq!(s.next(), None);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn relative_deadline_starts_at_first_delay() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(10).deadline(Duration::from_millis(50));
+
+    // time spent between construction and first use does not count against the deadline
+    tokio::time::advance(Duration::from_secs(60)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+
+    tokio::time::advance(Duration::from_millis(45)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(5)));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn until_instant_returns_remaining_delay() {
+    let target = tokio::time::Instant::now() + Duration::from_secs(5);
+    assert_eq!(until_instant(target), Duration::from_secs(5));
+
+    tokio::time::advance(Duration::from_secs(2)).await;
+    assert_eq!(until_instant(target), Duration::from_secs(3));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn until_instant_clamps_past_target_to_zero() {
+    let target = tokio::time::Instant::now();
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    assert_eq!(until_instant(target), Duration::ZERO);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn describes_wrapped_strategy_and_remaining_time() {
+    use super::{ExponentialBackoff, StrategyExt};
+
+    let s = ExponentialBackoff::from_millis(10)
+        .factor(1000)
+        .max_delay(Duration::from_secs(60))
+        .deadline(Duration::from_secs(5));
+
+    assert_eq!(
+        s.to_string(),
+        "exponential(base=10ms, factor=1000, max_delay=60s).deadline(remaining=5s)"
+    );
+}
+
+#[test]
+fn system_deadline_clamps_to_remaining_wall_clock_time() {
+    use super::FixedInterval;
+
+    let mut s = SystemDeadline::new(
+        FixedInterval::from_millis(10),
+        SystemTime::now() + Duration::from_secs(3600),
+    );
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+
+    let mut s = SystemDeadline::new(
+        FixedInterval::new(Duration::from_secs(3600)),
+        SystemTime::now() + Duration::from_secs(60),
+    );
+    let delay = s.next().unwrap();
+    assert!(delay <= Duration::from_secs(60));
+    assert!(delay > Duration::from_secs(50));
+}
+
+#[test]
+fn system_deadline_in_the_past_ends_strategy() {
+    use super::FixedInterval;
+
+    let mut s = SystemDeadline::new(
+        FixedInterval::from_millis(10),
+        SystemTime::now() - Duration::from_secs(1),
+    );
+    assert_eq!(s.next(), None);
+}
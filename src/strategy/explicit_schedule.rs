@@ -0,0 +1,95 @@
+use core::iter::{IntoIterator, Iterator};
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+/// A retry strategy that yields an explicit list of delays.
+///
+/// Unlike passing a `Vec<Duration>` to `Retry::spawn` directly, this is a named type
+/// which can be stored in a struct and cloned for every retry.
+#[derive(Debug, Clone)]
+pub struct ExplicitSchedule {
+    delays: Vec<Duration>,
+    index: usize,
+    repeat_last: bool,
+}
+
+impl ExplicitSchedule {
+    /// Constructs a schedule that yields the given delays in order, and ends after the last one.
+    pub fn new<T: IntoIterator<Item = Duration>>(delays: T) -> ExplicitSchedule {
+        ExplicitSchedule {
+            delays: delays.into_iter().collect(),
+            index: 0,
+            repeat_last: false,
+        }
+    }
+
+    /// Keeps yielding the last delay forever, instead of ending after it.
+    #[must_use]
+    pub fn repeat_last(mut self) -> ExplicitSchedule {
+        self.repeat_last = true;
+        self
+    }
+}
+
+impl Iterator for ExplicitSchedule {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        match self.delays.get(self.index) {
+            Some(&duration) => {
+                self.index += 1;
+                Some(duration)
+            }
+            None if self.repeat_last => self.delays.last().cloned(),
+            None => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.repeat_last && !self.delays.is_empty() {
+            (usize::MAX, None)
+        } else {
+            let remaining = self.delays.len().saturating_sub(self.index);
+            (remaining, Some(remaining))
+        }
+    }
+}
+
+/// Iterates over a clone of the strategy, from its current position, so that it can be
+/// passed by reference to `Retry::spawn` and reused.
+impl<'a> IntoIterator for &'a ExplicitSchedule {
+    type Item = Duration;
+    type IntoIter = ExplicitSchedule;
+
+    fn into_iter(self) -> ExplicitSchedule {
+        self.clone()
+    }
+}
+
+#[test]
+fn ends_after_last_delay() {
+    let mut s = ExplicitSchedule::new(vec![Duration::from_millis(10), Duration::from_millis(50)]);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn repeats_last_delay() {
+    let mut s = ExplicitSchedule::new(vec![Duration::from_millis(10), Duration::from_millis(50)])
+        .repeat_last();
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn empty_schedule_ends_even_when_repeating() {
+    let mut s = ExplicitSchedule::new(vec![]).repeat_last();
+
+    assert_eq!(s.next(), None);
+}
@@ -1,6 +1,10 @@
-use std::iter::Iterator;
-use std::time::Duration;
-use std::u64::MAX as U64_MAX;
+use core::cmp;
+use core::fmt;
+use core::iter::Iterator;
+use core::time::Duration;
+use core::u64::MAX as U64_MAX;
+
+use super::saturating::{saturating_mul_millis, scale};
 
 /// A retry strategy driven by exponential back-off.
 ///
@@ -10,7 +14,22 @@ pub struct ExponentialBackoff {
     current: u64,
     base: u64,
     factor: u64,
+    // the duration of one unit of `current`, one millisecond unless constructed otherwise
+    unit: Duration,
     max_delay: Option<Duration>,
+    // the delay at which the strategy stops growing
+    ceiling: Option<Duration>,
+    first_delay: Option<Duration>,
+    // whether the first delay has been yielded, so that `first_delay` is only applied once
+    emitted_first: bool,
+    multiplier: Option<f64>,
+    // the current delay in units while a multiplier is set, unrounded so that
+    // fractional multipliers do not accumulate rounding errors
+    exact: f64,
+    // the number of delays left to yield, if limited
+    remaining: Option<usize>,
+    #[cfg(feature = "jitter")]
+    randomization_factor: f64,
 }
 
 impl ExponentialBackoff {
@@ -20,40 +39,208 @@ impl ExponentialBackoff {
     /// The resulting duration is calculated by taking the base to the `n`-th power,
     /// where `n` denotes the number of past attempts.
     pub fn from_millis(base: u64) -> ExponentialBackoff {
+        ExponentialBackoff::from_unit(base, base, Duration::from_millis(1))
+    }
+
+    /// Constructs a new exponential back-off strategy,
+    /// given a base duration in microseconds.
+    ///
+    /// Like `from_millis`, but the `n`-th power of the base is taken in microseconds.
+    pub fn from_micros(base: u64) -> ExponentialBackoff {
+        ExponentialBackoff::from_unit(base, base, Duration::from_micros(1))
+    }
+
+    /// Constructs a new exponential back-off strategy,
+    /// given a base duration at full precision.
+    ///
+    /// Like `from_millis`, the base is both the first delay and, counted in milliseconds, the
+    /// ratio by which the delays grow: the `n`-th delay is `base * (base / 1ms)^n`, computed
+    /// in nanoseconds. So `from_duration` of 10ms yields the same delays as `from_millis(10)`,
+    /// and of 10.5ms yields 10.5ms, 110.25ms, 1157.625ms, ...
+    ///
+    /// A base of at most 1ms does not grow. Use `from_initial_delay` for delays that grow
+    /// independently of the first delay, which is usually preferable for short delays.
+    pub fn from_duration(base: Duration) -> ExponentialBackoff {
+        let nanos = base.as_nanos().min(U64_MAX as u128) as u64;
+        let ratio = base.as_secs_f64() * 1000.0;
+        ExponentialBackoff::from_unit(nanos, nanos, Duration::from_nanos(1))
+            .multiplier(ratio.max(1.0))
+    }
+
+    /// Constructs a new exponential back-off strategy that starts with the given delay,
+    /// at full precision, and doubles it on each attempt.
+    ///
+    /// Unlike `from_millis`, where the base is both the first delay and the ratio by which the
+    /// delays grow, the initial delay here does not affect the growth: `from_initial_delay` of
+    /// 10ms yields 10ms, 20ms, 40ms, ... whereas `from_millis(10)` yields 10ms, 100ms, 1000ms, ...
+    ///
+    /// Use `multiplier` to grow the delay by a different ratio, in which case the delays are
+    /// rounded down to multiples of the initial delay.
+    pub fn from_initial_delay(initial: Duration) -> ExponentialBackoff {
+        ExponentialBackoff::from_unit(1, 1, initial).multiplier(2.0)
+    }
+
+    fn from_unit(base: u64, current: u64, unit: Duration) -> ExponentialBackoff {
         ExponentialBackoff {
-            current: base,
+            current: current,
             base: base,
             factor: 1u64,
+            unit: unit,
             max_delay: None,
+            ceiling: None,
+            first_delay: None,
+            emitted_first: false,
+            multiplier: None,
+            exact: current as f64,
+            remaining: None,
+            #[cfg(feature = "jitter")]
+            randomization_factor: 0.0,
         }
     }
 
+    /// Constructs an exponential back-off strategy that resumes from a known point,
+    /// given the base, the `current` power of the base and the factor.
+    ///
+    /// The `current` power is the delay returned by `current`, in milliseconds and divided by the factor.
+    pub fn with_current(base: u64, current: u64, factor: u64) -> ExponentialBackoff {
+        ExponentialBackoff::from_unit(base, current, Duration::from_millis(1)).factor(factor)
+    }
+
+    /// Returns the delay that the next attempt will yield, before any randomization
+    /// and maximum delay are applied.
+    ///
+    /// This never exceeds the ceiling set with `with_max`.
+    pub fn current(&self) -> Duration {
+        let current = scale(self.unit, saturating_mul_millis(self.current, self.factor));
+        match self.ceiling {
+            Some(ceiling) => cmp::min(current, ceiling),
+            None => current,
+        }
+    }
+
+    /// Grows the delay by the given multiplier on each attempt, instead of by the base.
+    ///
+    /// The base then only determines the initial delay, so that for example
+    /// `ExponentialBackoff::from_millis(10).multiplier(1.5)` yields 10ms, 15ms, 22ms, 33ms, ...
+    /// The delays are rounded down to the unit of the strategy, milliseconds for `from_millis`,
+    /// and saturate at `u64::MAX` units.
+    #[must_use]
+    pub fn multiplier(mut self, multiplier: f64) -> ExponentialBackoff {
+        self.multiplier = Some(multiplier);
+        self.exact = self.current as f64;
+        self
+    }
+
     /// A multiplicative factor that will be applied to the retry delay.
     ///
     /// For example, using a factor of `1000` will make each delay in units of seconds.
     ///
     /// Default factor is `1`.
+    #[must_use]
     pub fn factor(mut self, factor: u64) -> ExponentialBackoff {
         self.factor = factor;
         self
     }
 
     /// Apply a maximum delay. No retry delay will be longer than this `Duration`.
+    #[must_use]
     pub fn max_delay(mut self, duration: Duration) -> ExponentialBackoff {
         self.max_delay = Some(duration);
         self
     }
+
+    /// Saturates the strategy at `ceiling`: once the delay reaches the ceiling, it is no longer
+    /// multiplied, and every following delay is the ceiling.
+    ///
+    /// Unlike `max_delay`, which caps the delays that are yielded while the strategy keeps
+    /// growing behind the cap, this stops the growth itself, so that `current` never exceeds the
+    /// ceiling. Randomized delays are capped at the ceiling as well.
+    ///
+    /// By default, the delay saturates at `u64::MAX` units only.
+    #[must_use]
+    pub fn with_max(mut self, ceiling: Duration) -> ExponentialBackoff {
+        self.ceiling = Some(ceiling);
+        self
+    }
+
+    /// Overrides the very first delay, for a quick first retry before backing off.
+    ///
+    /// The following delays are unaffected, and start from the base as usual. The maximum
+    /// delay still applies to the first delay.
+    #[must_use]
+    pub fn first_delay(mut self, duration: Duration) -> ExponentialBackoff {
+        self.first_delay = Some(duration);
+        self
+    }
+
+    /// Limits the strategy to `n` delays, after which it yields `None`.
+    ///
+    /// Unlike `Iterator::take`, this keeps the type of the strategy as `ExponentialBackoff`, so
+    /// that a bounded strategy can be stored in a field. The delays are unaffected.
+    #[must_use]
+    pub fn max_retries(mut self, n: usize) -> ExponentialBackoff {
+        self.remaining = Some(n);
+        self
+    }
+
+    /// Randomize each delay by a random factor within `[1 - randomization_factor, 1 + randomization_factor]`.
+    ///
+    /// The randomization factor is clamped to `[0.0, 1.0]`. The maximum delay still applies
+    /// to the randomized delay.
+    ///
+    /// Default randomization factor is `0.0`, which means no randomization.
+    #[cfg(feature = "jitter")]
+    #[must_use]
+    pub fn randomization_factor(mut self, randomization_factor: f64) -> ExponentialBackoff {
+        self.randomization_factor = randomization_factor.max(0.0).min(1.0);
+        self
+    }
 }
 
-impl Iterator for ExponentialBackoff {
-    type Item = Duration;
+impl ExponentialBackoff {
+    // yields the next delay, drawing the randomization from `random`, which returns samples
+    // within `[0, 1)`, so that tests can pin the randomized delays
+    fn next_with<R: FnMut() -> f64>(&mut self, mut random: R) -> Option<Duration> {
+        if let Some(ref mut remaining) = self.remaining {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+
+        if !self.emitted_first {
+            self.emitted_first = true;
+            if let Some(first_delay) = self.first_delay {
+                return Some(match self.max_delay {
+                    Some(max_delay) if first_delay > max_delay => max_delay,
+                    _ => first_delay,
+                });
+            }
+        }
 
-    fn next(&mut self) -> Option<Duration> {
         // set delay duration by applying factor
-        let duration = if let Some(duration) = self.current.checked_mul(self.factor) {
-            Duration::from_millis(duration)
+        let units = saturating_mul_millis(self.current, self.factor);
+
+        // perturb the delay by the randomization factor, float casts saturate
+        #[cfg(feature = "jitter")]
+        let units = if self.randomization_factor > 0.0 {
+            let delta = self.randomization_factor * (2.0 * random() - 1.0);
+            (units as f64 * (1.0 + delta)) as u64
         } else {
-            Duration::from_millis(U64_MAX)
+            units
+        };
+        #[cfg(not(feature = "jitter"))]
+        let _ = &mut random;
+
+        let mut duration = scale(self.unit, units);
+
+        // check if we reached the ceiling, after which the strategy stops growing
+        let saturated = match self.ceiling {
+            Some(ceiling) => {
+                duration = cmp::min(duration, ceiling);
+                self.current() >= ceiling
+            }
+            None => false,
         };
 
         // check if we reached max delay
@@ -63,16 +250,170 @@ impl Iterator for ExponentialBackoff {
             }
         }
 
-        if let Some(next) = self.current.checked_mul(self.base) {
-            self.current = next;
+        if saturated {
+            // the delay no longer grows
+        } else if let Some(multiplier) = self.multiplier {
+            // float casts saturate
+            self.exact *= multiplier;
+            self.current = self.exact as u64;
         } else {
-            self.current = U64_MAX;
+            self.current = saturating_mul_millis(self.current, self.base);
         }
 
         Some(duration)
     }
 }
 
+impl Iterator for ExponentialBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.next_with(random_sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "jitter")]
+fn random_sample() -> f64 {
+    rand::random::<f64>()
+}
+
+// without the `jitter` feature the delays are never randomized, so no sample is drawn
+#[cfg(not(feature = "jitter"))]
+fn random_sample() -> f64 {
+    0.5
+}
+
+/// Iterates over a clone of the strategy, from its current state, so that it can be
+/// passed by reference to `Retry::spawn` and reused.
+impl<'a> IntoIterator for &'a ExponentialBackoff {
+    type Item = Duration;
+    type IntoIter = ExponentialBackoff;
+
+    fn into_iter(self) -> ExponentialBackoff {
+        self.clone()
+    }
+}
+
+/// Describes the configuration of the strategy, for instance `exponential(base=10ms, factor=1000)`.
+impl fmt::Display for ExponentialBackoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "exponential(base={:?}, factor={}",
+            scale(self.unit, self.base),
+            self.factor
+        )?;
+        if let Some(multiplier) = self.multiplier {
+            write!(f, ", multiplier={}", multiplier)?;
+        }
+        if let Some(first_delay) = self.first_delay {
+            write!(f, ", first_delay={:?}", first_delay)?;
+        }
+        if let Some(max_delay) = self.max_delay {
+            write!(f, ", max_delay={:?}", max_delay)?;
+        }
+        if let Some(ceiling) = self.ceiling {
+            write!(f, ", max={:?}", ceiling)?;
+        }
+        #[cfg(feature = "jitter")]
+        if self.randomization_factor > 0.0 {
+            write!(f, ", randomization_factor={}", self.randomization_factor)?;
+        }
+        if let Some(remaining) = self.remaining {
+            write!(f, ", remaining={}", remaining)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ExponentialBackoffParams {
+    base_ms: u64,
+    #[serde(default = "super::config::default_factor")]
+    factor: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_delay_ns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_ns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    first_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    first_delay_ns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unit_ns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_retries: Option<usize>,
+    #[cfg(feature = "jitter")]
+    #[serde(default)]
+    randomization_factor: f64,
+}
+
+/// Serializes the construction parameters, not the progress of the strategy.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ExponentialBackoff {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ExponentialBackoffParams {
+            base_ms: self.base,
+            factor: self.factor,
+            max_delay_ms: self.max_delay.map(|d| d.as_millis() as u64),
+            max_delay_ns: self.max_delay.and_then(super::sub_millis_nanos),
+            max_ms: self.ceiling.map(|d| d.as_millis() as u64),
+            max_ns: self.ceiling.and_then(super::sub_millis_nanos),
+            first_delay_ms: self.first_delay.map(|d| d.as_millis() as u64),
+            first_delay_ns: self.first_delay.and_then(super::sub_millis_nanos),
+            multiplier: self.multiplier,
+            unit_ns: super::unit_nanos(self.unit),
+            max_retries: self.remaining,
+            #[cfg(feature = "jitter")]
+            randomization_factor: self.randomization_factor,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ExponentialBackoff {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let params = ExponentialBackoffParams::deserialize(deserializer)?;
+        let mut strategy = ExponentialBackoff::from_millis(params.base_ms).factor(params.factor);
+        if let Some(unit_ns) = params.unit_ns {
+            strategy.unit = Duration::from_nanos(unit_ns);
+        }
+        if let Some(max_delay_ms) = params.max_delay_ms {
+            let max_delay = super::from_millis_or_nanos(max_delay_ms, params.max_delay_ns);
+            strategy = strategy.max_delay(max_delay);
+        }
+        if let Some(max_ms) = params.max_ms {
+            strategy = strategy.with_max(super::from_millis_or_nanos(max_ms, params.max_ns));
+        }
+        if let Some(first_delay_ms) = params.first_delay_ms {
+            let first_delay = super::from_millis_or_nanos(first_delay_ms, params.first_delay_ns);
+            strategy = strategy.first_delay(first_delay);
+        }
+        if let Some(multiplier) = params.multiplier {
+            strategy = strategy.multiplier(multiplier);
+        }
+        strategy.remaining = params.max_retries;
+        #[cfg(feature = "jitter")]
+        let strategy = strategy.randomization_factor(params.randomization_factor);
+        Ok(strategy)
+    }
+}
+
 #[test]
 fn returns_some_exponential_base_10() {
     let mut s = ExponentialBackoff::from_millis(10);
@@ -126,3 +467,242 @@ fn returns_max_when_max_less_than_base() {
     assert_eq!(s.next(), Some(Duration::from_millis(10)));
     assert_eq!(s.next(), Some(Duration::from_millis(10)));
 }
+
+#[cfg(feature = "jitter")]
+#[test]
+fn randomizes_within_band() {
+    let mut s = ExponentialBackoff::from_millis(10).randomization_factor(0.5);
+    let mut samples = [0.0, 0.5, 0.75, 0.25].iter().copied();
+    let mut random = || samples.next().unwrap();
+
+    // a sample of 0 shortens the delay by half the band, 0.5 leaves it unchanged
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(5)));
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(100)));
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(1250)));
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(7500)));
+}
+
+#[cfg(feature = "jitter")]
+#[test]
+fn randomization_respects_max_delay() {
+    let mut s = ExponentialBackoff::from_millis(10)
+        .randomization_factor(1.0)
+        .max_delay(Duration::from_millis(100));
+
+    for _ in 0..100 {
+        assert!(s.next().unwrap() <= Duration::from_millis(100));
+    }
+}
+
+#[cfg(feature = "jitter")]
+#[test]
+fn zero_randomization_is_deterministic() {
+    let mut s = ExponentialBackoff::from_millis(10).randomization_factor(0.0);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+}
+
+#[test]
+fn stops_multiplying_once_max_delay_is_reached() {
+    let mut s = ExponentialBackoff::from_millis(10).max_delay(Duration::from_millis(500));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(500)));
+    assert_eq!(s.current, 1000);
+    assert_eq!(s.next(), Some(Duration::from_millis(500)));
+    assert_eq!(s.current, 1000);
+}
+
+#[test]
+fn saturates_current_at_ceiling() {
+    let mut s = ExponentialBackoff::from_millis(10).with_max(Duration::from_millis(500));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(500)));
+    assert_eq!(s.current, 1000);
+    assert_eq!(s.current(), Duration::from_millis(500));
+    for _ in 0..100 {
+        assert_eq!(s.next(), Some(Duration::from_millis(500)));
+    }
+    assert_eq!(s.current, 1000);
+
+    let mut s = ExponentialBackoff::from_millis(10)
+        .multiplier(2.0)
+        .with_max(Duration::from_millis(40));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(20)));
+    assert_eq!(s.next(), Some(Duration::from_millis(40)));
+    assert_eq!(s.current, 40);
+    assert_eq!(s.next(), Some(Duration::from_millis(40)));
+    assert_eq!(s.current, 40);
+}
+
+#[cfg(feature = "jitter")]
+#[test]
+fn randomization_does_not_hold_back_growth_to_ceiling() {
+    let mut s = ExponentialBackoff::from_millis(10)
+        .randomization_factor(0.5)
+        .with_max(Duration::from_millis(100));
+    let mut samples = [1.0, 1.0, 0.0, 0.5].iter().copied();
+    let mut random = || samples.next().unwrap();
+
+    // a randomized delay above the ceiling is capped, and the strategy still grows
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(15)));
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(100)));
+    assert_eq!(s.current(), Duration::from_millis(100));
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(50)));
+    assert_eq!(s.next_with(&mut random), Some(Duration::from_millis(100)));
+    assert_eq!(s.current, 100);
+}
+
+#[test]
+fn with_current_resumes_sequence() {
+    let mut s = ExponentialBackoff::from_millis(10).factor(2);
+    s.next();
+    s.next();
+
+    assert_eq!(s.current(), Duration::from_millis(2000));
+
+    let mut resumed = ExponentialBackoff::with_current(10, 1000, 2);
+
+    assert_eq!(resumed.current(), s.current());
+    assert_eq!(resumed.next(), s.next());
+    assert_eq!(resumed.next(), s.next());
+    assert_eq!(resumed.next(), Some(Duration::from_millis(200_000)));
+}
+
+#[test]
+fn grows_by_fractional_multiplier() {
+    let mut s = ExponentialBackoff::from_millis(10).multiplier(1.5);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(15)));
+    assert_eq!(s.next(), Some(Duration::from_millis(22)));
+    assert_eq!(s.next(), Some(Duration::from_millis(33)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn multiplier_saturates_at_maximum_value() {
+    let mut s = ExponentialBackoff::from_millis(U64_MAX / 2).multiplier(4.0);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX / 2)));
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
+}
+
+#[test]
+fn from_micros_keeps_sub_millisecond_precision() {
+    let mut s = ExponentialBackoff::from_micros(20);
+
+    assert_eq!(s.next(), Some(Duration::from_micros(20)));
+    assert_eq!(s.next(), Some(Duration::from_micros(400)));
+    assert_eq!(s.next(), Some(Duration::from_micros(8000)));
+}
+
+#[test]
+fn from_duration_grows_by_base_in_milliseconds() {
+    let mut s = ExponentialBackoff::from_duration(Duration::from_millis(10));
+    let mut expected = ExponentialBackoff::from_millis(10);
+    for _ in 0..3 {
+        assert_eq!(s.next(), expected.next());
+    }
+
+    let mut s = ExponentialBackoff::from_duration(Duration::from_micros(10_500));
+    assert_eq!(s.next(), Some(Duration::from_micros(10_500)));
+    assert_eq!(s.next(), Some(Duration::from_micros(110_250)));
+    assert_eq!(s.next(), Some(Duration::from_nanos(1_157_625_000)));
+
+    let mut s = ExponentialBackoff::from_duration(Duration::from_micros(500));
+    assert_eq!(s.next(), Some(Duration::from_micros(500)));
+    assert_eq!(s.next(), Some(Duration::from_micros(500)));
+}
+
+#[test]
+fn from_duration_grows_continuously_with_base() {
+    // a base that is off by a nanosecond grows at almost the same ratio
+    let mut s = ExponentialBackoff::from_duration(Duration::from_nanos(10_000_001));
+    assert_eq!(s.next(), Some(Duration::from_nanos(10_000_001)));
+    let delay = s.next().unwrap();
+    assert!(delay >= Duration::from_millis(100));
+    assert!(delay < Duration::from_millis(100) + Duration::from_micros(1));
+}
+
+#[test]
+fn from_initial_delay_doubles_the_delay() {
+    let mut s = ExponentialBackoff::from_initial_delay(Duration::from_micros(250));
+
+    assert_eq!(s.current(), Duration::from_micros(250));
+    assert_eq!(s.next(), Some(Duration::from_micros(250)));
+    assert_eq!(s.next(), Some(Duration::from_micros(500)));
+    assert_eq!(s.next(), Some(Duration::from_millis(1)));
+}
+
+#[test]
+fn overrides_first_delay_only() {
+    let mut s = ExponentialBackoff::from_millis(10).first_delay(Duration::from_millis(5));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(5)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(1000)));
+}
+
+#[test]
+fn caps_first_delay_at_max_delay() {
+    let mut s = ExponentialBackoff::from_millis(10)
+        .first_delay(Duration::from_secs(1))
+        .max_delay(Duration::from_millis(50));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+}
+
+#[test]
+fn describes_configuration() {
+    use alloc::string::ToString;
+
+    assert_eq!(
+        ExponentialBackoff::from_millis(10).factor(1000).to_string(),
+        "exponential(base=10ms, factor=1000)"
+    );
+    assert_eq!(
+        ExponentialBackoff::from_micros(500)
+            .multiplier(1.5)
+            .max_delay(Duration::from_secs(1))
+            .to_string(),
+        "exponential(base=500µs, factor=1, multiplier=1.5, max_delay=1s)"
+    );
+}
+
+#[test]
+fn max_retries_yields_exactly_n_delays() {
+    let mut s = ExponentialBackoff::from_millis(10).max_retries(3);
+
+    assert_eq!(s.size_hint(), (3, Some(3)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(1000)));
+    assert_eq!(s.next(), None);
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn max_retries_keeps_saturating_delays() {
+    let mut s = ExponentialBackoff::from_millis(U64_MAX - 1).max_retries(2);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX - 1)));
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
+    assert_eq!(s.next(), None);
+
+    let mut s = ExponentialBackoff::from_millis(10)
+        .max_delay(Duration::from_millis(50))
+        .max_retries(3);
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), None);
+}
@@ -0,0 +1,258 @@
+use core::iter::{IntoIterator, Iterator};
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+use super::bounded::Bounded;
+#[cfg(feature = "std")]
+use super::deadline::Deadline;
+#[cfg(feature = "jitter")]
+use super::jitter::{Jitter, JitterAfter, Jittered};
+#[cfg(feature = "std")]
+use super::limited_by::{LimitedBy, RetryLimits};
+use super::max_delay::MaxDelay;
+#[cfg(feature = "std")]
+use super::reset_after::ResetAfter;
+use super::scaled_by::ScaledBy;
+#[cfg(feature = "jitter")]
+use super::shuffled::Shuffled;
+use super::then::ThenStrategy;
+use super::total_delay_budget::TotalDelayBudget;
+
+/// Combinators for retry strategies, available on any `Iterator<Item = Duration>`.
+///
+/// This single import enables all strategy adapters of this crate, which chain freely with
+/// each other and with the adapters of `Iterator`:
+///
+/// ```rust
+/// # use std::time::Duration;
+/// use tokio_retry::strategy::{ExponentialBackoff, FixedInterval, StrategyExt};
+///
+/// let strategy = FixedInterval::from_millis(50)
+///     .take(2)
+///     .then(ExponentialBackoff::from_millis(100))
+///     .scaled_by(|| 2.0)
+///     .max_delay(Duration::from_secs(1))
+///     .total_delay_budget(Duration::from_secs(2));
+/// ```
+pub trait StrategyExt: Iterator<Item = Duration> + Sized {
+    /// Yields from this strategy until it is exhausted, then continues with `other`.
+    ///
+    /// For example, a few quick fixed retries can be followed by exponential back-off:
+    ///
+    /// ```rust
+    /// # use tokio_retry::strategy::{ExponentialBackoff, FixedInterval, StrategyExt};
+    /// let strategy = FixedInterval::from_millis(50)
+    ///     .take(3)
+    ///     .then(ExponentialBackoff::from_millis(100));
+    /// ```
+    fn then<T: IntoIterator<Item = Duration>>(self, other: T) -> ThenStrategy<Self, T::IntoIter> {
+        ThenStrategy::new(self, other.into_iter())
+    }
+
+    /// Caps each delay of this strategy at `max_delay`.
+    fn max_delay(self, max_delay: Duration) -> MaxDelay<Self> {
+        MaxDelay::new(self, max_delay)
+    }
+
+    /// Ends this strategy once `max_duration` has elapsed since its first delay, clamping the
+    /// last delay so that it ends right at the deadline.
+    #[cfg(feature = "std")]
+    fn deadline(self, max_duration: Duration) -> Deadline<Self> {
+        Deadline::new(self, max_duration)
+    }
+
+    /// Applies all limits that are present in `limits` at once.
+    ///
+    /// Delays are capped at `max_interval` first, then the number of retries is limited to
+    /// `max_attempts`, and finally the strategy ends `max_elapsed` after its first delay, like
+    /// `.max_delay(max_interval).take(max_attempts).deadline(max_elapsed)`, so that these need
+    /// not be chained by hand in the right order.
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use tokio_retry::strategy::{ExponentialBackoff, RetryLimits, StrategyExt};
+    /// let limits = RetryLimits {
+    ///     max_attempts: Some(5),
+    ///     max_elapsed: Some(Duration::from_secs(30)),
+    ///     max_interval: Some(Duration::from_secs(5)),
+    /// };
+    /// let strategy = ExponentialBackoff::from_millis(10).limited_by(limits);
+    /// ```
+    #[cfg(feature = "std")]
+    fn limited_by(self, limits: RetryLimits) -> LimitedBy<Self> {
+        LimitedBy::new(self, limits)
+    }
+
+    /// Restarts this strategy from its current state whenever a delay is requested more than
+    /// `threshold` after the previous delay ended.
+    #[cfg(feature = "std")]
+    fn reset_after(self, threshold: Duration) -> ResetAfter<Self>
+    where
+        Self: Clone,
+    {
+        ResetAfter::new(self, threshold)
+    }
+
+    /// Ends this strategy once the sum of all delays would exceed `budget`.
+    ///
+    /// Unlike a deadline, the budget only accounts for the time spent sleeping between attempts,
+    /// not for the time spent in the attempts themselves.
+    fn total_delay_budget(self, budget: Duration) -> TotalDelayBudget<Self> {
+        TotalDelayBudget::new(self, budget)
+    }
+
+    /// Ends this strategy after `max_retries` delays, or once the sum of all delays would exceed
+    /// `max_total_delay`, whichever comes first.
+    fn bounded(self, max_retries: usize, max_total_delay: Duration) -> Bounded<Self> {
+        Bounded::new(self, max_retries, max_total_delay)
+    }
+
+    /// Applies full jitter to each delay of this strategy, like `.map(jitter)`, but as a
+    /// concrete type which is `Clone` whenever this strategy is.
+    #[cfg(feature = "jitter")]
+    fn jitter(self) -> Jittered<Self> {
+        Jittered::new(self)
+    }
+
+    /// Applies `jitter` to each delay of this strategy.
+    ///
+    /// ```rust
+    /// # use tokio_retry::strategy::{EqualJitter, ExponentialBackoff, StrategyExt};
+    /// let strategy = ExponentialBackoff::from_millis(10).jittered(EqualJitter);
+    /// ```
+    #[cfg(feature = "jitter")]
+    fn jittered<J: Jitter>(self, jitter: J) -> Jittered<Self, J> {
+        Jittered::with(self, jitter)
+    }
+
+    /// Yields the delays of this strategy in a random order, so that many clients retrying with
+    /// the same strategy do not sleep in lockstep.
+    ///
+    /// This collects all delays up front, so it must only be used on finite strategies, such as
+    /// ones limited with `Iterator::take`. On an infinite strategy it never returns.
+    ///
+    /// ```rust
+    /// # use tokio_retry::strategy::{ExponentialBackoff, StrategyExt};
+    /// let strategy = ExponentialBackoff::from_millis(10).take(5).shuffled();
+    /// ```
+    #[cfg(feature = "jitter")]
+    fn shuffled(self) -> Shuffled {
+        Shuffled::new(self, &mut rand::thread_rng())
+    }
+
+    /// Like `shuffled`, but shuffles with a generator seeded with `seed`, so that the order is
+    /// reproducible.
+    #[cfg(feature = "jitter")]
+    fn shuffled_seeded(self, seed: u64) -> Shuffled {
+        Shuffled::seeded(self, seed)
+    }
+
+    /// Leaves the first `n` delays of this strategy unchanged, and applies `jitter` to all later
+    /// ones, so that the first retries are predictable while later ones are spread out.
+    ///
+    /// With `n = 0`, this is equivalent to `.map(jitter)`.
+    ///
+    /// ```rust
+    /// # use tokio_retry::strategy::{jitter, ExponentialBackoff, StrategyExt};
+    /// let strategy = ExponentialBackoff::from_millis(10).jitter_after(1, jitter);
+    /// ```
+    #[cfg(feature = "jitter")]
+    fn jitter_after<F: FnMut(Duration) -> Duration>(
+        self,
+        n: usize,
+        jitter: F,
+    ) -> JitterAfter<Self, F> {
+        JitterAfter::new(self, n, jitter)
+    }
+
+    /// Multiplies each delay of this strategy by the current value of `factor`, which is called
+    /// for every delay, for instance to back off harder while a queue is long.
+    ///
+    /// Negative factors are clamped to zero, a NaN factor leaves the delay unchanged, and delays
+    /// saturate at the maximum `Duration`. Note that a factor of zero retries without delay.
+    ///
+    /// ```rust
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # use tokio_retry::strategy::{ExponentialBackoff, StrategyExt};
+    /// static QUEUE_DEPTH: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// let strategy = ExponentialBackoff::from_millis(10)
+    ///     .scaled_by(|| 1.0 + QUEUE_DEPTH.load(Ordering::Relaxed) as f64 / 100.0);
+    /// ```
+    fn scaled_by<F: FnMut() -> f64>(self, factor: F) -> ScaledBy<Self, F> {
+        ScaledBy::new(self, factor)
+    }
+
+    /// Collects the first `n` delays of a clone of this strategy, leaving this one untouched.
+    ///
+    /// This is handy to log the effective schedule of a strategy, for instance at startup.
+    fn preview(&self, n: usize) -> Vec<Duration>
+    where
+        Self: Clone,
+    {
+        self.clone().take(n).collect()
+    }
+}
+
+impl<I: Iterator<Item = Duration>> StrategyExt for I {}
+
+/// Returns the next delay of a clone of the strategy, leaving the strategy itself untouched.
+///
+/// This lets a supervisor log the upcoming delay before it is slept. For randomized strategies,
+/// the delay that is actually yielded next can differ from the peeked one.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use tokio_retry::strategy::{peek_next, FixedInterval};
+/// let mut strategy = FixedInterval::from_millis(100);
+///
+/// assert_eq!(peek_next(&strategy), Some(Duration::from_millis(100)));
+/// assert_eq!(strategy.next(), Some(Duration::from_millis(100)));
+/// ```
+pub fn peek_next<I>(strategy: &I) -> Option<Duration>
+where
+    I: Iterator<Item = Duration> + Clone,
+{
+    strategy.clone().next()
+}
+
+#[test]
+fn peek_next_does_not_advance_strategy() {
+    use super::{ExponentialBackoff, FixedInterval};
+
+    let mut s = ExponentialBackoff::from_millis(10);
+    assert_eq!(peek_next(&s), Some(Duration::from_millis(10)));
+    assert_eq!(peek_next(&s), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(peek_next(&s), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+
+    let mut s = FixedInterval::from_millis(50).repeat(1);
+    assert_eq!(peek_next(&s), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(peek_next(&s), None);
+}
+
+#[test]
+fn combinators_compose() {
+    use super::{ExponentialBackoff, FixedInterval};
+
+    let delays: Vec<Duration> = FixedInterval::from_millis(50)
+        .take(2)
+        .then(ExponentialBackoff::from_millis(100))
+        .scaled_by(|| 2.0)
+        .max_delay(Duration::from_secs(1))
+        .total_delay_budget(Duration::from_secs(2))
+        .collect();
+
+    assert_eq!(
+        delays,
+        vec![
+            Duration::from_millis(100),
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+            Duration::from_secs(1),
+        ]
+    );
+}
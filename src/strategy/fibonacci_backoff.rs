@@ -1,6 +1,9 @@
-use std::iter::Iterator;
-use std::time::Duration;
-use std::u64::MAX as U64_MAX;
+use core::fmt;
+use core::iter::Iterator;
+use core::time::Duration;
+use core::u64::MAX as U64_MAX;
+
+use super::saturating::{saturating_add_millis, saturating_mul_millis, scale};
 
 /// A retry strategy driven by the fibonacci series.
 ///
@@ -16,7 +19,10 @@ use std::u64::MAX as U64_MAX;
 pub struct FibonacciBackoff {
     curr: u64,
     next: u64,
+    base: u64,
     factor: u64,
+    // the duration of one unit of the series, one millisecond unless constructed otherwise
+    unit: Duration,
     max_delay: Option<Duration>,
 }
 
@@ -24,10 +30,28 @@ impl FibonacciBackoff {
     /// Constructs a new fibonacci back-off strategy,
     /// given a base duration in milliseconds.
     pub fn from_millis(millis: u64) -> FibonacciBackoff {
+        FibonacciBackoff::from_unit(millis, Duration::from_millis(1))
+    }
+
+    /// Constructs a new fibonacci back-off strategy,
+    /// given a base duration in microseconds.
+    pub fn from_micros(micros: u64) -> FibonacciBackoff {
+        FibonacciBackoff::from_unit(micros, Duration::from_micros(1))
+    }
+
+    /// Constructs a new fibonacci back-off strategy,
+    /// given a base duration at full precision.
+    pub fn from_duration(base: Duration) -> FibonacciBackoff {
+        FibonacciBackoff::from_unit(1, base)
+    }
+
+    fn from_unit(base: u64, unit: Duration) -> FibonacciBackoff {
         FibonacciBackoff {
-            curr: millis,
-            next: millis,
+            curr: base,
+            next: base,
+            base: base,
             factor: 1u64,
+            unit: unit,
             max_delay: None,
         }
     }
@@ -37,45 +61,117 @@ impl FibonacciBackoff {
     /// For example, using a factor of `1000` will make each delay in units of seconds.
     ///
     /// Default factor is `1`.
+    #[must_use]
     pub fn factor(mut self, factor: u64) -> FibonacciBackoff {
         self.factor = factor;
         self
     }
 
     /// Apply a maximum delay. No retry delay will be longer than this `Duration`.
+    #[must_use]
     pub fn max_delay(mut self, duration: Duration) -> FibonacciBackoff {
         self.max_delay = Some(duration);
         self
     }
 }
 
+/// Describes the configuration of the strategy, for instance `fibonacci(base=10ms, factor=1)`.
+impl fmt::Display for FibonacciBackoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "fibonacci(base={:?}, factor={}",
+            scale(self.unit, self.base),
+            self.factor
+        )?;
+        if let Some(max_delay) = self.max_delay {
+            write!(f, ", max_delay={:?}", max_delay)?;
+        }
+        write!(f, ")")
+    }
+}
+
 impl Iterator for FibonacciBackoff {
     type Item = Duration;
 
     fn next(&mut self) -> Option<Duration> {
         // set delay duration by applying factor
-        let duration = if let Some(duration) = self.curr.checked_mul(self.factor) {
-            Duration::from_millis(duration)
-        } else {
-            Duration::from_millis(U64_MAX)
-        };
+        let duration = scale(self.unit, saturating_mul_millis(self.curr, self.factor));
+
+        let next_next = saturating_add_millis(self.curr, self.next);
+        self.curr = self.next;
+        self.next = next_next;
 
-        // check if we reached max delay
+        // check if we reached max delay, the series keeps advancing regardless
         if let Some(ref max_delay) = self.max_delay {
             if duration > *max_delay {
                 return Some(*max_delay);
             }
         }
 
-        if let Some(next_next) = self.curr.checked_add(self.next) {
-            self.curr = self.next;
-            self.next = next_next;
-        } else {
-            self.curr = self.next;
-            self.next = U64_MAX;
+        Some(duration)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// Iterates over a clone of the strategy, from its current state, so that it can be
+/// passed by reference to `Retry::spawn` and reused.
+impl<'a> IntoIterator for &'a FibonacciBackoff {
+    type Item = Duration;
+    type IntoIter = FibonacciBackoff;
+
+    fn into_iter(self) -> FibonacciBackoff {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FibonacciBackoffParams {
+    base_ms: u64,
+    #[serde(default = "super::config::default_factor")]
+    factor: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_delay_ns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unit_ns: Option<u64>,
+}
+
+/// Serializes the construction parameters, not the progress of the strategy.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FibonacciBackoff {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FibonacciBackoffParams {
+            base_ms: self.base,
+            factor: self.factor,
+            max_delay_ms: self.max_delay.map(|d| d.as_millis() as u64),
+            max_delay_ns: self.max_delay.and_then(super::sub_millis_nanos),
+            unit_ns: super::unit_nanos(self.unit),
         }
+        .serialize(serializer)
+    }
+}
 
-        Some(duration)
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FibonacciBackoff {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let params = FibonacciBackoffParams::deserialize(deserializer)?;
+        let mut strategy = FibonacciBackoff::from_millis(params.base_ms).factor(params.factor);
+        if let Some(unit_ns) = params.unit_ns {
+            strategy.unit = Duration::from_nanos(unit_ns);
+        }
+        Ok(match params.max_delay_ms {
+            Some(max_delay_ms) => strategy.max_delay(super::from_millis_or_nanos(
+                max_delay_ms,
+                params.max_delay_ns,
+            )),
+            None => strategy,
+        })
     }
 }
 
@@ -108,6 +204,20 @@ fn stops_increasing_at_max_delay() {
     assert_eq!(iter.next(), Some(Duration::from_millis(50)));
 }
 
+#[test]
+fn keeps_advancing_while_clamped_at_max_delay() {
+    let mut iter = FibonacciBackoff::from_millis(10).max_delay(Duration::from_millis(15));
+    assert_eq!(iter.next(), Some(Duration::from_millis(10)));
+    assert_eq!(iter.next(), Some(Duration::from_millis(10)));
+    assert_eq!(iter.next(), Some(Duration::from_millis(15)));
+    assert_eq!(iter.next(), Some(Duration::from_millis(15)));
+    assert_eq!((iter.curr, iter.next), (50, 80));
+    assert_eq!(
+        format!("{:?}", iter),
+        "FibonacciBackoff { curr: 50, next: 80, base: 10, factor: 1, unit: 1ms, max_delay: Some(15ms) }"
+    );
+}
+
 #[test]
 fn returns_max_when_max_less_than_base() {
     let mut iter = FibonacciBackoff::from_millis(20).max_delay(Duration::from_millis(10));
@@ -125,3 +235,53 @@ fn can_use_factor_to_get_seconds() {
     assert_eq!(s.next(), Some(Duration::from_secs(1)));
     assert_eq!(s.next(), Some(Duration::from_secs(2)));
 }
+
+#[test]
+fn factor_preserves_fibonacci_series() {
+    let mut s = FibonacciBackoff::from_millis(10).factor(1000);
+
+    assert_eq!(s.next(), Some(Duration::from_secs(10)));
+    assert_eq!(s.next(), Some(Duration::from_secs(10)));
+    assert_eq!(s.next(), Some(Duration::from_secs(20)));
+    assert_eq!(s.next(), Some(Duration::from_secs(30)));
+    assert_eq!(s.next(), Some(Duration::from_secs(50)));
+}
+
+#[test]
+fn saturates_when_factor_overflows() {
+    let mut s = FibonacciBackoff::from_millis(U64_MAX / 2).factor(4);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
+}
+
+#[test]
+fn from_micros_keeps_sub_millisecond_precision() {
+    let mut s = FibonacciBackoff::from_micros(300);
+
+    assert_eq!(s.next(), Some(Duration::from_micros(300)));
+    assert_eq!(s.next(), Some(Duration::from_micros(300)));
+    assert_eq!(s.next(), Some(Duration::from_micros(600)));
+    assert_eq!(s.next(), Some(Duration::from_micros(900)));
+}
+
+#[test]
+fn from_duration_uses_full_precision_base() {
+    let mut s = FibonacciBackoff::from_duration(Duration::from_nanos(1500));
+
+    assert_eq!(s.next(), Some(Duration::from_nanos(1500)));
+    assert_eq!(s.next(), Some(Duration::from_nanos(1500)));
+    assert_eq!(s.next(), Some(Duration::from_nanos(3000)));
+}
+
+#[test]
+fn describes_configuration() {
+    use alloc::string::ToString;
+
+    assert_eq!(
+        FibonacciBackoff::from_millis(10)
+            .max_delay(Duration::from_millis(100))
+            .to_string(),
+        "fibonacci(base=10ms, factor=1, max_delay=100ms)"
+    );
+}
@@ -1,16 +1,24 @@
-use std::iter::Iterator;
-use std::time::Duration;
+use core::fmt;
+use core::iter::Iterator;
+use core::time::Duration;
+
+#[cfg(feature = "jitter")]
+use super::jitter::Jittered;
 
 /// A retry strategy driven by a fixed interval.
 #[derive(Debug, Clone)]
 pub struct FixedInterval {
     duration: Duration,
+    remaining: Option<usize>,
 }
 
 impl FixedInterval {
     /// Constructs a new fixed interval strategy.
     pub fn new(duration: Duration) -> FixedInterval {
-        FixedInterval { duration: duration }
+        FixedInterval {
+            duration: duration,
+            remaining: None,
+        }
     }
 
     /// Constructs a new fixed interval strategy,
@@ -18,7 +26,48 @@ impl FixedInterval {
     pub fn from_millis(millis: u64) -> FixedInterval {
         FixedInterval {
             duration: Duration::from_millis(millis),
+            remaining: None,
+        }
+    }
+
+    /// Constructs a new fixed interval strategy,
+    /// given a duration in microseconds.
+    pub fn from_micros(micros: u64) -> FixedInterval {
+        FixedInterval::new(Duration::from_micros(micros))
+    }
+
+    /// Constructs a new fixed interval strategy, given a duration at full precision.
+    ///
+    /// This is the same as `new`, named for symmetry with the other strategies.
+    pub fn from_duration(duration: Duration) -> FixedInterval {
+        FixedInterval::new(duration)
+    }
+
+    /// Limits the strategy to `n` delays, after which it yields `None`.
+    ///
+    /// Unlike `Iterator::take`, this keeps the type of the strategy as `FixedInterval`.
+    #[must_use]
+    pub fn repeat(mut self, n: usize) -> FixedInterval {
+        self.remaining = Some(n);
+        self
+    }
+
+    /// Applies full jitter to the fixed duration on each attempt.
+    #[cfg(feature = "jitter")]
+    #[must_use]
+    pub fn with_jitter(self) -> Jittered<FixedInterval> {
+        Jittered::new(self)
+    }
+}
+
+/// Describes the configuration of the strategy, for instance `fixed(100ms)`.
+impl fmt::Display for FixedInterval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "fixed({:?}", self.duration)?;
+        if let Some(remaining) = self.remaining {
+            write!(f, ", remaining={}", remaining)?;
         }
+        write!(f, ")")
     }
 }
 
@@ -26,8 +75,66 @@ impl Iterator for FixedInterval {
     type Item = Duration;
 
     fn next(&mut self) -> Option<Duration> {
+        match self.remaining {
+            Some(0) => return None,
+            Some(ref mut remaining) => *remaining -= 1,
+            None => {}
+        }
         Some(self.duration)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+/// Iterates over a clone of the strategy, with its remaining repetitions, so that it can be
+/// passed by reference to `Retry::spawn` and reused.
+impl<'a> IntoIterator for &'a FixedInterval {
+    type Item = Duration;
+    type IntoIter = FixedInterval;
+
+    fn into_iter(self) -> FixedInterval {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FixedIntervalParams {
+    interval_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interval_ns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    repeat: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FixedInterval {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FixedIntervalParams {
+            interval_ms: self.duration.as_millis() as u64,
+            interval_ns: super::sub_millis_nanos(self.duration),
+            repeat: self.remaining,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FixedInterval {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let params = FixedIntervalParams::deserialize(deserializer)?;
+        let mut strategy = FixedInterval::new(super::from_millis_or_nanos(
+            params.interval_ms,
+            params.interval_ns,
+        ));
+        strategy.remaining = params.repeat;
+        Ok(strategy)
+    }
 }
 
 #[test]
@@ -38,3 +145,53 @@ fn returns_some_fixed() {
     assert_eq!(s.next(), Some(Duration::from_millis(123)));
     assert_eq!(s.next(), Some(Duration::from_millis(123)));
 }
+
+#[test]
+fn returns_some_fixed_from_millis() {
+    let mut s = FixedInterval::from_millis(123);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(123)));
+    assert_eq!(s.next(), Some(Duration::from_millis(123)));
+}
+
+#[test]
+fn returns_some_fixed_from_micros() {
+    let mut s = FixedInterval::from_micros(500);
+
+    assert_eq!(s.next(), Some(Duration::from_micros(500)));
+    assert_eq!(s.next(), Some(Duration::from_micros(500)));
+    assert_eq!(
+        FixedInterval::from_duration(Duration::from_nanos(1)).next(),
+        Some(Duration::from_nanos(1))
+    );
+}
+
+#[test]
+fn repeat_yields_exactly_n_times() {
+    let mut s = FixedInterval::from_millis(123).repeat(2);
+
+    assert_eq!(s.size_hint(), (2, Some(2)));
+    assert_eq!(s.next(), Some(Duration::from_millis(123)));
+    assert_eq!(s.next(), Some(Duration::from_millis(123)));
+    assert_eq!(s.next(), None);
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn repeat_zero_yields_none() {
+    let mut s = FixedInterval::from_millis(123).repeat(0);
+
+    assert_eq!(s.next(), None);
+}
+
+#[cfg(feature = "jitter")]
+#[test]
+fn with_jitter_stays_within_duration() {
+    let mut s = FixedInterval::from_millis(100).with_jitter();
+
+    for _ in 0..100 {
+        assert!(s.next().unwrap() <= Duration::from_millis(100));
+    }
+
+    let _ = s.clone();
+}
@@ -0,0 +1,55 @@
+use std::time::{Duration, SystemTime};
+
+use ::http::HeaderValue;
+
+/// Parses the value of a `Retry-After` header into the delay before the next attempt.
+///
+/// Both the delta-seconds form (`120`) and the HTTP-date form (`Wed, 21 Oct 2015 07:28:00 GMT`)
+/// are supported. A date in the past yields `Duration::ZERO`. Returns `None` if the value
+/// cannot be parsed.
+///
+/// Combined with `RetryDecision::ContinueAfter`, this lets the server drive the back-off.
+pub fn retry_after_from_header(value: &HeaderValue) -> Option<Duration> {
+    retry_after_at(value, SystemTime::now())
+}
+
+fn retry_after_at(value: &HeaderValue, now: SystemTime) -> Option<Duration> {
+    let value = value.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+#[test]
+fn parses_delta_seconds() {
+    let value = HeaderValue::from_static("120");
+
+    assert_eq!(
+        retry_after_from_header(&value),
+        Some(Duration::from_secs(120))
+    );
+}
+
+#[test]
+fn parses_future_http_date() {
+    let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+    let value = HeaderValue::from_static("Wed, 21 Oct 2015 07:30:00 GMT");
+
+    assert_eq!(retry_after_at(&value, now), Some(Duration::from_secs(120)));
+}
+
+#[test]
+fn past_http_date_yields_zero() {
+    let value = HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT");
+
+    assert_eq!(retry_after_from_header(&value), Some(Duration::ZERO));
+}
+
+#[test]
+fn rejects_invalid_values() {
+    let value = HeaderValue::from_static("soon");
+
+    assert_eq!(retry_after_from_header(&value), None);
+}
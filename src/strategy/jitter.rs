@@ -1,5 +1,439 @@
+use std::cmp;
+use std::iter::Iterator;
 use std::time::Duration;
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::saturating::saturating_scale;
+use super::{ExponentialBackoff, StrategyExt};
+
 pub fn jitter(duration: Duration) -> Duration {
-    duration.mul_f64(rand::random::<f64>())
+    apply_jitter(duration, rand::random::<f64>())
+}
+
+/// Returns a jitter function that applies full jitter like `jitter`, but never shortens a delay
+/// below `min_floor`.
+///
+/// Full jitter can draw delays arbitrarily close to zero, which for small delays amounts to
+/// retrying in a busy loop. Delays that are shorter than `min_floor` to begin with are left
+/// unchanged.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use tokio_retry::strategy::{jitter_with_floor, ExponentialBackoff};
+/// let strategy = ExponentialBackoff::from_millis(10).map(jitter_with_floor(Duration::from_millis(1)));
+/// ```
+pub fn jitter_with_floor(min_floor: Duration) -> impl Fn(Duration) -> Duration + Clone {
+    move |duration| apply_jitter_with_floor(duration, rand::random::<f64>(), min_floor)
+}
+
+/// Returns a jitter function that keeps the fraction `keep` of each delay, and randomizes the rest.
+///
+/// Each delay `d` becomes `d * keep + random(0, d * (1 - keep))`. A `keep` of `0.0` is full
+/// jitter like `jitter`, `0.5` is equal jitter and `1.0` leaves the delays unchanged. `keep` is
+/// clamped to `[0.0, 1.0]`.
+///
+/// ```rust
+/// # use tokio_retry::strategy::{jitter_fraction, ExponentialBackoff};
+/// let strategy = ExponentialBackoff::from_millis(10).map(jitter_fraction(0.5));
+/// ```
+pub fn jitter_fraction(keep: f64) -> impl Fn(Duration) -> Duration + Clone {
+    let keep = keep.max(0.0).min(1.0);
+    move |duration| apply_jitter(duration, keep + (1.0 - keep) * rand::random::<f64>())
+}
+
+/// Returns the "full jitter" strategy, where the `n`-th delay (counting from zero) is
+/// `random(0, min(cap, base * 2^n))`.
+///
+/// The delays are capped before the jitter is applied, so that a delay is drawn from the whole
+/// range below the cap rather than being clamped to the cap itself.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use tokio_retry::strategy::capped_exponential_full_jitter;
+/// let strategy = capped_exponential_full_jitter(100, Duration::from_secs(10)).take(5);
+/// ```
+pub fn capped_exponential_full_jitter(
+    base_ms: u64,
+    cap: Duration,
+) -> impl Iterator<Item = Duration> + Clone {
+    capped_exponential_jittered(base_ms, cap, FullJitter)
+}
+
+pub(crate) fn capped_exponential_jittered<J: Jitter>(
+    base_ms: u64,
+    cap: Duration,
+    jitter: J,
+) -> Jittered<ExponentialBackoff, J> {
+    ExponentialBackoff::from_millis(base_ms)
+        .multiplier(2.0)
+        .max_delay(cap)
+        .jittered(jitter)
+}
+
+/// Scales the duration by the jitter factor, retaining nanosecond precision.
+///
+/// Negative factors are clamped to zero, a NaN factor leaves the duration unchanged, and the
+/// result saturates at the maximum `Duration`.
+fn apply_jitter(duration: Duration, factor: f64) -> Duration {
+    if factor.is_nan() {
+        return duration;
+    }
+    saturating_scale(duration, factor.max(0.0))
+}
+
+/// Like `apply_jitter`, but keeps the result at or above `min_floor`, or the duration itself
+/// if that is shorter.
+fn apply_jitter_with_floor(duration: Duration, factor: f64, min_floor: Duration) -> Duration {
+    cmp::max(
+        apply_jitter(duration, factor),
+        cmp::min(min_floor, duration),
+    )
+}
+
+/// A source of jitter that randomizes each delay of a retry strategy.
+///
+/// See `StrategyExt::jittered`.
+pub trait Jitter {
+    /// Returns the jittered version of the delay.
+    fn jitter(&mut self, duration: Duration) -> Duration;
+}
+
+/// Full jitter: each delay `d` becomes `random(0, d)`, like `jitter`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FullJitter;
+
+impl Jitter for FullJitter {
+    fn jitter(&mut self, duration: Duration) -> Duration {
+        jitter(duration)
+    }
+}
+
+/// Equal jitter: each delay `d` becomes `d / 2 + random(0, d / 2)`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EqualJitter;
+
+impl Jitter for EqualJitter {
+    fn jitter(&mut self, duration: Duration) -> Duration {
+        apply_jitter(duration, 0.5 + 0.5 * rand::random::<f64>())
+    }
+}
+
+/// No jitter: each delay is left unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoJitter;
+
+impl Jitter for NoJitter {
+    fn jitter(&mut self, duration: Duration) -> Duration {
+        duration
+    }
+}
+
+/// A retry strategy wrapper that applies a `Jitter` to each delay of the wrapped strategy,
+/// full jitter by default.
+///
+/// Unlike `.map(jitter)`, this is a concrete type which can be named in struct fields.
+#[derive(Debug, Clone)]
+pub struct Jittered<S, J = FullJitter> {
+    inner: S,
+    jitter: J,
+}
+
+impl<S> Jittered<S> {
+    pub(crate) fn new(inner: S) -> Jittered<S> {
+        Jittered::with(inner, FullJitter)
+    }
+}
+
+impl<S, J> Jittered<S, J> {
+    pub(crate) fn with(inner: S, jitter: J) -> Jittered<S, J> {
+        Jittered {
+            inner: inner,
+            jitter: jitter,
+        }
+    }
+}
+
+impl<S: Iterator<Item = Duration>, J: Jitter> Iterator for Jittered<S, J> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let jitter = &mut self.jitter;
+        self.inner.next().map(|duration| jitter.jitter(duration))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A retry strategy wrapper that leaves the first `n` delays of the wrapped strategy as they
+/// are, and applies a jitter function to all later delays.
+///
+/// See `StrategyExt::jitter_after`.
+#[derive(Debug, Clone)]
+pub struct JitterAfter<S, F> {
+    inner: S,
+    jitter: F,
+    // the number of delays still to be yielded without jitter
+    remaining: usize,
+}
+
+impl<S, F> JitterAfter<S, F> {
+    pub(crate) fn new(inner: S, n: usize, jitter: F) -> JitterAfter<S, F> {
+        JitterAfter {
+            inner: inner,
+            jitter: jitter,
+            remaining: n,
+        }
+    }
+}
+
+impl<S: Iterator<Item = Duration>, F: FnMut(Duration) -> Duration> Iterator for JitterAfter<S, F> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.inner.next()?;
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            Some(duration)
+        } else {
+            Some((self.jitter)(duration))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Applies full jitter like `jitter`, but draws from a seeded generator so that the jittered
+/// delays are reproducible, for instance in tests that assert timing.
+///
+/// ```rust
+/// # use tokio_retry::strategy::{ExponentialBackoff, SeededJitter};
+/// let mut jitter = SeededJitter::new(42);
+/// let strategy = ExponentialBackoff::from_millis(10).map(move |d| jitter.jitter(d));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SeededJitter {
+    rng: StdRng,
+    min_floor: Duration,
+}
+
+impl SeededJitter {
+    pub fn new(seed: u64) -> SeededJitter {
+        SeededJitter {
+            rng: StdRng::seed_from_u64(seed),
+            min_floor: Duration::ZERO,
+        }
+    }
+
+    /// Never shortens a delay below `min_floor`, see `jitter_with_floor`.
+    #[must_use]
+    pub fn min_floor(mut self, min_floor: Duration) -> SeededJitter {
+        self.min_floor = min_floor;
+        self
+    }
+
+    /// Scales the duration by the next random factor of this generator.
+    pub fn jitter(&mut self, duration: Duration) -> Duration {
+        apply_jitter_with_floor(duration, self.rng.gen::<f64>(), self.min_floor)
+    }
+}
+
+impl Jitter for SeededJitter {
+    fn jitter(&mut self, duration: Duration) -> Duration {
+        SeededJitter::jitter(self, duration)
+    }
+}
+
+#[test]
+fn retains_sub_millisecond_precision() {
+    let duration = Duration::from_micros(500);
+
+    assert_eq!(apply_jitter(duration, 1.0), Duration::from_micros(500));
+    assert_eq!(apply_jitter(duration, 0.5), Duration::from_micros(250));
+}
+
+#[test]
+fn jitter_stays_within_duration() {
+    for _ in 0..100 {
+        assert!(jitter(Duration::from_micros(200)) <= Duration::from_micros(200));
+    }
+}
+
+#[test]
+fn seeded_jitter_is_reproducible() {
+    let mut first = SeededJitter::new(42);
+    let mut second = SeededJitter::new(42);
+    let duration = Duration::from_millis(100);
+
+    let first: Vec<Duration> = (0..10).map(|_| first.jitter(duration)).collect();
+    let second: Vec<Duration> = (0..10).map(|_| second.jitter(duration)).collect();
+
+    assert_eq!(first, second);
+    assert!(first.iter().all(|d| *d <= duration));
+    assert!(first.iter().any(|d| *d != first[0]));
+}
+
+#[test]
+fn jitter_fraction_keeps_fraction_of_duration() {
+    let duration = Duration::from_millis(100);
+
+    for _ in 0..100 {
+        assert!(jitter_fraction(0.0)(duration) <= duration);
+
+        let jittered = jitter_fraction(0.5)(duration);
+        assert!(jittered >= Duration::from_millis(50) && jittered <= duration);
+
+        assert_eq!(jitter_fraction(1.0)(duration), duration);
+    }
+}
+
+#[test]
+fn jitter_implementors_stay_within_bounds() {
+    let duration = Duration::from_millis(100);
+
+    for _ in 0..100 {
+        assert!(FullJitter.jitter(duration) <= duration);
+
+        let jittered = EqualJitter.jitter(duration);
+        assert!(jittered >= Duration::from_millis(50) && jittered <= duration);
+
+        assert_eq!(NoJitter.jitter(duration), duration);
+    }
+}
+
+#[test]
+fn jittered_applies_custom_jitter() {
+    use super::{FixedInterval, StrategyExt};
+
+    struct Halve;
+
+    impl Jitter for Halve {
+        fn jitter(&mut self, duration: Duration) -> Duration {
+            duration / 2
+        }
+    }
+
+    let mut s = FixedInterval::from_millis(100).jittered(Halve);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn jitter_adapter_stays_within_bounds_and_clones() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).take(50).jitter();
+    let cloned = s.clone();
+
+    assert_eq!(cloned.count(), 50);
+    for _ in 0..50 {
+        assert!(s.next().unwrap() <= Duration::from_millis(100));
+    }
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn capped_exponential_full_jitter_stays_below_cap() {
+    let cap = Duration::from_millis(1000);
+    let s = capped_exponential_jittered(100, cap, SeededJitter::new(42));
+
+    for (n, delay) in s.take(10).enumerate() {
+        let upper = cmp::min(cap, Duration::from_millis(100 * 2u64.pow(n as u32)));
+        assert!(
+            delay <= upper,
+            "delay {:?} of attempt {} exceeds {:?}",
+            delay,
+            n,
+            upper
+        );
+    }
+
+    assert_eq!(
+        capped_exponential_full_jitter(100, cap).take(10).count(),
+        10
+    );
+}
+
+#[test]
+fn jitter_after_leaves_first_delays_unjittered() {
+    use super::{FixedInterval, StrategyExt};
+
+    let s = FixedInterval::from_millis(100).jitter_after(2, jitter_fraction(0.5));
+    let delays: Vec<Duration> = s.clone().take(50).collect();
+
+    assert_eq!(delays[..2], [Duration::from_millis(100); 2]);
+    assert!(delays[2..]
+        .iter()
+        .all(|d| *d >= Duration::from_millis(50) && *d <= Duration::from_millis(100)));
+    assert!(delays[2..].iter().any(|d| *d != Duration::from_millis(100)));
+    assert_eq!(
+        s.take(2).collect::<Vec<_>>(),
+        [Duration::from_millis(100); 2]
+    );
+}
+
+#[test]
+fn jitter_after_zero_jitters_every_delay() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).jitter_after(0, |d: Duration| d / 2);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn jitter_does_not_overflow_on_huge_durations() {
+    let duration = Duration::from_secs(1 << 60);
+
+    assert_eq!(apply_jitter(duration, 1.0), duration);
+    assert_eq!(apply_jitter(Duration::MAX, 1.0), Duration::MAX);
+    assert!(jitter(Duration::MAX) <= Duration::MAX);
+}
+
+#[test]
+fn jitter_handles_degenerate_factors() {
+    let duration = Duration::from_millis(100);
+
+    assert_eq!(apply_jitter(duration, f64::NAN), duration);
+    assert_eq!(apply_jitter(duration, -0.5), Duration::ZERO);
+    assert_eq!(apply_jitter(duration, f64::NEG_INFINITY), Duration::ZERO);
+    assert_eq!(apply_jitter(duration, 1e300), Duration::MAX);
+    assert_eq!(apply_jitter(duration, f64::INFINITY), Duration::MAX);
+}
+
+#[test]
+fn rounds_to_nearest_nanosecond() {
+    assert_eq!(
+        apply_jitter(Duration::from_nanos(10), 0.05),
+        Duration::from_nanos(1)
+    );
+    assert_eq!(apply_jitter(Duration::from_nanos(10), 0.04), Duration::ZERO);
+}
+
+#[test]
+fn floor_keeps_small_delays_from_collapsing() {
+    let floor = Duration::from_millis(1);
+    let mut jitter = SeededJitter::new(7).min_floor(floor);
+
+    for _ in 0..10_000 {
+        let delay = jitter.jitter(Duration::from_millis(10));
+        assert!(delay >= floor);
+        assert!(delay <= Duration::from_millis(10));
+    }
+    assert_eq!(
+        jitter.jitter(Duration::from_micros(500)),
+        Duration::from_micros(500)
+    );
+    assert_eq!(
+        apply_jitter_with_floor(Duration::from_millis(10), 0.0, floor),
+        floor
+    );
+    assert!(jitter_with_floor(floor)(Duration::from_millis(10)) >= floor);
 }
@@ -0,0 +1,311 @@
+use std::cmp;
+use std::fmt;
+use std::iter::Iterator;
+use std::time::{Duration, Instant};
+
+use crate::sleeper::{Clock, RealClock};
+
+/// Limits to apply to a retry strategy at once, see `StrategyExt::limited_by`.
+///
+/// Limits that are `None` are not applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryLimits {
+    /// The maximum number of retries.
+    pub max_attempts: Option<usize>,
+    /// The maximum time after which the strategy ends, counted from the first delay.
+    pub max_elapsed: Option<Duration>,
+    /// The maximum delay between two attempts.
+    pub max_interval: Option<Duration>,
+}
+
+/// A retry strategy wrapper that applies a set of `RetryLimits`.
+///
+/// Delays are first capped at the maximum interval, then the number of retries is limited,
+/// and finally the strategy ends at the deadline, like
+/// `.max_delay(max_interval).take(max_attempts).deadline(max_elapsed)`.
+///
+/// As with `Deadline`, the maximum elapsed time starts counting down at the first delay,
+/// rather than when the limits are applied. Time is measured with `RealClock`, unless another
+/// clock is set with `with_clock`.
+///
+/// See `StrategyExt::limited_by`.
+#[derive(Debug, Clone)]
+pub struct LimitedBy<I, C = RealClock> {
+    inner: I,
+    max_interval: Option<Duration>,
+    remaining: Option<usize>,
+    max_elapsed: Option<Duration>,
+    // unset until the first delay
+    deadline: Option<Instant>,
+    clock: C,
+}
+
+impl<I> LimitedBy<I> {
+    pub(crate) fn new(inner: I, limits: RetryLimits) -> LimitedBy<I> {
+        LimitedBy {
+            inner: inner,
+            max_interval: limits.max_interval,
+            remaining: limits.max_attempts,
+            max_elapsed: limits.max_elapsed,
+            deadline: None,
+            clock: RealClock,
+        }
+    }
+}
+
+impl<I, C: Clock> LimitedBy<I, C> {
+    /// Measures time with the given clock, for instance the virtual clock of a simulation.
+    ///
+    /// If the strategy was already used, the maximum elapsed time is counted anew from the
+    /// next delay.
+    pub fn with_clock<C2: Clock>(self, clock: C2) -> LimitedBy<I, C2> {
+        LimitedBy {
+            inner: self.inner,
+            max_interval: self.max_interval,
+            remaining: self.remaining,
+            max_elapsed: self.max_elapsed,
+            deadline: None,
+            clock: clock,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Duration>, C: Clock> Iterator for LimitedBy<I, C> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let remaining_time = match self.max_elapsed {
+            Some(max_elapsed) => {
+                let now = self.clock.now();
+                Some(
+                    self.deadline
+                        .get_or_insert_with(|| now + max_elapsed)
+                        .checked_duration_since(now)
+                        .filter(|remaining| *remaining > Duration::ZERO)?,
+                )
+            }
+            None => None,
+        };
+        if let Some(ref mut remaining) = self.remaining {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+        let mut duration = self.inner.next()?;
+        if let Some(max_interval) = self.max_interval {
+            duration = cmp::min(duration, max_interval);
+        }
+        if let Some(remaining_time) = remaining_time {
+            duration = cmp::min(duration, remaining_time);
+        }
+        Some(duration)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = match self.remaining {
+            Some(remaining) => {
+                let (lower, upper) = self.inner.size_hint();
+                let upper = match upper {
+                    Some(upper) => cmp::min(upper, remaining),
+                    None => remaining,
+                };
+                (cmp::min(lower, remaining), Some(upper))
+            }
+            None => self.inner.size_hint(),
+        };
+        match self.max_elapsed {
+            Some(_) => (0, upper),
+            None => (lower, upper),
+        }
+    }
+}
+
+/// Describes the wrapped strategy followed by the limits that are present, for instance
+/// `fixed(100ms).limited_by(max_attempts=3, max_interval=50ms)`.
+impl<I: fmt::Display, C: Clock> fmt::Display for LimitedBy<I, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.limited_by(", self.inner)?;
+        let mut sep = "";
+        if let Some(remaining) = self.remaining {
+            write!(f, "{}max_attempts={}", sep, remaining)?;
+            sep = ", ";
+        }
+        if let Some(max_elapsed) = self.max_elapsed {
+            let remaining = match self.deadline {
+                Some(deadline) => deadline.saturating_duration_since(self.clock.now()),
+                None => max_elapsed,
+            };
+            write!(f, "{}remaining={:?}", sep, remaining)?;
+            sep = ", ";
+        }
+        if let Some(max_interval) = self.max_interval {
+            write!(f, "{}max_interval={:?}", sep, max_interval)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+fn limited(limits: RetryLimits) -> LimitedBy<super::ExponentialBackoff> {
+    use super::{ExponentialBackoff, StrategyExt};
+
+    // 10ms, 100ms, 1s, 10s, ...
+    ExponentialBackoff::from_millis(10).limited_by(limits)
+}
+
+#[test]
+fn applies_no_limits_when_absent() {
+    let mut s = limited(RetryLimits::default());
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_secs(1)));
+    assert_eq!(s.size_hint(), (usize::MAX, None));
+}
+
+#[test]
+fn limits_attempts() {
+    let s = limited(RetryLimits {
+        max_attempts: Some(2),
+        ..RetryLimits::default()
+    });
+
+    assert_eq!(s.size_hint(), (2, Some(2)));
+    assert_eq!(
+        s.collect::<Vec<_>>(),
+        vec![Duration::from_millis(10), Duration::from_millis(100)]
+    );
+}
+
+#[test]
+fn caps_interval() {
+    let mut s = limited(RetryLimits {
+        max_interval: Some(Duration::from_millis(50)),
+        ..RetryLimits::default()
+    });
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn caps_interval_and_limits_attempts() {
+    let s = limited(RetryLimits {
+        max_attempts: Some(3),
+        max_interval: Some(Duration::from_millis(50)),
+        ..RetryLimits::default()
+    });
+
+    assert_eq!(
+        s.collect::<Vec<_>>(),
+        vec![
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+            Duration::from_millis(50),
+        ]
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn ends_at_deadline() {
+    let mut s = limited(RetryLimits {
+        max_elapsed: Some(Duration::from_millis(150)),
+        ..RetryLimits::default()
+    });
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    tokio::time::advance(Duration::from_millis(10)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    tokio::time::advance(Duration::from_millis(100)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(40)));
+    tokio::time::advance(Duration::from_millis(40)).await;
+    assert_eq!(s.next(), None);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn starts_deadline_at_first_delay() {
+    let mut s = limited(RetryLimits {
+        max_elapsed: Some(Duration::from_millis(150)),
+        ..RetryLimits::default()
+    });
+
+    // time spent between construction and first use does not count against the deadline
+    tokio::time::advance(Duration::from_secs(60)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    tokio::time::advance(Duration::from_millis(10)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    tokio::time::advance(Duration::from_millis(100)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(40)));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn limits_attempts_and_ends_at_deadline() {
+    let mut s = limited(RetryLimits {
+        max_attempts: Some(1),
+        max_elapsed: Some(Duration::from_secs(60)),
+        ..RetryLimits::default()
+    });
+
+    assert_eq!(s.size_hint(), (0, Some(1)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), None);
+
+    let mut s = limited(RetryLimits {
+        max_attempts: Some(5),
+        max_elapsed: Some(Duration::from_millis(50)),
+        ..RetryLimits::default()
+    });
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    tokio::time::advance(Duration::from_millis(10)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(40)));
+    tokio::time::advance(Duration::from_millis(40)).await;
+    assert_eq!(s.next(), None);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn caps_interval_and_ends_at_deadline() {
+    let mut s = limited(RetryLimits {
+        max_elapsed: Some(Duration::from_millis(80)),
+        max_interval: Some(Duration::from_millis(50)),
+        ..RetryLimits::default()
+    });
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    tokio::time::advance(Duration::from_millis(10)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    tokio::time::advance(Duration::from_millis(50)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(20)));
+    tokio::time::advance(Duration::from_millis(20)).await;
+    assert_eq!(s.next(), None);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn applies_all_limits_in_order() {
+    let limits = RetryLimits {
+        max_attempts: Some(3),
+        max_elapsed: Some(Duration::from_secs(1)),
+        max_interval: Some(Duration::from_millis(50)),
+    };
+
+    assert_eq!(
+        limited(limits).to_string(),
+        "exponential(base=10ms, factor=1).limited_by(max_attempts=3, remaining=1s, max_interval=50ms)"
+    );
+
+    let mut s = limited(limits);
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    tokio::time::advance(Duration::from_millis(10)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    tokio::time::advance(Duration::from_millis(50)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    tokio::time::advance(Duration::from_millis(50)).await;
+    assert_eq!(s.next(), None);
+}
@@ -0,0 +1,87 @@
+use core::iter::Iterator;
+use core::time::Duration;
+use core::u64::MAX as U64_MAX;
+
+use super::saturating::saturating_add_millis;
+
+/// A retry strategy driven by linear back-off.
+///
+/// Each retry uses a delay which is a fixed step longer than the previous one,
+/// for growth that is gentler than `ExponentialBackoff`.
+#[derive(Debug, Clone)]
+pub struct LinearBackoff {
+    current: u64,
+    step: u64,
+}
+
+impl LinearBackoff {
+    /// Constructs a new linear back-off strategy,
+    /// given a base duration in milliseconds.
+    ///
+    /// The step defaults to the base duration.
+    pub fn from_millis(base: u64) -> LinearBackoff {
+        LinearBackoff {
+            current: base,
+            step: base,
+        }
+    }
+
+    /// Sets the number of milliseconds that is added to the delay after each retry.
+    #[must_use]
+    pub fn step(mut self, step: u64) -> LinearBackoff {
+        self.step = step;
+        self
+    }
+}
+
+impl Iterator for LinearBackoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = Duration::from_millis(self.current);
+        self.current = saturating_add_millis(self.current, self.step);
+        Some(duration)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// Iterates over a clone of the strategy, from its current state, so that it can be
+/// passed by reference to `Retry::spawn` and reused.
+impl<'a> IntoIterator for &'a LinearBackoff {
+    type Item = Duration;
+    type IntoIter = LinearBackoff;
+
+    fn into_iter(self) -> LinearBackoff {
+        self.clone()
+    }
+}
+
+#[test]
+fn returns_some_linear_base_100_step_50() {
+    let mut s = LinearBackoff::from_millis(100).step(50);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(150)));
+    assert_eq!(s.next(), Some(Duration::from_millis(200)));
+    assert_eq!(s.next(), Some(Duration::from_millis(250)));
+}
+
+#[test]
+fn step_defaults_to_base() {
+    let mut s = LinearBackoff::from_millis(10);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(20)));
+}
+
+#[test]
+fn saturates_at_maximum_value() {
+    let mut s = LinearBackoff::from_millis(U64_MAX - 1).step(10);
+
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX - 1)));
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
+    assert_eq!(s.next(), Some(Duration::from_millis(U64_MAX)));
+}
@@ -0,0 +1,73 @@
+use core::cmp;
+use core::fmt;
+use core::iter::Iterator;
+use core::time::Duration;
+
+/// A retry strategy wrapper that caps each delay of the wrapped strategy.
+///
+/// See `StrategyExt::max_delay`.
+#[derive(Debug, Clone)]
+pub struct MaxDelay<I> {
+    inner: I,
+    max_delay: Duration,
+}
+
+impl<I> MaxDelay<I> {
+    pub(crate) fn new(inner: I, max_delay: Duration) -> MaxDelay<I> {
+        MaxDelay {
+            inner: inner,
+            max_delay: max_delay,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for MaxDelay<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.inner
+            .next()
+            .map(|duration| cmp::min(duration, self.max_delay))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Describes the wrapped strategy followed by the cap, for instance `fixed(100ms).max_delay(50ms)`.
+impl<I: fmt::Display> fmt::Display for MaxDelay<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.max_delay({:?})", self.inner, self.max_delay)
+    }
+}
+
+#[test]
+fn caps_delays_at_max_delay() {
+    use super::{FibonacciBackoff, StrategyExt};
+
+    let mut s = FibonacciBackoff::from_millis(10)
+        .take(5)
+        .max_delay(Duration::from_millis(25));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(20)));
+    assert_eq!(s.next(), Some(Duration::from_millis(25)));
+    assert_eq!(s.next(), Some(Duration::from_millis(25)));
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn describes_wrapped_strategy() {
+    use super::{FixedInterval, StrategyExt};
+    use alloc::string::ToString;
+
+    assert_eq!(
+        FixedInterval::from_millis(100)
+            .repeat(3)
+            .max_delay(Duration::from_millis(50))
+            .to_string(),
+        "fixed(100ms, remaining=3).max_delay(50ms)"
+    );
+}
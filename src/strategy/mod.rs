@@ -1,9 +1,289 @@
+#[cfg(feature = "std")]
+mod adaptive;
+mod bounded;
+#[cfg(feature = "serde")]
+mod config;
+mod custom;
+#[cfg(feature = "std")]
+mod deadline;
+mod explicit_schedule;
 mod exponential_backoff;
+mod ext;
 mod fibonacci_backoff;
 mod fixed_interval;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "jitter")]
 mod jitter;
+#[cfg(feature = "std")]
+mod limited_by;
+mod linear_backoff;
+mod max_delay;
+#[cfg(feature = "humantime")]
+mod parse;
+#[cfg(feature = "jitter")]
+pub mod presets;
+#[cfg(feature = "std")]
+mod reset_after;
+mod resettable;
+mod saturating;
+mod scaled_by;
+#[cfg(feature = "jitter")]
+mod shuffled;
+pub mod testing;
+mod then;
+mod total_delay_budget;
 
+#[cfg(feature = "std")]
+pub use self::adaptive::AdaptiveBackoff;
+pub use self::bounded::Bounded;
+#[cfg(feature = "serde")]
+pub use self::config::{RetryConfig, StrategyConfig};
+pub use self::custom::CustomStrategy;
+#[cfg(feature = "tokio")]
+pub use self::deadline::until_instant;
+#[cfg(feature = "std")]
+pub use self::deadline::{Deadline, SystemDeadline};
+pub use self::explicit_schedule::ExplicitSchedule;
 pub use self::exponential_backoff::ExponentialBackoff;
+pub use self::ext::{peek_next, StrategyExt};
 pub use self::fibonacci_backoff::FibonacciBackoff;
 pub use self::fixed_interval::FixedInterval;
-pub use self::jitter::jitter;
+#[cfg(feature = "http")]
+pub use self::http::retry_after_from_header;
+#[cfg(feature = "jitter")]
+pub use self::jitter::{
+    capped_exponential_full_jitter, jitter, jitter_fraction, jitter_with_floor, EqualJitter,
+    FullJitter, Jitter, JitterAfter, Jittered, NoJitter, SeededJitter,
+};
+#[cfg(feature = "std")]
+pub use self::limited_by::{LimitedBy, RetryLimits};
+pub use self::linear_backoff::LinearBackoff;
+pub use self::max_delay::MaxDelay;
+#[cfg(feature = "humantime")]
+pub use self::parse::ParseStrategyError;
+#[cfg(feature = "std")]
+pub use self::reset_after::ResetAfter;
+pub use self::resettable::Resettable;
+pub use self::scaled_by::ScaledBy;
+#[cfg(feature = "jitter")]
+pub use self::shuffled::Shuffled;
+pub use self::then::ThenStrategy;
+pub use self::total_delay_budget::TotalDelayBudget;
+
+/// The unit of a strategy if it is not the default of one millisecond, for serialization.
+#[cfg(feature = "serde")]
+pub(crate) fn unit_nanos(unit: core::time::Duration) -> Option<u64> {
+    if unit == core::time::Duration::from_millis(1) {
+        None
+    } else {
+        Some(unit.as_nanos() as u64)
+    }
+}
+
+/// The duration in nanoseconds if it is not a whole number of milliseconds, for serialization
+/// next to the duration in milliseconds.
+#[cfg(feature = "serde")]
+pub(crate) fn sub_millis_nanos(duration: core::time::Duration) -> Option<u64> {
+    if duration.subsec_nanos() % 1_000_000 == 0 {
+        None
+    } else {
+        Some(duration.as_nanos() as u64)
+    }
+}
+
+/// The duration serialized as milliseconds and, if it is not a whole number of milliseconds,
+/// nanoseconds, which take precedence.
+#[cfg(feature = "serde")]
+pub(crate) fn from_millis_or_nanos(millis: u64, nanos: Option<u64>) -> core::time::Duration {
+    match nanos {
+        Some(nanos) => core::time::Duration::from_nanos(nanos),
+        None => core::time::Duration::from_millis(millis),
+    }
+}
+
+/// Randomizes `duration` like `jitter` when the `jitter` feature is enabled, and returns it
+/// unchanged otherwise.
+///
+/// This lets libraries offer jitter as an optional feature without a `#[cfg]` at every call site:
+///
+/// ```rust
+/// # use tokio_retry::strategy::{maybe_jitter, ExponentialBackoff};
+/// let strategy = ExponentialBackoff::from_millis(10).map(maybe_jitter).take(3);
+/// ```
+#[cfg(feature = "jitter")]
+pub fn maybe_jitter(duration: core::time::Duration) -> core::time::Duration {
+    jitter(duration)
+}
+
+/// Randomizes `duration` like `jitter` when the `jitter` feature is enabled, and returns it
+/// unchanged otherwise.
+///
+/// This lets libraries offer jitter as an optional feature without a `#[cfg]` at every call site:
+///
+/// ```rust
+/// # use tokio_retry::strategy::{maybe_jitter, ExponentialBackoff};
+/// let strategy = ExponentialBackoff::from_millis(10).map(maybe_jitter).take(3);
+/// ```
+#[cfg(not(feature = "jitter"))]
+pub fn maybe_jitter(duration: core::time::Duration) -> core::time::Duration {
+    duration
+}
+
+#[test]
+fn strategies_implement_debug() {
+    let _ = format!("{:?}", ExponentialBackoff::from_millis(10));
+    let _ = format!("{:?}", FibonacciBackoff::from_millis(10));
+    let _ = format!("{:?}", FixedInterval::from_millis(10));
+    let _ = format!("{:?}", LinearBackoff::from_millis(10));
+    let _ = format!(
+        "{:?}",
+        CustomStrategy::new(|_| std::time::Duration::from_millis(10))
+    );
+    let _ = format!("{:?}", Resettable::new(FixedInterval::from_millis(10)));
+}
+
+#[test]
+fn iterator_adapters_cover_former_strategy_decorators() {
+    use std::iter;
+    use std::time::Duration;
+
+    // `NoRetry`
+    assert_eq!(iter::empty::<Duration>().next(), None);
+
+    // `FromIterator`
+    let delays = vec![Duration::from_millis(10), Duration::from_millis(20)];
+    assert_eq!(delays.clone().into_iter().collect::<Vec<_>>(), delays);
+
+    // `LimitedRetries`
+    assert_eq!(FixedInterval::from_millis(10).take(3).count(), 3);
+
+    // `LimitedDelay`
+    let capped: Vec<Duration> = FixedInterval::from_millis(10)
+        .take(2)
+        .then(FixedInterval::from_millis(100))
+        .max_delay(Duration::from_millis(50))
+        .take(4)
+        .collect();
+    assert_eq!(
+        capped,
+        vec![
+            Duration::from_millis(10),
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+            Duration::from_millis(50),
+        ]
+    );
+}
+
+#[test]
+fn infinite_strategies_hint_unbounded_size() {
+    use std::time::Duration;
+
+    assert_eq!(
+        ExponentialBackoff::from_millis(10).size_hint(),
+        (usize::MAX, None)
+    );
+    assert_eq!(
+        FibonacciBackoff::from_millis(10).size_hint(),
+        (usize::MAX, None)
+    );
+    assert_eq!(
+        FixedInterval::from_millis(10).size_hint(),
+        (usize::MAX, None)
+    );
+    assert_eq!(
+        CustomStrategy::new(|_| Duration::from_millis(10)).size_hint(),
+        (usize::MAX, None)
+    );
+    assert_eq!(
+        ExplicitSchedule::new(vec![Duration::from_millis(10)])
+            .repeat_last()
+            .size_hint(),
+        (usize::MAX, None)
+    );
+}
+
+#[test]
+fn adapters_forward_size_hint() {
+    use std::time::Duration;
+
+    let s = ExponentialBackoff::from_millis(10).take(5);
+    assert_eq!(s.size_hint(), (5, Some(5)));
+    assert_eq!(
+        s.clone().max_delay(Duration::from_secs(1)).size_hint(),
+        (5, Some(5))
+    );
+    assert_eq!(Resettable::new(s.clone()).size_hint(), (5, Some(5)));
+    assert_eq!(
+        s.clone()
+            .then(FixedInterval::from_millis(10).take(3))
+            .size_hint(),
+        (8, Some(8))
+    );
+    assert_eq!(
+        s.clone().then(FixedInterval::from_millis(10)).size_hint(),
+        (usize::MAX, None)
+    );
+    assert_eq!(
+        s.clone()
+            .total_delay_budget(Duration::from_secs(1))
+            .size_hint(),
+        (0, Some(5))
+    );
+    assert_eq!(
+        ExplicitSchedule::new(vec![Duration::from_millis(10); 3]).size_hint(),
+        (3, Some(3))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn deadline_forwards_upper_size_hint() {
+    use std::time::Duration;
+
+    let s = FixedInterval::from_millis(10)
+        .take(5)
+        .deadline(Duration::from_secs(1));
+    assert_eq!(s.size_hint(), (0, Some(5)));
+}
+
+#[cfg(feature = "jitter")]
+#[test]
+fn preview_collects_delays_without_consuming_strategy() {
+    use std::time::Duration;
+
+    let mut s = ExponentialBackoff::from_millis(10).map(jitter);
+    let preview = s.preview(5);
+
+    assert_eq!(preview.len(), 5);
+    assert!(preview
+        .iter()
+        .all(|delay| *delay <= Duration::from_millis(100_000)));
+    assert!(s.next().unwrap() <= Duration::from_millis(10));
+}
+
+#[cfg(feature = "jitter")]
+#[test]
+fn maybe_jitter_jitters_with_feature() {
+    use std::time::Duration;
+
+    let delay = Duration::from_secs(10);
+    let delays: Vec<Duration> = (0..20).map(|_| maybe_jitter(delay)).collect();
+
+    assert!(delays.iter().all(|d| *d <= delay));
+    assert!(delays.iter().any(|d| *d != delay));
+}
+
+#[cfg(not(feature = "jitter"))]
+#[test]
+fn maybe_jitter_passes_through_without_feature() {
+    use std::time::Duration;
+
+    let delays: Vec<Duration> = FixedInterval::from_millis(10)
+        .map(maybe_jitter)
+        .take(3)
+        .collect();
+
+    assert_eq!(delays, vec![Duration::from_millis(10); 3]);
+}
@@ -0,0 +1,102 @@
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::{ExponentialBackoff, FibonacciBackoff, FixedInterval};
+
+/// Error returned when parsing a strategy from a human-readable duration fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseStrategyError {
+    /// The duration is zero, which would retry without any delay.
+    Zero,
+    /// The string is not a duration like `250ms` or `1m 30s`, with a description of the problem.
+    Invalid(String),
+}
+
+impl fmt::Display for ParseStrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseStrategyError::Zero => write!(f, "the base duration must not be zero"),
+            ParseStrategyError::Invalid(ref reason) => {
+                write!(f, "invalid base duration: {}", reason)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseStrategyError {}
+
+fn parse_base(s: &str) -> Result<Duration, ParseStrategyError> {
+    let duration = humantime::parse_duration(s.trim())
+        .map_err(|err| ParseStrategyError::Invalid(err.to_string()))?;
+    if duration == Duration::ZERO {
+        return Err(ParseStrategyError::Zero);
+    }
+    Ok(duration)
+}
+
+/// Parses the initial delay, like `"250ms"`, as for `ExponentialBackoff::from_initial_delay`.
+impl FromStr for ExponentialBackoff {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<ExponentialBackoff, ParseStrategyError> {
+        parse_base(s).map(ExponentialBackoff::from_initial_delay)
+    }
+}
+
+/// Parses the base delay, like `"250ms"`, as for `FibonacciBackoff::from_duration`.
+impl FromStr for FibonacciBackoff {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<FibonacciBackoff, ParseStrategyError> {
+        parse_base(s).map(FibonacciBackoff::from_duration)
+    }
+}
+
+/// Parses the interval, like `"1s"`, as for `FixedInterval::new`.
+impl FromStr for FixedInterval {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<FixedInterval, ParseStrategyError> {
+        parse_base(s).map(FixedInterval::new)
+    }
+}
+
+#[test]
+fn parses_human_readable_base_durations() {
+    let mut s: ExponentialBackoff = "250ms".parse().unwrap();
+    assert_eq!(s.next(), Some(Duration::from_millis(250)));
+    assert_eq!(s.next(), Some(Duration::from_millis(500)));
+
+    let mut s: FibonacciBackoff = "1m 30s".parse().unwrap();
+    assert_eq!(s.next(), Some(Duration::from_secs(90)));
+
+    let mut s: FixedInterval = " 1s ".parse().unwrap();
+    assert_eq!(s.next(), Some(Duration::from_secs(1)));
+}
+
+#[test]
+fn rejects_zero_durations() {
+    assert_eq!(
+        "0s".parse::<ExponentialBackoff>().unwrap_err(),
+        ParseStrategyError::Zero
+    );
+    assert_eq!(
+        "0ms".parse::<FixedInterval>().unwrap_err(),
+        ParseStrategyError::Zero
+    );
+}
+
+#[test]
+fn rejects_unparseable_durations() {
+    for s in &["", "fast", "10", "10 parsecs", "-1s"] {
+        match s.parse::<FixedInterval>() {
+            Err(ParseStrategyError::Invalid(_)) => {}
+            other => panic!("expected {:?} to be invalid, got {:?}", s, other),
+        }
+    }
+
+    let err = "fast".parse::<FibonacciBackoff>().unwrap_err();
+    assert!(err.to_string().starts_with("invalid base duration: "));
+}
@@ -0,0 +1,83 @@
+//! Ready-made strategies encoding the retry behaviour recommended by cloud providers.
+//!
+//! Each preset limits the number of retries, so that the retry gives up eventually.
+
+use core::iter::{Iterator, Take};
+use core::time::Duration;
+
+use super::jitter::{capped_exponential_jittered, FullJitter, Jittered};
+use super::ExponentialBackoff;
+
+/// The "standard" retry mode of the AWS SDKs: exponential back-off from a base of 1 second,
+/// doubling on every retry and capped at 20 seconds, with full jitter and at most 3 attempts,
+/// that is 2 retries.
+///
+/// ```rust
+/// # use tokio_retry::strategy::presets;
+/// let strategy = presets::aws_standard();
+/// ```
+pub fn aws_standard() -> impl Iterator<Item = Duration> + Clone {
+    preset(1_000, Duration::from_secs(20), 2)
+}
+
+/// The default retry settings of the Google Cloud client libraries: exponential back-off from
+/// a base of 1 second, doubling on every retry and capped at 32 seconds, with full jitter and at
+/// most 6 attempts, that is 5 retries.
+///
+/// ```rust
+/// # use tokio_retry::strategy::presets;
+/// let strategy = presets::gcp_default();
+/// ```
+pub fn gcp_default() -> impl Iterator<Item = Duration> + Clone {
+    preset(1_000, Duration::from_secs(32), 5)
+}
+
+fn preset(
+    base_ms: u64,
+    cap: Duration,
+    max_retries: usize,
+) -> Take<Jittered<ExponentialBackoff, FullJitter>> {
+    capped_exponential_jittered(base_ms, cap, FullJitter).take(max_retries)
+}
+
+#[cfg(test)]
+fn assert_within_bounds<I: Iterator<Item = Duration>>(
+    strategy: I,
+    base: Duration,
+    cap: Duration,
+    retries: usize,
+) {
+    use alloc::vec::Vec;
+    use core::cmp;
+
+    let delays: Vec<Duration> = strategy.collect();
+
+    assert_eq!(delays.len(), retries);
+    for (n, delay) in delays.into_iter().enumerate() {
+        assert!(delay <= cmp::min(cap, base * 2u32.pow(n as u32)));
+    }
+}
+
+#[test]
+fn aws_standard_retries_twice_within_bounds() {
+    for _ in 0..100 {
+        assert_within_bounds(
+            aws_standard(),
+            Duration::from_secs(1),
+            Duration::from_secs(20),
+            2,
+        );
+    }
+}
+
+#[test]
+fn gcp_default_retries_five_times_within_bounds() {
+    for _ in 0..100 {
+        assert_within_bounds(
+            gcp_default(),
+            Duration::from_secs(1),
+            Duration::from_secs(32),
+            5,
+        );
+    }
+}
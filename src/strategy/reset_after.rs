@@ -0,0 +1,108 @@
+use std::iter::Iterator;
+use std::time::{Duration, Instant};
+
+use crate::sleeper::{Clock, RealClock};
+
+/// A retry strategy wrapper that starts over from its initial state after a long healthy period.
+///
+/// Whenever a delay is requested more than `threshold` after the previous delay ended, the
+/// wrapped strategy is restored from a clone captured at construction. This suits supervisors
+/// of long-running connections sharing one strategy, where a connection that stayed up for a
+/// while should be retried from the base delay again rather than continuing to back off.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tokio_retry::Retry;
+/// # use tokio_retry::strategy::{ExponentialBackoff, StrategyExt};
+/// # async fn serve() -> Result<(), ()> { Err(()) }
+/// # async fn run() -> Result<(), ()> {
+/// let mut strategy = ExponentialBackoff::from_millis(10).reset_after(Duration::from_secs(60));
+/// Retry::spawn(&mut strategy, serve).await
+/// # }
+/// ```
+///
+/// Time is measured with `RealClock`, unless another clock is set with `with_clock`.
+///
+/// See `StrategyExt::reset_after`.
+#[derive(Debug, Clone)]
+pub struct ResetAfter<I, C = RealClock> {
+    initial: I,
+    current: I,
+    threshold: Duration,
+    last_delay_end: Option<Instant>,
+    clock: C,
+}
+
+impl<I: Clone> ResetAfter<I> {
+    pub(crate) fn new(strategy: I, threshold: Duration) -> ResetAfter<I> {
+        ResetAfter {
+            initial: strategy.clone(),
+            current: strategy,
+            threshold: threshold,
+            last_delay_end: None,
+            clock: RealClock,
+        }
+    }
+}
+
+impl<I, C: Clock> ResetAfter<I, C> {
+    /// Measures time with the given clock, for instance the virtual clock of a simulation.
+    pub fn with_clock<C2: Clock>(self, clock: C2) -> ResetAfter<I, C2> {
+        ResetAfter {
+            initial: self.initial,
+            current: self.current,
+            threshold: self.threshold,
+            // the end of the last delay was measured with the previous clock
+            last_delay_end: None,
+            clock: clock,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Duration> + Clone, C: Clock> Iterator for ResetAfter<I, C> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let now = self.clock.now();
+        if let Some(last_delay_end) = self.last_delay_end {
+            if now.saturating_duration_since(last_delay_end) > self.threshold {
+                self.current = self.initial.clone();
+            }
+        }
+        let duration = self.current.next()?;
+        self.last_delay_end = Some(now + duration);
+        Some(duration)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // A reset may restore more delays than the current strategy has left.
+        (self.current.size_hint().0, self.initial.size_hint().1)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn resets_after_long_healthy_period() {
+    use super::{ExponentialBackoff, StrategyExt};
+
+    let mut s = ExponentialBackoff::from_millis(10).reset_after(Duration::from_secs(60));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+
+    tokio::time::advance(Duration::from_millis(10)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+
+    tokio::time::advance(Duration::from_millis(100) + Duration::from_secs(61)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn keeps_backing_off_within_threshold() {
+    use super::{ExponentialBackoff, StrategyExt};
+
+    let mut s = ExponentialBackoff::from_millis(10).reset_after(Duration::from_secs(60));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+
+    tokio::time::advance(Duration::from_millis(10) + Duration::from_secs(59)).await;
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+}
@@ -0,0 +1,82 @@
+use core::iter::Iterator;
+use core::time::Duration;
+
+/// A retry strategy wrapper that can be rewound to its initial state.
+///
+/// A clone of the wrapped strategy is captured at construction, and restored
+/// whenever `reset` is called. This is useful for long-lived retry loops, where the
+/// back-off should start over from the base after a successful attempt.
+///
+/// The wrapper can be borrowed mutably by a `Retry`, so that it is shared across several
+/// retries and reset between them:
+///
+/// ```rust,no_run
+/// # use tokio_retry::Retry;
+/// # use tokio_retry::strategy::{ExponentialBackoff, Resettable};
+/// # async fn connect() -> Result<(), ()> { Ok(()) }
+/// # async fn run() -> Result<(), ()> {
+/// let mut strategy = Resettable::new(ExponentialBackoff::from_millis(10));
+/// loop {
+///     Retry::spawn(&mut strategy, connect).await?;
+///     strategy.reset();
+///     // use the connection until it fails...
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Resettable<I> {
+    initial: I,
+    current: I,
+}
+
+impl<I: Clone> Resettable<I> {
+    /// Wraps the given strategy, capturing its current state as the initial one.
+    pub fn new(strategy: I) -> Resettable<I> {
+        Resettable {
+            initial: strategy.clone(),
+            current: strategy,
+        }
+    }
+
+    /// Restores the strategy to the state it had at construction.
+    pub fn reset(&mut self) {
+        self.current = self.initial.clone();
+    }
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for Resettable<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.current.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.current.size_hint()
+    }
+}
+
+#[test]
+fn returns_to_base_after_reset() {
+    use super::ExponentialBackoff;
+
+    let mut s = Resettable::new(ExponentialBackoff::from_millis(10));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    s.reset();
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+}
+
+#[test]
+fn reset_restores_exhausted_strategy() {
+    use super::FixedInterval;
+
+    let mut s = Resettable::new(FixedInterval::from_millis(10).take(1));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    assert_eq!(s.next(), None);
+    s.reset();
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+}
@@ -0,0 +1,99 @@
+//! Saturating arithmetic shared by the strategies, so that delays grow up to the maximum
+//! representable value instead of overflowing.
+
+use core::time::Duration;
+
+/// Multiplies a delay by a factor, saturating at `u64::MAX`.
+///
+/// The delay is counted in milliseconds, or in whatever unit the strategy uses.
+pub(crate) fn saturating_mul_millis(millis: u64, factor: u64) -> u64 {
+    millis.checked_mul(factor).unwrap_or(u64::MAX)
+}
+
+/// Adds two delays, saturating at `u64::MAX`.
+///
+/// The delays are counted in milliseconds, or in whatever unit the strategy uses.
+pub(crate) fn saturating_add_millis(millis: u64, other: u64) -> u64 {
+    millis.checked_add(other).unwrap_or(u64::MAX)
+}
+
+/// Multiplies `unit` by `units` without losing sub-millisecond precision, saturating at the
+/// maximum `Duration`.
+pub(crate) fn scale(unit: Duration, units: u64) -> Duration {
+    let nanos = unit.as_nanos().saturating_mul(units as u128);
+    let secs = nanos / 1_000_000_000;
+    if secs > u64::MAX as u128 {
+        return Duration::MAX;
+    }
+    Duration::new(secs as u64, (nanos % 1_000_000_000) as u32)
+}
+
+/// Scales the duration by a floating point factor, rounded to the nearest nanosecond.
+///
+/// Results beyond the maximum `Duration` saturate, while negative and NaN factors yield zero.
+pub(crate) fn saturating_scale(duration: Duration, factor: f64) -> Duration {
+    let nanos = duration.as_nanos() as f64 * factor;
+    if !(nanos > 0.0) {
+        return Duration::ZERO;
+    }
+    if nanos >= Duration::MAX.as_nanos() as f64 {
+        return Duration::MAX;
+    }
+    // round to the nearest nanosecond rather than truncating, `f64::round` is not in `core`
+    let nanos = nanos + 0.5;
+    Duration::new((nanos / 1e9) as u64, (nanos % 1e9) as u32)
+}
+
+#[test]
+fn mul_millis_saturates_on_overflow() {
+    assert_eq!(saturating_mul_millis(10, 1000), 10_000);
+    assert_eq!(saturating_mul_millis(u64::MAX / 2, 3), u64::MAX);
+    assert_eq!(saturating_mul_millis(u64::MAX, u64::MAX), u64::MAX);
+}
+
+#[test]
+fn add_millis_saturates_on_overflow() {
+    assert_eq!(saturating_add_millis(10, 20), 30);
+    assert_eq!(saturating_add_millis(u64::MAX - 1, 10), u64::MAX);
+}
+
+#[test]
+fn scale_saturates_at_max_duration() {
+    assert_eq!(
+        scale(Duration::from_micros(500), 3),
+        Duration::from_micros(1500)
+    );
+    assert_eq!(scale(Duration::from_secs(u64::MAX), 2), Duration::MAX);
+    assert_eq!(
+        scale(Duration::from_millis(1), u64::MAX),
+        Duration::from_millis(u64::MAX)
+    );
+}
+
+#[test]
+fn scale_by_factor_saturates_at_max_duration() {
+    assert_eq!(
+        saturating_scale(Duration::from_micros(500), 0.5),
+        Duration::from_micros(250)
+    );
+    assert_eq!(
+        saturating_scale(Duration::from_secs(1 << 60), 1.0),
+        Duration::from_secs(1 << 60)
+    );
+    assert_eq!(
+        saturating_scale(Duration::from_millis(u64::MAX), 2000.0),
+        Duration::MAX
+    );
+    assert_eq!(
+        saturating_scale(Duration::MAX, f64::INFINITY),
+        Duration::MAX
+    );
+    assert_eq!(
+        saturating_scale(Duration::from_millis(10), -1.0),
+        Duration::ZERO
+    );
+    assert_eq!(
+        saturating_scale(Duration::from_millis(10), f64::NAN),
+        Duration::ZERO
+    );
+}
@@ -0,0 +1,73 @@
+use core::iter::Iterator;
+use core::time::Duration;
+
+use super::saturating::saturating_scale;
+
+/// A retry strategy wrapper that multiplies each delay of the wrapped strategy by a factor,
+/// which is obtained anew for every delay.
+///
+/// See `StrategyExt::scaled_by`.
+#[derive(Debug, Clone)]
+pub struct ScaledBy<I, F> {
+    inner: I,
+    factor: F,
+}
+
+impl<I, F> ScaledBy<I, F> {
+    pub(crate) fn new(inner: I, factor: F) -> ScaledBy<I, F> {
+        ScaledBy {
+            inner: inner,
+            factor: factor,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Duration>, F: FnMut() -> f64> Iterator for ScaledBy<I, F> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let duration = self.inner.next()?;
+        let factor = (self.factor)();
+        if factor.is_nan() {
+            return Some(duration);
+        }
+        Some(saturating_scale(duration, factor.max(0.0)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[test]
+fn scales_by_current_factor() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut load = 0.0;
+    let mut s = FixedInterval::from_millis(100).scaled_by(move || {
+        load += 0.5;
+        load
+    });
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(150)));
+    assert_eq!(s.next(), Some(Duration::from_millis(200)));
+}
+
+#[test]
+fn clamps_and_saturates_factor() {
+    use super::{FixedInterval, StrategyExt};
+
+    let factors = [0.0, -2.0, f64::NAN, 1e300];
+    let mut n = 0;
+    let mut s = FixedInterval::from_millis(100).scaled_by(move || {
+        n += 1;
+        factors[n - 1]
+    });
+
+    assert_eq!(s.next(), Some(Duration::ZERO));
+    assert_eq!(s.next(), Some(Duration::ZERO));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::MAX));
+}
@@ -0,0 +1,80 @@
+use core::iter::Iterator;
+use core::time::Duration;
+
+use alloc::vec::{IntoIter, Vec};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// A retry strategy that yields the delays of a finite strategy in a random order.
+///
+/// See `StrategyExt::shuffled`.
+#[derive(Debug, Clone)]
+pub struct Shuffled {
+    delays: IntoIter<Duration>,
+}
+
+impl Shuffled {
+    pub(crate) fn new<I: Iterator<Item = Duration>, R: Rng>(inner: I, rng: &mut R) -> Shuffled {
+        let mut delays: Vec<Duration> = inner.collect();
+        delays.shuffle(rng);
+        Shuffled {
+            delays: delays.into_iter(),
+        }
+    }
+
+    pub(crate) fn seeded<I: Iterator<Item = Duration>>(inner: I, seed: u64) -> Shuffled {
+        Shuffled::new(inner, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Iterator for Shuffled {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        self.delays.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.delays.size_hint()
+    }
+}
+
+#[test]
+fn seeded_shuffle_permutes_delays() {
+    use super::{ExponentialBackoff, StrategyExt};
+
+    let delays: Vec<Duration> = ExponentialBackoff::from_millis(2).take(8).collect();
+    let shuffled: Vec<Duration> = ExponentialBackoff::from_millis(2)
+        .take(8)
+        .shuffled_seeded(42)
+        .collect();
+
+    assert_ne!(shuffled, delays);
+    let mut sorted = shuffled.clone();
+    sorted.sort();
+    assert_eq!(sorted, delays);
+
+    let again: Vec<Duration> = ExponentialBackoff::from_millis(2)
+        .take(8)
+        .shuffled_seeded(42)
+        .collect();
+    assert_eq!(again, shuffled);
+}
+
+#[test]
+fn shuffle_keeps_multiset_of_delays() {
+    use super::{ExplicitSchedule, StrategyExt};
+
+    let schedule = vec![
+        Duration::from_millis(10),
+        Duration::from_millis(10),
+        Duration::from_millis(50),
+    ];
+    let s = ExplicitSchedule::new(schedule.clone()).shuffled();
+    assert_eq!(s.size_hint(), (3, Some(3)));
+
+    let mut shuffled: Vec<Duration> = s.collect();
+    shuffled.sort();
+    assert_eq!(shuffled, schedule);
+}
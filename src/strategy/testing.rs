@@ -0,0 +1,97 @@
+//! Helpers to compare the delays of strategies in tests.
+//!
+//! ```rust
+//! # use std::time::Duration;
+//! # use tokio_retry::strategy::testing::delays_eq;
+//! # use tokio_retry::strategy::ExponentialBackoff;
+//! assert!(delays_eq(
+//!     ExponentialBackoff::from_initial_delay(Duration::from_millis(10)),
+//!     ExponentialBackoff::from_millis(10).multiplier(2.0),
+//!     5,
+//! ));
+//! ```
+
+use core::iter::{IntoIterator, Iterator};
+use core::time::Duration;
+
+/// Returns whether the first `n` delays of `a` and `b` are equal.
+///
+/// A strategy that ends within the first `n` delays only equals one that ends at the same point.
+pub fn delays_eq<A, B>(a: A, b: B, n: usize) -> bool
+where
+    A: IntoIterator<Item = Duration>,
+    B: IntoIterator<Item = Duration>,
+{
+    delays_within(a, b, n, Duration::ZERO)
+}
+
+/// Returns whether the first `n` delays of `a` and `b` differ by at most `tolerance` each,
+/// for instance to compare jittered strategies.
+///
+/// A strategy that ends within the first `n` delays only matches one that ends at the same point.
+pub fn delays_within<A, B>(a: A, b: B, n: usize, tolerance: Duration) -> bool
+where
+    A: IntoIterator<Item = Duration>,
+    B: IntoIterator<Item = Duration>,
+{
+    let mut a = a.into_iter();
+    let mut b = b.into_iter();
+    for _ in 0..n {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => {
+                let difference = if x > y { x - y } else { y - x };
+                if difference > tolerance {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[test]
+fn equal_strategies_have_equal_delays() {
+    use super::{ExplicitSchedule, FixedInterval, StrategyExt};
+
+    assert!(delays_eq(
+        FixedInterval::from_millis(10).take(3),
+        ExplicitSchedule::new(vec![Duration::from_millis(10); 3]),
+        5,
+    ));
+    assert!(delays_eq(
+        FixedInterval::from_millis(10),
+        FixedInterval::from_millis(10).then(FixedInterval::from_millis(20)),
+        3,
+    ));
+}
+
+#[test]
+fn unequal_strategies_differ() {
+    use super::{ExponentialBackoff, FixedInterval, StrategyExt};
+
+    // equal up to the second delay only
+    let a = || ExponentialBackoff::from_millis(10).max_delay(Duration::from_millis(100));
+    let b = || ExponentialBackoff::from_millis(10).max_delay(Duration::from_millis(200));
+    assert!(delays_eq(a(), b(), 2));
+    assert!(!delays_eq(a(), b(), 3));
+
+    // one ends before the other
+    assert!(!delays_eq(
+        FixedInterval::from_millis(10).take(2),
+        FixedInterval::from_millis(10),
+        3,
+    ));
+}
+
+#[test]
+fn delays_within_tolerance() {
+    use super::{FixedInterval, StrategyExt};
+
+    let a = || FixedInterval::from_millis(100).take(3);
+    let b = || FixedInterval::from_millis(105).take(3);
+    assert!(delays_within(a(), b(), 3, Duration::from_millis(5)));
+    assert!(delays_within(b(), a(), 3, Duration::from_millis(5)));
+    assert!(!delays_within(a(), b(), 3, Duration::from_millis(4)));
+}
@@ -0,0 +1,84 @@
+use core::iter::Iterator;
+use core::time::Duration;
+
+/// A retry strategy that yields from a first strategy until it is exhausted,
+/// and from a second strategy afterwards.
+///
+/// See `StrategyExt::then`.
+#[derive(Debug, Clone)]
+pub struct ThenStrategy<A, B> {
+    first: Option<A>,
+    second: B,
+}
+
+impl<A, B> ThenStrategy<A, B> {
+    pub(crate) fn new(first: A, second: B) -> ThenStrategy<A, B> {
+        ThenStrategy {
+            first: Some(first),
+            second: second,
+        }
+    }
+}
+
+impl<A, B> Iterator for ThenStrategy<A, B>
+where
+    A: Iterator<Item = Duration>,
+    B: Iterator<Item = Duration>,
+{
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if let Some(ref mut first) = self.first {
+            match first.next() {
+                Some(duration) => return Some(duration),
+                None => self.first = None,
+            }
+        }
+        self.second.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (second_lower, second_upper) = self.second.size_hint();
+        match self.first {
+            Some(ref first) => {
+                let (first_lower, first_upper) = first.size_hint();
+                let upper = match (first_upper, second_upper) {
+                    (Some(first_upper), Some(second_upper)) => {
+                        first_upper.checked_add(second_upper)
+                    }
+                    _ => None,
+                };
+                (first_lower.saturating_add(second_lower), upper)
+            }
+            None => (second_lower, second_upper),
+        }
+    }
+}
+
+#[test]
+fn switches_to_second_strategy_when_first_is_exhausted() {
+    use super::{ExponentialBackoff, FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(50)
+        .take(3)
+        .then(ExponentialBackoff::from_millis(100));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(10000)));
+}
+
+#[test]
+fn ends_when_both_strategies_are_exhausted() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(50)
+        .take(1)
+        .then(FixedInterval::from_millis(100).take(1));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(50)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), None);
+}
@@ -0,0 +1,71 @@
+use core::iter::Iterator;
+use core::time::Duration;
+
+/// A retry strategy wrapper that ends once the sum of all delays would exceed a budget.
+///
+/// See `StrategyExt::total_delay_budget`.
+#[derive(Debug, Clone)]
+pub struct TotalDelayBudget<I> {
+    inner: I,
+    remaining: Option<Duration>,
+}
+
+impl<I> TotalDelayBudget<I> {
+    pub(crate) fn new(inner: I, budget: Duration) -> TotalDelayBudget<I> {
+        TotalDelayBudget {
+            inner: inner,
+            remaining: Some(budget),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for TotalDelayBudget<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let remaining = self.remaining?;
+        let duration = self.inner.next()?;
+        self.remaining = remaining.checked_sub(duration);
+        self.remaining.map(|_| duration)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(_) => (0, self.inner.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+#[test]
+fn yields_delays_summing_up_to_budget() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).total_delay_budget(Duration::from_millis(300));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn stops_before_exceeding_budget() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).total_delay_budget(Duration::from_millis(299));
+
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+    assert_eq!(s.next(), None);
+    assert_eq!(s.next(), None);
+}
+
+#[test]
+fn returns_none_when_first_delay_exceeds_budget() {
+    use super::{FixedInterval, StrategyExt};
+
+    let mut s = FixedInterval::from_millis(100).total_delay_budget(Duration::from_millis(50));
+
+    assert_eq!(s.next(), None);
+}
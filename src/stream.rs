@@ -0,0 +1,255 @@
+use std::future::Future;
+use std::iter::{IntoIterator, Iterator};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use pin_project::pin_project;
+
+use super::action::Action;
+use super::error::Error;
+use super::sleeper::{Sleeper, TokioSleeper};
+use super::strategy::Resettable;
+
+#[pin_project(project = StreamStateProj)]
+enum StreamState<A, S>
+where
+    A: Action,
+    S: Sleeper,
+{
+    Idle,
+    Running(#[pin] A::Future),
+    Sleeping(#[pin] S::Sleep),
+    Done,
+}
+
+/// Stream that yields the items produced by an action, retrying each step via a retry strategy.
+///
+/// The action resolves with `Ok(Some(item))` to yield an item, and with `Ok(None)` to end the
+/// stream. The strategy is reset after every item, so that each step is retried independently.
+/// Once the strategy is exhausted for a step, the last error is yielded and the stream ends.
+#[pin_project]
+pub struct RetryStream<I, A>
+where
+    I: Iterator<Item = Duration>,
+    A: Action,
+{
+    strategy: Resettable<I>,
+    #[pin]
+    state: StreamState<A, TokioSleeper>,
+    action: A,
+    sleeper: TokioSleeper,
+}
+
+impl<I, A> RetryStream<I, A>
+where
+    I: Iterator<Item = Duration> + Clone,
+    A: Action,
+{
+    pub fn new<T: IntoIterator<IntoIter = I, Item = Duration>>(
+        strategy: T,
+        action: A,
+    ) -> RetryStream<I, A> {
+        RetryStream {
+            strategy: Resettable::new(strategy.into_iter()),
+            state: StreamState::Idle,
+            action: action,
+            sleeper: TokioSleeper,
+        }
+    }
+}
+
+impl<I, A, T> Stream for RetryStream<I, A>
+where
+    I: Iterator<Item = Duration> + Clone,
+    A: Action<Item = Option<T>>,
+{
+    type Item = Result<T, A::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            let mut this = self.as_mut().project();
+            match this.state.as_mut().project() {
+                StreamStateProj::Idle => {
+                    let future = this.action.run();
+                    this.state.set(StreamState::Running(future));
+                }
+                StreamStateProj::Running(future) => match future.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(Some(item))) => {
+                        this.strategy.reset();
+                        this.state.set(StreamState::Idle);
+                        return Poll::Ready(Some(Ok(item)));
+                    }
+                    Poll::Ready(Ok(None)) => {
+                        this.state.set(StreamState::Done);
+                        return Poll::Ready(None);
+                    }
+                    Poll::Ready(Err(err)) => match this.strategy.next() {
+                        Some(duration) => {
+                            let future = this.sleeper.sleep(duration);
+                            this.state.set(StreamState::Sleeping(future));
+                        }
+                        None => {
+                            this.state.set(StreamState::Done);
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                    },
+                },
+                StreamStateProj::Sleeping(future) => match future.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(()) => this.state.set(StreamState::Idle),
+                },
+                StreamStateProj::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// Returns a stream that yields the items of `stream`, and retries polling it after a delay
+/// whenever it yields an error.
+///
+/// Unlike `RetryStream`, this keeps polling the same stream, for streams whose errors are
+/// transient and leave the stream usable. Each run of consecutive errors is retried with a fresh
+/// strategy from `strategy_factory`, so the back-off is reset after every successful item.
+/// Once the strategy is exhausted, the last error is yielded as `Error::RetriesExhausted`
+/// along with the number of consecutive errors, and the stream ends.
+pub fn retry_stream_items<F, T, St>(
+    strategy_factory: F,
+    stream: St,
+) -> RetryItems<F, T::IntoIter, St>
+where
+    F: FnMut() -> T,
+    T: IntoIterator<Item = Duration>,
+{
+    RetryItems {
+        strategy_factory: strategy_factory,
+        strategy: None,
+        failures: 0,
+        stream: stream,
+        sleep: None,
+        sleeper: TokioSleeper,
+        done: false,
+    }
+}
+
+/// Stream that retries the errors of another stream.
+///
+/// See `retry_stream_items`.
+#[pin_project]
+pub struct RetryItems<F, I, St> {
+    strategy_factory: F,
+    // the strategy for the current run of errors
+    strategy: Option<I>,
+    failures: usize,
+    #[pin]
+    stream: St,
+    #[pin]
+    sleep: Option<<TokioSleeper as Sleeper>::Sleep>,
+    sleeper: TokioSleeper,
+    done: bool,
+}
+
+impl<F, T, St, R, E> Stream for RetryItems<F, T::IntoIter, St>
+where
+    F: FnMut() -> T,
+    T: IntoIterator<Item = Duration>,
+    St: Stream<Item = Result<R, E>>,
+{
+    type Item = Result<R, Error<E>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            let mut this = self.as_mut().project();
+            if *this.done {
+                return Poll::Ready(None);
+            }
+            if let Some(sleep) = this.sleep.as_mut().as_pin_mut() {
+                match sleep.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(()) => this.sleep.set(None),
+                }
+            }
+            let err = match this.stream.poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => {
+                    *this.done = true;
+                    return Poll::Ready(None);
+                }
+                Poll::Ready(Some(Ok(item))) => {
+                    *this.strategy = None;
+                    *this.failures = 0;
+                    return Poll::Ready(Some(Ok(item)));
+                }
+                Poll::Ready(Some(Err(err))) => err,
+            };
+            *this.failures += 1;
+            let strategy_factory = this.strategy_factory;
+            let strategy = this
+                .strategy
+                .get_or_insert_with(|| strategy_factory().into_iter());
+            match strategy.next() {
+                Some(duration) => {
+                    let future = this.sleeper.sleep(duration);
+                    this.sleep.set(Some(future));
+                }
+                None => {
+                    *this.done = true;
+                    return Poll::Ready(Some(Err(Error::RetriesExhausted(err, *this.failures))));
+                }
+            }
+        }
+    }
+}
+
+/// Returns a stream that yields `()` after sleeping for each successive delay of the strategy,
+/// and ends when the strategy does.
+///
+/// This allows the back-off of a strategy to be reused when the caller decides what to do
+/// between the delays itself.
+pub fn delay_stream<T: IntoIterator<Item = Duration>>(strategy: T) -> DelayStream<T::IntoIter> {
+    DelayStream {
+        strategy: strategy.into_iter(),
+        sleep: None,
+        sleeper: TokioSleeper,
+    }
+}
+
+/// Stream that ticks after each delay of a retry strategy.
+///
+/// See `delay_stream`.
+#[pin_project]
+pub struct DelayStream<I> {
+    strategy: I,
+    #[pin]
+    sleep: Option<<TokioSleeper as Sleeper>::Sleep>,
+    sleeper: TokioSleeper,
+}
+
+impl<I: Iterator<Item = Duration>> Stream for DelayStream<I> {
+    type Item = ();
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<()>> {
+        let mut this = self.as_mut().project();
+        if this.sleep.is_none() {
+            match this.strategy.next() {
+                Some(duration) => {
+                    let future = this.sleeper.sleep(duration);
+                    this.sleep.set(Some(future));
+                }
+                None => return Poll::Ready(None),
+            }
+        }
+        match this.sleep.as_mut().as_pin_mut() {
+            Some(future) => match future.poll(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(()) => {
+                    this.sleep.set(None);
+                    Poll::Ready(Some(()))
+                }
+            },
+            None => Poll::Ready(None),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use tracing::{Level, Span};
+
+use crate::notify::{GiveupReason, Notify};
+
+/// Reports retries and successes as `tracing` events, within a span per retried operation.
+///
+/// Every retry is recorded as a `WARN` event with the `attempt` that failed, the `delay_ms`
+/// before the next attempt and the `error`, which is formatted using `Display`. A success is
+/// recorded as a `DEBUG` event with the total number of `attempts`.
+///
+/// See `Retry::spawn_traced`.
+#[derive(Debug)]
+pub struct Traced {
+    span: Span,
+    attempt: usize,
+}
+
+impl Traced {
+    /// Records the events within the given span.
+    pub fn new(span: Span) -> Traced {
+        Traced {
+            span: span,
+            attempt: 0,
+        }
+    }
+}
+
+impl Default for Traced {
+    fn default() -> Traced {
+        Traced::new(tracing::info_span!("retry"))
+    }
+}
+
+impl<E: Display> Notify<E> for Traced {
+    fn notify(&mut self, error: &E, duration: Duration) {
+        self.attempt += 1;
+        tracing::event!(
+            parent: &self.span,
+            Level::WARN,
+            attempt = self.attempt,
+            delay_ms = duration.as_millis() as u64,
+            error = %error,
+            "retrying after error"
+        );
+    }
+
+    fn success(&mut self, attempts: usize) {
+        tracing::event!(
+            parent: &self.span,
+            Level::DEBUG,
+            attempts = attempts,
+            "succeeded"
+        );
+    }
+
+    fn give_up(&mut self, attempts: usize, reason: GiveupReason) {
+        tracing::event!(
+            parent: &self.span,
+            Level::WARN,
+            attempts = attempts,
+            reason = ?reason,
+            "gave up"
+        );
+    }
+}
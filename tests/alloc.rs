@@ -0,0 +1,56 @@
+#![cfg(feature = "tokio")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::future::{self, Future};
+use std::iter;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+use futures::task::noop_waker;
+use tokio_retry::Retry;
+
+/// Counts the allocations made by the current thread while counting is enabled.
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+}
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn empty_strategy_attempts_once_without_allocating() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let attempts = Cell::new(0);
+
+    COUNTING.with(|counting| counting.set(true));
+    let mut future = pin!(Retry::spawn(iter::empty(), || {
+        attempts.set(attempts.get() + 1);
+        future::ready(Err::<(), u64>(42))
+    }));
+    let res = future.as_mut().poll(&mut cx);
+    COUNTING.with(|counting| counting.set(false));
+
+    assert_eq!(res, Poll::Ready(Err(42)));
+    assert_eq!(attempts.get(), 1);
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), 0);
+}
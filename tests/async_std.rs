@@ -0,0 +1,32 @@
+#![cfg(feature = "async-std")]
+
+use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio_retry::strategy::FixedInterval;
+use tokio_retry::{AsyncStdSleeper, Retry};
+
+#[test]
+fn retries_on_async_std_runtime() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = Instant::now();
+    let future = Retry::spawn_with_sleeper(
+        FixedInterval::from_millis(10),
+        move || {
+            if cloned_counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                future::ready(Err::<(), u64>(42))
+            } else {
+                future::ready(Ok::<(), u64>(()))
+            }
+        },
+        AsyncStdSleeper,
+    );
+    let res = async_std::task::block_on(future);
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "tokio")]
+
+use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio_retry::strategy::FixedInterval;
+use tokio_retry::RetryBuilder;
+
+#[tokio::test]
+async fn applies_configuration_from_builder() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let mut delays = Vec::new();
+    let future = RetryBuilder::new()
+        .strategy(FixedInterval::from_millis(10))
+        .max_delay(Duration::from_millis(5))
+        .max_retries(2)
+        .retry_if(|e: &u64| *e == 42)
+        .notify(|_: &u64, duration| delays.push(duration))
+        .spawn(move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        });
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(
+        delays,
+        vec![Duration::from_millis(5), Duration::from_millis(5)]
+    );
+}
+
+#[tokio::test]
+async fn stops_when_builder_condition_declines() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryBuilder::new()
+        .strategy(FixedInterval::from_millis(10))
+        .retry_if(|e: &u64| *e != 42)
+        .spawn(move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        });
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn applies_condition_and_cap_from_policy() {
+    use tokio_retry::{Policy, Retry};
+
+    let delays = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let cloned_delays = delays.clone();
+    let policy = Policy::new(|| FixedInterval::from_millis(10))
+        .retry_if(|e: &u64| *e != 0)
+        .notify(move |_: &u64, duration| cloned_delays.lock().unwrap().push(duration))
+        .max_delay(Duration::from_millis(5))
+        .max_retries(2);
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_with_policy(&policy, move || {
+        cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(Err::<(), u64>(42))
+    })
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(
+        *delays.lock().unwrap(),
+        vec![Duration::from_millis(5), Duration::from_millis(5)]
+    );
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_with_policy(&policy, move || {
+        cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(Err::<(), u64>(0))
+    })
+    .await;
+
+    assert_eq!(res, Err(0));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
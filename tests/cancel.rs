@@ -0,0 +1,67 @@
+#![cfg(feature = "cancel")]
+
+use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::time::{sleep, Duration, Instant};
+use tokio_retry::strategy::FixedInterval;
+use tokio_retry::{Error, Retry};
+use tokio_util::sync::CancellationToken;
+
+#[tokio::test(start_paused = true)]
+async fn cancelling_during_sleep_returns_promptly() {
+    let token = CancellationToken::new();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_cancellable(
+        FixedInterval::new(Duration::from_secs(60)),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        token.clone(),
+    );
+    let start = Instant::now();
+    let cancel = async {
+        sleep(Duration::from_secs(1)).await;
+        token.cancel();
+    };
+    let (res, ()) = tokio::join!(future, cancel);
+
+    assert_eq!(res, Err(Error::Cancelled));
+    assert_eq!(start.elapsed(), Duration::from_secs(1));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn cancelled_token_skips_attempts() {
+    let token = CancellationToken::new();
+    token.cancel();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_cancellable(
+        FixedInterval::from_millis(10),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<(), u64>(()))
+        },
+        token,
+    )
+    .await;
+
+    assert_eq!(res, Err(Error::Cancelled));
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn reports_action_errors_when_not_cancelled() {
+    let res = Retry::spawn_cancellable(
+        FixedInterval::from_millis(1).take(2),
+        || future::ready(Err::<(), u64>(42)),
+        CancellationToken::new(),
+    )
+    .await;
+
+    assert_eq!(res, Err(Error::OperationError(42)));
+}
@@ -0,0 +1,283 @@
+#![cfg(feature = "std")]
+
+use std::future;
+use std::sync::{Arc, Mutex};
+
+use std::time::{Duration, Instant, SystemTime};
+use tokio_retry::strategy::{ExponentialBackoff, FixedInterval, RetryLimits, StrategyExt};
+use tokio_retry::{BreakerAction, CircuitBreaker, Clock, Error, Retry, RetryIf, Sleeper};
+
+/// A virtual clock, whose sleeps advance time right away instead of waiting.
+#[derive(Clone)]
+struct MockClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl MockClock {
+    fn new() -> MockClock {
+        MockClock {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+impl Sleeper for MockClock {
+    type Sleep = future::Ready<()>;
+
+    fn sleep(&mut self, duration: Duration) -> Self::Sleep {
+        self.advance(duration);
+        future::ready(())
+    }
+
+    fn now(&self) -> Instant {
+        Clock::now(self)
+    }
+}
+
+#[test]
+fn deadline_follows_mock_clock() {
+    let clock = MockClock::new();
+    let s = FixedInterval::from_millis(30)
+        .deadline(Duration::from_millis(100))
+        .with_clock(clock.clone());
+
+    let mut delays = Vec::new();
+    for delay in s {
+        clock.advance(delay);
+        delays.push(delay);
+    }
+
+    assert_eq!(
+        delays,
+        vec![
+            Duration::from_millis(30),
+            Duration::from_millis(30),
+            Duration::from_millis(30),
+            Duration::from_millis(10),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn spaces_attempts_on_mock_clock() {
+    let clock = MockClock::new();
+    let start = Clock::now(&clock);
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+
+    let cloned_clock = clock.clone();
+    let cloned_attempts = attempts.clone();
+    let res = Retry::spawn_with_sleeper(
+        FixedInterval::from_millis(10).take(3),
+        move || {
+            let elapsed = Clock::now(&cloned_clock) - start;
+            cloned_attempts.lock().unwrap().push(elapsed);
+            future::ready(Err::<(), u64>(42))
+        },
+        clock.clone(),
+    )
+    .min_attempt_spacing(Duration::from_millis(100))
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(
+        *attempts.lock().unwrap(),
+        vec![
+            Duration::ZERO,
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+            Duration::from_millis(300),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn retries_until_deadline_of_mock_clock() {
+    let clock = MockClock::new();
+    let start = Clock::now(&clock);
+
+    let cloned_clock = clock.clone();
+    let res = Retry::spawn_with_sleeper(
+        FixedInterval::new(Duration::from_secs(30))
+            .deadline(Duration::from_secs(100))
+            .with_clock(clock.clone()),
+        || future::ready(Err::<(), u64>(42)),
+        clock.clone(),
+    )
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(Clock::now(&cloned_clock) - start, Duration::from_secs(100));
+}
+
+#[test]
+fn limits_follow_mock_clock() {
+    let clock = MockClock::new();
+    let limits = RetryLimits {
+        max_elapsed: Some(Duration::from_millis(100)),
+        ..RetryLimits::default()
+    };
+    let s = FixedInterval::from_millis(30)
+        .limited_by(limits)
+        .with_clock(clock.clone());
+
+    let mut delays = Vec::new();
+    for delay in s {
+        clock.advance(delay);
+        delays.push(delay);
+    }
+
+    assert_eq!(
+        delays,
+        vec![
+            Duration::from_millis(30),
+            Duration::from_millis(30),
+            Duration::from_millis(30),
+            Duration::from_millis(10),
+        ]
+    );
+}
+
+#[test]
+fn resets_after_healthy_period_of_mock_clock() {
+    let clock = MockClock::new();
+    let mut s = ExponentialBackoff::from_millis(10)
+        .reset_after(Duration::from_secs(60))
+        .with_clock(clock.clone());
+
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+    clock.advance(Duration::from_millis(10));
+    assert_eq!(s.next(), Some(Duration::from_millis(100)));
+
+    clock.advance(Duration::from_millis(100) + Duration::from_secs(61));
+    assert_eq!(s.next(), Some(Duration::from_millis(10)));
+}
+
+#[tokio::test]
+async fn breaker_stays_open_for_duration_of_mock_clock() {
+    let clock = MockClock::new();
+    let breaker = CircuitBreaker::new(1, Duration::from_secs(10)).with_clock(clock.clone());
+
+    let res = Retry::spawn_with_sleeper(
+        FixedInterval::new(Duration::from_secs(1)).take(3),
+        BreakerAction::new(|| future::ready(Err::<(), u64>(42)), breaker.clone()),
+        clock.clone(),
+    )
+    .await;
+
+    // the breaker opens after the first failure, and stays open while the retry sleeps
+    assert_eq!(res, Err(Error::CircuitOpen));
+    assert!(breaker.is_open());
+
+    clock.advance(Duration::from_secs(10));
+    assert!(!breaker.is_open());
+}
+
+#[tokio::test]
+async fn times_retry_on_mock_clock() {
+    let clock = MockClock::new();
+    let attempts = Arc::new(Mutex::new(0));
+
+    let cloned_attempts = attempts.clone();
+    let res = Retry::spawn_with_sleeper(
+        FixedInterval::new(Duration::from_secs(30)),
+        move || {
+            let mut attempts = cloned_attempts.lock().unwrap();
+            *attempts += 1;
+            future::ready(if *attempts < 3 {
+                Err(42)
+            } else {
+                Ok(*attempts)
+            })
+        },
+        clock.clone(),
+    )
+    .timed()
+    .await;
+
+    assert_eq!(res, Ok((3, Duration::from_secs(60))));
+}
+
+#[tokio::test]
+async fn gives_context_condition_elapsed_time_of_mock_clock() {
+    let clock = MockClock::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let cloned_seen = seen.clone();
+    let res = RetryIf::spawn_ctx_with_sleeper(
+        FixedInterval::new(Duration::from_secs(30)).take(2),
+        || future::ready(Err::<(), u64>(42)),
+        move |_: &u64, attempt: usize, elapsed: Duration| {
+            cloned_seen.lock().unwrap().push((attempt, elapsed));
+            true
+        },
+        clock.clone(),
+    )
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            (1, Duration::ZERO),
+            (2, Duration::from_secs(30)),
+            (3, Duration::from_secs(60)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn retries_until_wall_clock_deadline_on_mock_clock() {
+    let clock = MockClock::new();
+    let start = Clock::now(&clock);
+
+    let res = Retry::spawn_before_with_sleeper(
+        FixedInterval::new(Duration::from_secs(30)),
+        || future::ready(Err::<(), u64>(42)),
+        SystemTime::now() + Duration::from_secs(100),
+        clock.clone(),
+    )
+    .detailed()
+    .await;
+
+    assert_eq!(res, Err(Error::RetriesExhausted(42, 5)));
+    let elapsed = Clock::now(&clock) - start;
+    assert!(elapsed <= Duration::from_secs(100));
+    assert!(elapsed > Duration::from_secs(99));
+}
+
+/// A sleeper without a clock, like a timer in the browser.
+struct NoClock;
+
+impl Sleeper for NoClock {
+    type Sleep = future::Ready<()>;
+
+    fn sleep(&mut self, _duration: Duration) -> Self::Sleep {
+        future::ready(())
+    }
+
+    fn now(&self) -> Instant {
+        panic!("the clock was read")
+    }
+}
+
+#[tokio::test]
+async fn retries_without_reading_clock_of_sleeper() {
+    let res = Retry::spawn_with_sleeper(
+        FixedInterval::from_millis(10).take(3),
+        || future::ready(Err::<(), u64>(42)),
+        NoClock,
+    )
+    .await;
+
+    assert_eq!(res, Err(42));
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "tokio")]
+
 use std::future;
 use std::iter::Take;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -95,3 +97,2733 @@ async fn attempts_retry_only_if_given_condition_is_true() {
     assert_eq!(res, Err(3));
     assert_eq!(counter.load(Ordering::SeqCst), 3);
 }
+
+#[tokio::test]
+async fn attempts_retry_on_ok_until_condition_is_false() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(100).take(5);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn_on(
+        s,
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<usize, ()>(previous + 1))
+        },
+        |_: &()| true,
+        |pending: &usize| *pending < 3,
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn returns_last_ok_when_retries_exhausted_on_ok() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(100).take(2);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn_on(
+        s,
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<usize, ()>(previous + 1))
+        },
+        |_: &()| true,
+        |_: &usize| true,
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn resolves_with_fallback_for_matching_error() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(100).take(5);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_with_fallbacks(
+        s,
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<&str, usize>(previous))
+        },
+        |e: &usize| if *e == 2 { Some("fallback") } else { None },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok("fallback"));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn resettable_strategy_starts_from_base_after_success() {
+    use tokio_retry::strategy::{FixedInterval, Resettable};
+    let mut s = Resettable::new(FixedInterval::from_millis(10).take(2));
+    let counter = Arc::new(AtomicUsize::new(0));
+    for _ in 0..2 {
+        let cloned_counter = counter.clone();
+        let res = Retry::spawn(&mut s, move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            if previous % 3 < 2 {
+                future::ready(Err::<(), u64>(42))
+            } else {
+                future::ready(Ok::<(), u64>(()))
+            }
+        })
+        .await;
+        assert_eq!(res, Ok(()));
+        s.reset();
+    }
+
+    assert_eq!(counter.load(Ordering::SeqCst), 6);
+}
+
+#[tokio::test(start_paused = true)]
+async fn delays_first_attempt_when_spawned_after() {
+    use std::iter::empty;
+    use tokio::time::{timeout, Duration, Instant};
+    let start = Instant::now();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_after(Duration::from_secs(1), empty(), move || {
+        cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(Ok::<(), u64>(()))
+    });
+    tokio::pin!(future);
+
+    assert!(timeout(Duration::from_millis(500), &mut future)
+        .await
+        .is_err());
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+    let res = future.await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+    assert_eq!(start.elapsed(), Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn resumes_with_initial_sleep_and_resumed_strategy() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::strategy::ExponentialBackoff;
+    let start = Instant::now();
+    let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let cloned_attempts = attempts.clone();
+    let strategy = ExponentialBackoff::with_current(10, 100, 1);
+    let future = Retry::resume(
+        strategy,
+        move || {
+            let mut attempts = cloned_attempts.lock().unwrap();
+            attempts.push(start.elapsed());
+            future::ready(if attempts.len() < 3 { Err(()) } else { Ok(()) })
+        },
+        Duration::from_millis(50),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(
+        *attempts.lock().unwrap(),
+        vec![
+            Duration::from_millis(50),
+            Duration::from_millis(150),
+            Duration::from_millis(1150),
+        ]
+    );
+}
+
+#[derive(Clone, Default)]
+struct RecordingSleeper {
+    delays: Arc<std::sync::Mutex<Vec<std::time::Duration>>>,
+}
+
+impl tokio_retry::Sleeper for RecordingSleeper {
+    type Sleep = future::Ready<()>;
+
+    fn sleep(&mut self, duration: std::time::Duration) -> Self::Sleep {
+        self.delays.lock().unwrap().push(duration);
+        future::ready(())
+    }
+}
+
+#[tokio::test]
+async fn requests_delays_from_sleeper() {
+    use std::time::Duration;
+    use tokio_retry::strategy::ExponentialBackoff;
+    let s = ExponentialBackoff::from_millis(10).take(3);
+    let sleeper = RecordingSleeper::default();
+    let future =
+        Retry::spawn_with_sleeper(s, || future::ready(Err::<(), u64>(42)), sleeper.clone());
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(
+        *sleeper.delays.lock().unwrap(),
+        vec![
+            Duration::from_millis(10),
+            Duration::from_millis(100),
+            Duration::from_millis(1000)
+        ]
+    );
+}
+
+struct Countdown(usize);
+
+impl std::future::Future for Countdown {
+    type Output = Result<(), usize>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context,
+    ) -> std::task::Poll<Self::Output> {
+        if self.0 == 0 {
+            std::task::Poll::Ready(Ok(()))
+        } else {
+            std::task::Poll::Ready(Err(self.0))
+        }
+    }
+}
+
+#[tokio::test]
+async fn accepts_hand_written_future_actions() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(10);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn(s, move || {
+        let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        Countdown(2usize.saturating_sub(previous))
+    });
+    let res = future.await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn retries_synchronous_closure() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(10);
+    let mut counter = 0;
+    let future = Retry::spawn_fn_sync(s, || {
+        counter += 1;
+        if counter < 3 {
+            Err(42)
+        } else {
+            Ok(counter)
+        }
+    });
+    let res = future.await;
+
+    assert_eq!(res, Ok(3));
+}
+
+#[tokio::test]
+async fn reports_retries_exhausted_with_attempts() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let s = FixedInterval::from_millis(10).take(2);
+    let future = Retry::spawn(s, || future::ready(Err::<(), u64>(42))).detailed();
+    let res = future.await;
+
+    assert_eq!(res, Err(Error::RetriesExhausted(42, 3)));
+}
+
+#[tokio::test]
+async fn reports_attempt_of_exhausted_retry() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let s = FixedInterval::from_millis(10).take(3);
+    let err = Retry::spawn(s, || future::ready(Err::<(), u64>(42)))
+        .detailed()
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.attempt(), Some(4));
+    let inner = match err {
+        Error::OperationError(err) | Error::RetriesExhausted(err, _) => Some(err),
+        _ => None,
+    };
+    assert_eq!(inner, Some(42));
+}
+
+#[tokio::test]
+async fn reports_operation_error_when_condition_declines() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let s = FixedInterval::from_millis(10).take(5);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn(
+        s,
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), usize>(previous + 1))
+        },
+        |e: &usize| *e < 2,
+    )
+    .detailed();
+    let res = future.await;
+
+    assert_eq!(res, Err(Error::OperationError(2)));
+}
+
+#[tokio::test]
+async fn matches_operation_error_by_its_single_field() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let s = FixedInterval::from_millis(10).take(5);
+    let err = RetryIf::spawn(s, || future::ready(Err::<(), u64>(42)), |_: &u64| false)
+        .detailed()
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.attempt(), None);
+    let inner = match err {
+        Error::OperationError(err) => err,
+        Error::RetriesExhausted(err, _) => err,
+        Error::CircuitOpen | Error::Cancelled | Error::Aborted | Error::AllFailed(_) => 0,
+    };
+    assert_eq!(inner, 42);
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_once_context_condition_elapsed_exceeds_bound() {
+    use std::time::Duration;
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(100);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let mut attempts = Vec::new();
+    let future = RetryIf::spawn_ctx(
+        s,
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        |_: &u64, attempt: usize, elapsed: Duration| {
+            attempts.push(attempt);
+            elapsed < Duration::from_millis(250)
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+    assert_eq!(attempts, vec![1, 2, 3, 4]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn context_condition_is_given_attempts_and_elapsed_time_of_run_loop() {
+    use std::time::Duration;
+    use tokio_retry::strategy::FixedInterval;
+
+    // time before the retry is created does not count
+    tokio::time::advance(Duration::from_secs(60)).await;
+
+    let mut contexts = Vec::new();
+    let future = RetryIf::spawn_ctx(
+        FixedInterval::from_millis(100),
+        || async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Err::<(), u64>(42)
+        },
+        |_: &u64, attempt: usize, elapsed: Duration| {
+            contexts.push((attempt, elapsed));
+            attempt < 3
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(
+        contexts,
+        vec![
+            (1, Duration::from_millis(10)),
+            (2, Duration::from_millis(120)),
+            (3, Duration::from_millis(230)),
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn continue_after_does_not_consume_strategy_step() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::strategy::ExponentialBackoff;
+    use tokio_retry::RetryDecision;
+    let start = Instant::now();
+    let s = ExponentialBackoff::from_millis(10).take(2);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let mut decisions = 0;
+    let future = RetryIf::spawn_decide(
+        s,
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        |_: &u64| {
+            decisions += 1;
+            if decisions == 1 {
+                RetryDecision::ContinueAfter(Duration::from_millis(5))
+            } else {
+                RetryDecision::Continue
+            }
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+    assert_eq!(start.elapsed(), Duration::from_millis(5 + 10 + 100));
+}
+
+#[tokio::test(start_paused = true)]
+async fn continue_after_respects_max_retries() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{Error, RetryDecision};
+    let start = Instant::now();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn_decide(
+        FixedInterval::from_millis(10),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        |_: &u64| RetryDecision::ContinueAfter(Duration::from_millis(5)),
+    )
+    .max_retries(3)
+    .detailed();
+    let res = future.await;
+
+    assert_eq!(res, Err(Error::RetriesExhausted(42, 4)));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+    assert_eq!(start.elapsed(), Duration::from_millis(3 * 5));
+}
+
+#[tokio::test]
+async fn stop_decision_resolves_immediately() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::RetryDecision;
+    let s = FixedInterval::from_millis(10);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn_decide(
+        s,
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        |_: &u64| RetryDecision::Stop,
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn retry_function_can_be_spawned() {
+    use tokio_retry::retry;
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(10);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let handle = tokio::spawn(retry(s, move || {
+        let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if previous < 2 {
+                Err::<usize, u64>(42)
+            } else {
+                Ok::<usize, u64>(previous)
+            }
+        }
+    }));
+    let res = handle.await.unwrap();
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_function_reports_exhaustion() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{retry, Error};
+    let s = FixedInterval::from_millis(10).take(2);
+    let res = retry(s, || future::ready(Err::<(), u64>(42))).await;
+
+    assert_eq!(res, Err(Error::RetriesExhausted(42, 3)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_notify_notifies_before_every_retry() {
+    use tokio::time::Duration;
+    use tokio_retry::retry_notify;
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let mut notified = Vec::new();
+
+    let res = retry_notify(
+        FixedInterval::from_millis(10).take(5),
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(if previous < 2 {
+                Err::<usize, u64>(42)
+            } else {
+                Ok(previous)
+            })
+        },
+        |err: &u64, dur| notified.push((*err, dur)),
+    )
+    .await;
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(
+        notified,
+        vec![
+            (42, Duration::from_millis(10)),
+            (42, Duration::from_millis(10))
+        ]
+    );
+}
+
+#[tokio::test]
+async fn retry_future_can_be_spawned() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(10).take(5);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let handle = tokio::spawn(Retry::spawn(s, move || {
+        let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            tokio::task::yield_now().await;
+            if previous < 2 {
+                Err::<usize, u64>(42)
+            } else {
+                Ok::<usize, u64>(previous)
+            }
+        }
+    }));
+    let res = handle.await.unwrap();
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn can_be_dropped_while_sleeping() {
+    use tokio::time::{advance, sleep, timeout, Duration};
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(100);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn(s, move || {
+        cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(Err::<(), u64>(42))
+    });
+
+    assert!(timeout(Duration::from_millis(50), future).await.is_err());
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    advance(Duration::from_secs(1)).await;
+    sleep(Duration::from_millis(1)).await;
+
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: std::sync::Mutex<Vec<String>>,
+}
+
+impl tokio_retry::RetryObserver<u64> for RecordingObserver {
+    fn on_attempt(&self, attempt: usize) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("attempt {}", attempt));
+    }
+
+    fn on_retry(&self, error: &u64, delay: std::time::Duration) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("retry {} after {:?}", error, delay));
+    }
+
+    fn on_success(&self, attempts: usize) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("success after {}", attempts));
+    }
+
+    fn on_giveup(&self, attempts: usize, _reason: tokio_retry::GiveupReason) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("giveup after {}", attempts));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn reports_progress_to_observer() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(100);
+    let observer = RecordingObserver::default();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_observed(
+        s,
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            if previous < 2 {
+                future::ready(Err::<(), u64>(42))
+            } else {
+                future::ready(Ok::<(), u64>(()))
+            }
+        },
+        &observer,
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(
+        *observer.events.lock().unwrap(),
+        vec![
+            "attempt 1",
+            "retry 42 after 100ms",
+            "attempt 2",
+            "retry 42 after 100ms",
+            "attempt 3",
+            "success after 3",
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn reports_giveup_to_observer() {
+    use tokio_retry::strategy::FixedInterval;
+    let s = FixedInterval::from_millis(100).take(1);
+    let observer = RecordingObserver::default();
+    let future = Retry::spawn_observed(s, || future::ready(Err::<(), u64>(42)), &observer);
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(
+        *observer.events.lock().unwrap(),
+        vec![
+            "attempt 1",
+            "retry 42 after 100ms",
+            "attempt 2",
+            "giveup after 2",
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn limited_retries_do_not_overlap_attempts() {
+    use tokio::sync::Semaphore;
+    use tokio::time::{sleep, Duration};
+    use tokio_retry::strategy::FixedInterval;
+    let permits = Arc::new(Semaphore::new(1));
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+    let spawn = || {
+        let in_flight = in_flight.clone();
+        let max_in_flight = max_in_flight.clone();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        Retry::spawn_limited(
+            FixedInterval::from_millis(5),
+            move || {
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                let previous = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(current, Ordering::SeqCst);
+                    sleep(Duration::from_millis(10)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    if previous < 2 {
+                        Err::<usize, ()>(())
+                    } else {
+                        Ok(previous)
+                    }
+                }
+            },
+            permits.clone(),
+        )
+    };
+    let (first, second) = tokio::join!(spawn(), spawn());
+
+    assert_eq!(first, Ok(2));
+    assert_eq!(second, Ok(2));
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+    assert_eq!(permits.available_permits(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn limited_retry_starts_attempt_only_once_permit_is_acquired() {
+    use tokio::sync::Semaphore;
+    use tokio::time::{sleep, Duration};
+    use tokio_retry::strategy::FixedInterval;
+    let permits = Arc::new(Semaphore::new(1));
+    let started = Arc::new(AtomicUsize::new(0));
+    let permit = permits.clone().acquire_owned().await.unwrap();
+
+    let cloned_started = started.clone();
+    let handle = tokio::spawn(Retry::spawn_limited(
+        FixedInterval::from_millis(5),
+        move || {
+            cloned_started.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<(), ()>(()))
+        },
+        permits.clone(),
+    ));
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(started.load(Ordering::SeqCst), 0);
+
+    drop(permit);
+    assert_eq!(handle.await.unwrap(), Ok(()));
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn limited_retry_keeps_action_state_across_attempts() {
+    use tokio::sync::Semaphore;
+    use tokio_retry::strategy::FixedInterval;
+    let permits = Arc::new(Semaphore::new(1));
+
+    let mut attempts = 0;
+    let res = Retry::spawn_limited(
+        FixedInterval::from_millis(5).take(5),
+        move || {
+            attempts += 1;
+            future::ready(if attempts < 3 {
+                Err::<usize, usize>(attempts)
+            } else {
+                Ok(attempts)
+            })
+        },
+        permits.clone(),
+    )
+    .await;
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(permits.available_permits(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stops_retrying_once_budget_is_drained() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::RetryBudget;
+    let budget = RetryBudget::new(3.0, 0.5);
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let cloned_counter = counter.clone();
+    let first = Retry::spawn_with_budget(
+        FixedInterval::from_millis(10).take(2),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        budget.clone(),
+    );
+    assert_eq!(first.await, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+
+    let cloned_counter = counter.clone();
+    let second = Retry::spawn_with_budget(
+        FixedInterval::from_millis(10).take(2),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        budget.clone(),
+    );
+    assert_eq!(second.await, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 5);
+
+    let cloned_counter = counter.clone();
+    let third = Retry::spawn_with_budget(
+        FixedInterval::from_millis(10).take(2),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        budget.clone(),
+    );
+    assert_eq!(third.await, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 6);
+    assert_eq!(budget.tokens(), 0.0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_retries_do_not_overdraw_budget() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::RetryBudget;
+    let budget = RetryBudget::new(5.0, 0.5);
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..16)
+        .map(|_| {
+            let counter = counter.clone();
+            tokio::spawn(Retry::spawn_with_budget(
+                FixedInterval::from_millis(1).take(10),
+                move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    future::ready(Err::<(), u64>(42))
+                },
+                budget.clone(),
+            ))
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.await.unwrap(), Err(42));
+    }
+
+    // one attempt per retry, plus one retry per token
+    assert_eq!(counter.load(Ordering::SeqCst), 16 + 5);
+    assert_eq!(budget.tokens(), 0.0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn successes_refill_budget() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::RetryBudget;
+    let budget = RetryBudget::new(2.0, 1.0);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_with_budget(
+        FixedInterval::from_millis(10),
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            if previous < 1 {
+                future::ready(Err::<(), u64>(42))
+            } else {
+                future::ready(Ok::<(), u64>(()))
+            }
+        },
+        budget.clone(),
+    );
+
+    assert_eq!(future.await, Ok(()));
+    assert_eq!(budget.tokens(), 2.0);
+}
+
+static ASYNC_FN_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+async fn fails_twice() -> Result<usize, u64> {
+    let calls = ASYNC_FN_CALLS.fetch_add(1, Ordering::SeqCst) + 1;
+    if calls < 3 {
+        Err(42)
+    } else {
+        Ok(calls)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_async_fn_through_boxed_action() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::BoxedAction;
+
+    let future: Retry<Take<FixedInterval>, BoxedAction<usize, u64>> = Retry::spawn(
+        FixedInterval::from_millis(10).take(3),
+        BoxedAction::from_async_fn(fails_twice),
+    );
+    assert_eq!(future.await, Ok(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn adaptive_backoff_grows_on_failures_and_decays_on_success() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::strategy::AdaptiveBackoff;
+    let backoff = AdaptiveBackoff::new(Duration::from_millis(100), Duration::from_secs(10));
+    let failures = Arc::new(AtomicUsize::new(2));
+
+    let cloned_failures = failures.clone();
+    let action = move || {
+        let failed = cloned_failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        future::ready(if failed { Err::<(), u64>(42) } else { Ok(()) })
+    };
+
+    let start = Instant::now();
+    assert_eq!(
+        Retry::spawn_adaptive(backoff.clone(), action.clone()).await,
+        Ok(())
+    );
+    assert_eq!(start.elapsed(), Duration::from_millis(300));
+    // grown to 400ms by the two failures, and decayed by the success
+    assert_eq!(backoff.current_delay(), Duration::from_millis(200));
+
+    failures.store(1, Ordering::SeqCst);
+    let start = Instant::now();
+    assert_eq!(
+        Retry::spawn_adaptive(backoff.clone(), action.clone()).await,
+        Ok(())
+    );
+    assert_eq!(start.elapsed(), Duration::from_millis(200));
+    assert_eq!(backoff.current_delay(), Duration::from_millis(200));
+
+    assert_eq!(Retry::spawn_adaptive(backoff.clone(), action).await, Ok(()));
+    assert_eq!(backoff.current_delay(), Duration::from_millis(100));
+}
+
+#[tokio::test(start_paused = true)]
+async fn adaptive_backoff_grows_on_failure_it_gives_up_after() {
+    use tokio::time::Duration;
+    use tokio_retry::strategy::AdaptiveBackoff;
+    let backoff = AdaptiveBackoff::new(Duration::from_millis(100), Duration::from_secs(10));
+
+    let res = Retry::spawn_adaptive(backoff.clone().max_retries(1), || {
+        future::ready(Err::<(), u64>(42))
+    })
+    .await;
+
+    assert_eq!(res, Err(42));
+    // grown by the retried failure, and by the last one
+    assert_eq!(backoff.current_delay(), Duration::from_millis(400));
+}
+
+#[tokio::test(start_paused = true)]
+async fn cloned_plan_starts_each_run_from_base_delay() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::strategy::ExponentialBackoff;
+    use tokio_retry::RetryPlan;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let plan = RetryPlan::new(ExponentialBackoff::from_millis(10).take(2), move || {
+        let counter = cloned_counter.clone();
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        }
+    });
+    let cloned_plan = plan.clone();
+
+    for plan in &[plan, cloned_plan] {
+        let start = Instant::now();
+        let res = plan.run().await;
+
+        assert_eq!(res, Err(42));
+        assert_eq!(start.elapsed(), Duration::from_millis(110));
+    }
+    assert_eq!(counter.load(Ordering::SeqCst), 6);
+}
+
+#[tokio::test]
+async fn action_can_borrow_local_state() {
+    use std::cell::Cell;
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Cell::new(0);
+    let future = Retry::spawn(FixedInterval::from_millis(1).take(5), || {
+        counter.set(counter.get() + 1);
+        future::ready(if counter.get() < 3 { Err(()) } else { Ok(()) })
+    });
+    let res = future.await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(counter.get(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn reusable_strategy_starts_each_retry_from_base() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::strategy::ExponentialBackoff;
+    let template = ExponentialBackoff::from_millis(10).take(3);
+
+    for _ in 0..3 {
+        let start = Instant::now();
+        let res = Retry::spawn_reusable(&template, || future::ready(Err::<(), u64>(42))).await;
+
+        assert_eq!(res, Err(42));
+        assert_eq!(start.elapsed(), Duration::from_millis(1110));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_when_attempt_runs_past_deadline() {
+    use tokio::time::{sleep, Duration, Instant};
+    use tokio_retry::strategy::FixedInterval;
+    let start = Instant::now();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_with_deadline(
+        FixedInterval::from_millis(10),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            async {
+                sleep(Duration::from_millis(30)).await;
+                Err::<(), u64>(42)
+            }
+        },
+        start + Duration::from_millis(50),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+    assert_eq!(start.elapsed(), Duration::from_millis(70));
+}
+
+#[tokio::test(start_paused = true)]
+async fn shortens_last_delay_to_deadline() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::strategy::FixedInterval;
+    let start = Instant::now();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_with_deadline(
+        FixedInterval::from_millis(40),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        start + Duration::from_millis(50),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(start.elapsed(), Duration::from_millis(50));
+}
+
+#[test]
+fn sleeps_on_given_runtime_handle() {
+    use tokio_retry::strategy::FixedInterval;
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let handle = runtime.handle().clone();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+
+    let res = std::thread::spawn(move || {
+        let future =
+            Retry::spawn_on_handle(&handle, FixedInterval::from_millis(1).take(5), move || {
+                let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+                if previous < 2 {
+                    future::ready(Err::<usize, u64>(42))
+                } else {
+                    future::ready(Ok::<usize, u64>(previous))
+                }
+            });
+        futures::executor::block_on(future)
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn open_circuit_breaker_short_circuits_attempts() {
+    use tokio::time::{advance, Duration};
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{CircuitBreaker, Error};
+    let breaker = CircuitBreaker::new(3, Duration::from_secs(10));
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_with_breaker(
+        FixedInterval::from_millis(10).take(5),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        breaker.clone(),
+    )
+    .await;
+
+    assert_eq!(res, Err(Error::CircuitOpen));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert!(breaker.is_open());
+
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_with_breaker(
+        FixedInterval::from_millis(10).take(5),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<(), u64>(()))
+        },
+        breaker.clone(),
+    )
+    .await;
+
+    assert_eq!(res, Err(Error::CircuitOpen));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+
+    advance(Duration::from_secs(10)).await;
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_with_breaker(
+        FixedInterval::from_millis(10).take(5),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<(), u64>(()))
+        },
+        breaker.clone(),
+    )
+    .await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+    assert!(!breaker.is_open());
+}
+
+#[tokio::test(start_paused = true)]
+async fn closed_circuit_breaker_reports_last_error() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{CircuitBreaker, Error};
+    let breaker = CircuitBreaker::new(10, std::time::Duration::from_secs(10));
+    let res = Retry::spawn_with_breaker(
+        FixedInterval::from_millis(10).take(2),
+        || future::ready(Err::<(), u64>(42)),
+        breaker.clone(),
+    )
+    .await;
+
+    assert_eq!(res, Err(Error::OperationError(42)));
+    assert!(!breaker.is_open());
+}
+
+#[tokio::test(start_paused = true)]
+async fn reports_elapsed_time_including_attempts_and_delays() {
+    use tokio::time::{sleep, Duration};
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_timed(FixedInterval::from_millis(100), move || {
+        let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            sleep(Duration::from_millis(30)).await;
+            if previous < 2 {
+                Err::<usize, u64>(42)
+            } else {
+                Ok(previous)
+            }
+        }
+    });
+    let res = future.await;
+
+    assert_eq!(res, Ok((2, Duration::from_millis(3 * 30 + 2 * 100))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn timed_retry_reports_detailed_error() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let future = Retry::spawn_timed(FixedInterval::from_millis(100).take(1), || {
+        future::ready(Err::<(), u64>(42))
+    });
+    let res = future.await;
+
+    assert_eq!(res, Err(Error::RetriesExhausted(42, 2)));
+}
+
+#[tokio::test]
+async fn retries_only_errors_matching_one_of_the_predicates() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::RetryOn;
+    let s = FixedInterval::from_millis(1).take(10);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let statuses = [503u16, 504, 503, 404, 503];
+    let future = RetryIf::spawn(
+        s,
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u16>(statuses[previous]))
+        },
+        RetryOn::new()
+            .when(|status: &u16| *status == 503)
+            .or_when(|status: &u16| *status == 504),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(404));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn retries_unless_condition_matches() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{RetryOn, RetryUnless};
+    let s = FixedInterval::from_millis(1).take(10);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn(
+        s,
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), usize>(previous))
+        },
+        RetryUnless::new(RetryOn::new().when(|attempt: &usize| *attempt == 2)),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn sends_event_before_every_retry() {
+    use tokio::time::Duration;
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::AttemptEvent;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let (future, mut events) =
+        Retry::spawn_with_events(FixedInterval::from_millis(100), move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            if previous < 2 {
+                future::ready(Err::<usize, String>(format!("failure {}", previous + 1)))
+            } else {
+                future::ready(Ok::<usize, String>(previous))
+            }
+        });
+    let res = future.await;
+
+    assert_eq!(res, Ok(2));
+    let mut received = Vec::new();
+    while let Some(event) = events.recv().await {
+        received.push(event);
+    }
+    assert_eq!(
+        received,
+        vec![
+            AttemptEvent {
+                attempt: 1,
+                error: "failure 1".to_string(),
+                delay: Duration::from_millis(100),
+            },
+            AttemptEvent {
+                attempt: 2,
+                error: "failure 2".to_string(),
+                delay: Duration::from_millis(100),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn runs_setup_again_for_every_retry() {
+    use tokio_retry::strategy::FixedInterval;
+    let setups = Arc::new(AtomicUsize::new(0));
+    let uses = Arc::new(AtomicUsize::new(0));
+    let cloned_setups = setups.clone();
+    let cloned_uses = uses.clone();
+    let future = Retry::spawn_with_setup(
+        FixedInterval::from_millis(1).take(5),
+        move || {
+            let connection = cloned_setups.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<usize, &str>(connection))
+        },
+        move |connection: usize| {
+            cloned_uses.fetch_add(1, Ordering::SeqCst);
+            if connection < 2 {
+                future::ready(Err("connection lost"))
+            } else {
+                future::ready(Ok(connection))
+            }
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(setups.load(Ordering::SeqCst), 3);
+    assert_eq!(uses.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn uses_connections_with_stateful_function() {
+    use tokio_retry::strategy::FixedInterval;
+    // counts the uses, and is not `Clone`
+    struct Uses(usize);
+
+    let mut uses = Uses(0);
+    let res = Retry::spawn_with_setup(
+        FixedInterval::from_millis(1).take(5),
+        || future::ready(Ok::<(), &str>(())),
+        move |_connection: ()| {
+            uses.0 += 1;
+            future::ready(if uses.0 < 3 {
+                Err("connection lost")
+            } else {
+                Ok(uses.0)
+            })
+        },
+    )
+    .await;
+
+    assert_eq!(res, Ok(3));
+}
+
+#[tokio::test]
+async fn uses_connection_by_reference_while_in_flight() {
+    use tokio_retry::strategy::FixedInterval;
+
+    struct Connection {
+        id: usize,
+        queries: usize,
+    }
+
+    impl Connection {
+        async fn query(&mut self) -> Result<usize, &'static str> {
+            self.queries += 1;
+            tokio::task::yield_now().await;
+            if self.id < 2 {
+                Err("connection lost")
+            } else {
+                Ok(self.queries)
+            }
+        }
+    }
+
+    let setups = Arc::new(AtomicUsize::new(0));
+    let cloned_setups = setups.clone();
+    let future = Retry::spawn_with_setup(
+        FixedInterval::from_millis(1).take(5),
+        move || {
+            let id = cloned_setups.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok(Connection { id, queries: 0 }))
+        },
+        |mut connection: Connection| async move {
+            connection.query().await?;
+            connection.query().await
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(setups.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn shares_borrowed_strategy_across_actions() {
+    use tokio::time::Duration;
+    use tokio_retry::strategy::ExponentialBackoff;
+    let mut strategy = ExponentialBackoff::from_millis(10);
+    for _ in 0..2 {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let cloned_counter = counter.clone();
+        let res = RetryIf::spawn_borrowed(
+            &mut strategy,
+            move || {
+                let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+                future::ready(if previous < 1 { Err(()) } else { Ok(previous) })
+            },
+            |_: &()| true,
+        )
+        .await;
+        assert_eq!(res, Ok(1));
+    }
+
+    assert_eq!(strategy.next(), Some(Duration::from_millis(1000)));
+}
+
+#[tokio::test]
+async fn retries_caught_panics() {
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_catch_unwind(
+        FixedInterval::from_millis(1).take(5),
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if previous < 2 {
+                    panic!("attempt {} panicked", previous);
+                }
+                Ok::<usize, String>(previous)
+            }
+        },
+        |payload: Box<dyn std::any::Any + Send>| {
+            payload
+                .downcast::<String>()
+                .map(|message| *message)
+                .unwrap_or_default()
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn retries_panics_when_running_action() {
+    use tokio_retry::strategy::FixedInterval;
+    // counts the caught panics, and is not `Clone`
+    struct Panics(usize);
+
+    let mut attempts = 0;
+    let mut panics = Panics(0);
+    let res = Retry::spawn_catch_unwind(
+        FixedInterval::from_millis(1).take(5),
+        move || {
+            attempts += 1;
+            if attempts < 3 {
+                panic!("attempt {} panicked", attempts);
+            }
+            future::ready(Ok::<usize, usize>(attempts))
+        },
+        move |_: Box<dyn std::any::Any + Send>| {
+            panics.0 += 1;
+            panics.0
+        },
+    )
+    .await;
+
+    assert_eq!(res, Ok(3));
+
+    let mut panics = Panics(0);
+    let res = Retry::spawn_catch_unwind(
+        FixedInterval::from_millis(1).take(2),
+        || -> future::Ready<Result<(), usize>> { panic!("always") },
+        move |_: Box<dyn std::any::Any + Send>| {
+            panics.0 += 1;
+            panics.0
+        },
+    )
+    .await;
+
+    assert_eq!(res, Err(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_all_actions_independently() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let actions = vec![0, 2, 5]
+        .into_iter()
+        .map(|failures| {
+            let counter = counter.clone();
+            let mut attempts = 0;
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                attempts += 1;
+                future::ready(if attempts > failures {
+                    Ok(failures)
+                } else {
+                    Err(failures)
+                })
+            }
+        })
+        .collect();
+    let res = Retry::spawn_all(|| FixedInterval::from_millis(10).take(3), actions).await;
+
+    assert_eq!(res, vec![Ok(0), Ok(2), Err(Error::RetriesExhausted(5, 4))]);
+    assert_eq!(counter.load(Ordering::SeqCst), 1 + 3 + 4);
+}
+
+#[tokio::test]
+async fn passes_attempt_to_action() {
+    use std::sync::Mutex;
+    use tokio_retry::strategy::FixedInterval;
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+    let cloned_attempts = attempts.clone();
+    let future = Retry::spawn_with_attempt(
+        FixedInterval::from_millis(1).take(5),
+        move |attempt: usize| {
+            cloned_attempts.lock().unwrap().push(attempt);
+            future::ready(if attempt >= 2 { Ok(attempt) } else { Err(()) })
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(*attempts.lock().unwrap(), vec![0, 1, 2]);
+}
+
+#[tokio::test]
+async fn formats_retry_with_debug() {
+    use tokio_retry::strategy::FixedInterval;
+    let future = Retry::spawn(FixedInterval::from_millis(1).take(1), || {
+        future::ready(Ok::<(), ()>(()))
+    });
+
+    assert_eq!(
+        format!("{:?}", future),
+        "Retry { state: Running, attempts: 1 }"
+    );
+
+    let future = RetryIf::spawn(
+        FixedInterval::from_millis(1).take(1),
+        || future::ready(Ok::<(), ()>(())),
+        |_: &()| true,
+    );
+
+    assert_eq!(
+        format!("{:?}", future),
+        "RetryIf { state: Running, attempts: 1 }"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn bounds_infinite_strategy() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_bounded(
+        FixedInterval::from_millis(10),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        4,
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(Error::RetriesExhausted(42, 5)));
+    assert_eq!(counter.load(Ordering::SeqCst), 5);
+}
+
+#[tokio::test(start_paused = true)]
+async fn caps_retries_of_items_in_run_loop() {
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn_on(
+        FixedInterval::from_millis(10),
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Ok::<usize, u64>(previous + 1))
+        },
+        |_: &u64| true,
+        |_: &usize| true,
+    )
+    .max_retries(2);
+    let res = future.await;
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn maps_action_errors() {
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_map_err(
+        FixedInterval::from_millis(1).take(2),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        |err: u64| format!("error {}", err),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err("error 42".to_string()));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn maps_action_errors_with_stateful_function() {
+    use tokio_retry::strategy::FixedInterval;
+    // numbers the mapped errors, and is not `Clone`
+    struct Mapped(usize);
+
+    let mut mapped = Mapped(0);
+    let res = Retry::spawn_map_err(
+        FixedInterval::from_millis(1).take(2),
+        || future::ready(Err::<(), u64>(42)),
+        move |err: u64| {
+            mapped.0 += 1;
+            format!("error {} #{}", err, mapped.0)
+        },
+    )
+    .await;
+
+    // every attempt was mapped by the same function
+    assert_eq!(res, Err("error 42 #3".to_string()));
+}
+
+#[derive(Debug, PartialEq)]
+struct DetailedError {
+    code: usize,
+    detail: Option<String>,
+}
+
+#[tokio::test]
+async fn condition_takes_ownership_of_errors() {
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn_owned(
+        FixedInterval::from_millis(1).take(2),
+        move || {
+            let code = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), _>(DetailedError {
+                code,
+                detail: Some("verbose".to_string()),
+            }))
+        },
+        |err: DetailedError| {
+            Ok(DetailedError {
+                detail: None,
+                ..err
+            })
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(
+        res,
+        Err(DetailedError {
+            code: 2,
+            detail: None
+        })
+    );
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn owning_condition_stops_with_its_error() {
+    use tokio_retry::strategy::FixedInterval;
+    let future = RetryIf::spawn_owned(
+        FixedInterval::from_millis(1).take(2),
+        || future::ready(Err::<(), u64>(42)),
+        |err: u64| Err(err + 1),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(43));
+}
+
+#[tokio::test(start_paused = true)]
+async fn drives_retry_by_hand() {
+    use std::pin::pin;
+    use std::task::Poll;
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::RetryDriver;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let mut driver = pin!(RetryDriver::new(
+        FixedInterval::from_millis(10).take(5),
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(if previous < 2 {
+                Err(previous)
+            } else {
+                Ok(previous)
+            })
+        },
+    ));
+
+    let mut errors = Vec::new();
+    let res = future::poll_fn(|cx| loop {
+        match driver.as_mut().poll_attempt(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Ok(item)) => return Poll::Ready(item),
+            Poll::Ready(Err(err)) => {
+                errors.push(err);
+                assert!(driver.as_mut().retry().is_some());
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(res, 2);
+    assert_eq!(errors, vec![0, 1]);
+    assert_eq!(driver.attempts(), 3);
+}
+
+#[tokio::test]
+async fn accepts_boxed_futures() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use tokio_retry::strategy::FixedInterval;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn(
+        FixedInterval::from_millis(1).take(2),
+        move || -> Pin<Box<dyn Future<Output = Result<usize, &str>> + Send>> {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            if previous < 1 {
+                Box::pin(async { Err("first attempt") })
+            } else {
+                Box::pin(async move {
+                    tokio::task::yield_now().await;
+                    Ok(previous)
+                })
+            }
+        },
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(1));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn notifies_effective_delay_after_adapters() {
+    use std::sync::Mutex;
+    use tokio::time::Duration;
+    use tokio_retry::strategy::{ExponentialBackoff, StrategyExt};
+    let delays = Arc::new(Mutex::new(Vec::new()));
+    let cloned_delays = delays.clone();
+    let future = Retry::spawn_notify(
+        StrategyExt::max_delay(
+            ExponentialBackoff::from_millis(100),
+            Duration::from_millis(50),
+        )
+        .take(3),
+        || future::ready(Err::<(), u64>(42)),
+        move |_: &u64, delay: Duration| cloned_delays.lock().unwrap().push(delay),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(*delays.lock().unwrap(), vec![Duration::from_millis(50); 3]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn shares_policy_across_retries() {
+    use std::iter::Take;
+    use tokio::time::Duration;
+    use tokio_retry::strategy::{ExponentialBackoff, MaxDelay, StrategyExt};
+    use tokio_retry::RetryPolicy;
+
+    struct Policy {
+        base: u64,
+    }
+
+    impl RetryPolicy for Policy {
+        type Iter = Take<MaxDelay<ExponentialBackoff>>;
+
+        fn iter(&self) -> Self::Iter {
+            StrategyExt::max_delay(
+                ExponentialBackoff::from_millis(self.base),
+                Duration::from_secs(1),
+            )
+            .take(2)
+        }
+    }
+
+    let policy = Arc::new(Policy { base: 10 });
+    for _ in 0..2 {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let cloned_counter = counter.clone();
+        let res = Retry::spawn_policy(&policy, move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        })
+        .await;
+
+        assert_eq!(res, Err(42));
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+}
+
+#[tokio::test]
+async fn skips_sleeper_for_zero_delays() {
+    use std::time::Duration;
+    use tokio_retry::strategy::ExplicitSchedule;
+    let s = ExplicitSchedule::new(vec![
+        Duration::from_millis(10),
+        Duration::ZERO,
+        Duration::from_millis(20),
+    ]);
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let sleeper = RecordingSleeper::default();
+    let future = Retry::spawn_with_sleeper(
+        s,
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        sleeper.clone(),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+    assert_eq!(
+        *sleeper.delays.lock().unwrap(),
+        vec![Duration::from_millis(10), Duration::from_millis(20)]
+    );
+}
+
+#[tokio::test]
+async fn stop_decisions_map_to_distinct_errors() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{Error, RetryDecision};
+    for (decision, expected) in [
+        (RetryDecision::Stop, Error::OperationError(42)),
+        (RetryDecision::StopExhausted, Error::RetriesExhausted(42, 2)),
+    ] {
+        let mut decisions = 0;
+        let future = RetryIf::spawn_decide(
+            FixedInterval::from_millis(1),
+            || future::ready(Err::<(), u64>(42)),
+            move |_: &u64| {
+                decisions += 1;
+                if decisions == 1 {
+                    RetryDecision::Continue
+                } else {
+                    decision
+                }
+            },
+        );
+        let res = future.detailed().await;
+
+        assert_eq!(res, Err(expected));
+    }
+}
+
+#[tokio::test]
+async fn continue_decision_retries_until_success() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::RetryDecision;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = RetryIf::spawn_decide(
+        FixedInterval::from_millis(1),
+        move || {
+            let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(if previous < 2 {
+                Err(previous)
+            } else {
+                Ok(previous)
+            })
+        },
+        |_: &usize| RetryDecision::Continue,
+    );
+    let res = future.detailed().await;
+
+    assert_eq!(res, Ok(2));
+}
+
+#[cfg(feature = "jitter")]
+#[tokio::test(start_paused = true)]
+async fn numbers_attempts_contiguously_across_chained_strategies() {
+    use tokio_retry::strategy::{ExponentialBackoff, FixedInterval, StrategyExt};
+    let s = FixedInterval::from_millis(5)
+        .take(2)
+        .then(ExponentialBackoff::from_millis(10).take(2))
+        .jitter();
+    let observer = RecordingObserver::default();
+    let future = Retry::spawn_observed(s, || future::ready(Err::<(), u64>(42)), &observer);
+    let res = future.await;
+
+    assert_eq!(res, Err(42));
+    let attempts = observer
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|event| event.starts_with("attempt "))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attempts,
+        vec![
+            "attempt 1",
+            "attempt 2",
+            "attempt 3",
+            "attempt 4",
+            "attempt 5"
+        ]
+    );
+    assert_eq!(
+        observer.events.lock().unwrap().last().unwrap(),
+        "giveup after 5"
+    );
+}
+
+#[test]
+fn yields_once_after_sleeping_before_retrying() {
+    use std::future::Future;
+    use std::task::{Context, Poll};
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::YieldingSleeper;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let sleeper = RecordingSleeper::default();
+    let future = Retry::spawn_with_sleeper(
+        FixedInterval::from_millis(10).take(1),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        YieldingSleeper::new(sleeper.clone()),
+    );
+    let mut future = Box::pin(future);
+    let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+    assert_eq!(sleeper.delays.lock().unwrap().len(), 1);
+
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(Err(42)));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn reports_timing_of_every_attempt() {
+    use tokio::time::{sleep, Duration};
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::AttemptRecord;
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let (res, report) = Retry::spawn_report(FixedInterval::from_millis(100), move || {
+        let cloned_counter = cloned_counter.clone();
+        async move {
+            sleep(Duration::from_millis(10)).await;
+            if cloned_counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err::<u64, u64>(42)
+            } else {
+                Ok(7)
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(res, Ok(7));
+    assert_eq!(
+        report.attempts,
+        vec![
+            AttemptRecord {
+                attempt: 1,
+                slept_before: Duration::ZERO,
+                action_took: Duration::from_millis(10),
+                outcome: Err("42".to_string()),
+            },
+            AttemptRecord {
+                attempt: 2,
+                slept_before: Duration::from_millis(100),
+                action_took: Duration::from_millis(10),
+                outcome: Err("42".to_string()),
+            },
+            AttemptRecord {
+                attempt: 3,
+                slept_before: Duration::from_millis(100),
+                action_took: Duration::from_millis(10),
+                outcome: Ok(()),
+            },
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn aborts_between_attempts_when_signal_resolves() {
+    use tokio::time::{sleep, Duration, Instant};
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+    let start = Instant::now();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_until_signal(
+        FixedInterval::from_millis(1000),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        sleep(Duration::from_millis(1500)),
+    );
+    let res = future.await;
+
+    assert_eq!(res, Err(Error::Aborted));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+    assert_eq!(start.elapsed(), Duration::from_millis(1500));
+}
+
+#[tokio::test]
+async fn retries_with_condition_selected_at_runtime() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{Condition, RetryOn};
+
+    fn select(name: &str) -> Box<dyn Condition<u64> + Send> {
+        match name {
+            "transient" => Box::new(RetryOn::new().when(|e: &u64| *e == 42)),
+            _ => Box::new(|e: &u64| *e != 42),
+        }
+    }
+
+    for (name, expected) in [("transient", 3), ("permanent", 1)] {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let cloned_counter = counter.clone();
+        let future = RetryIf::spawn_dyn(
+            FixedInterval::from_millis(1).take(2),
+            move || {
+                cloned_counter.fetch_add(1, Ordering::SeqCst);
+                future::ready(Err::<(), u64>(42))
+            },
+            select(name),
+        );
+        let res = future.await;
+
+        assert_eq!(res, Err(42));
+        assert_eq!(counter.load(Ordering::SeqCst), expected);
+    }
+}
+
+#[tokio::test]
+async fn retries_with_boxed_condition_that_is_not_send() {
+    use std::rc::Rc;
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Condition;
+    let retryable = Rc::new(vec![42]);
+    let condition: Box<dyn Condition<u64>> = Box::new(move |err: &u64| retryable.contains(err));
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+
+    let res = RetryIf::spawn(
+        FixedInterval::from_millis(1).take(2),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        condition,
+    )
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn drives_unpin_unsafe_action_futures() {
+    use std::marker::PhantomPinned;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio_retry::strategy::FixedInterval;
+
+    // resolves on the second poll, and must not move in between
+    struct NotUnpin {
+        attempt: usize,
+        polled: bool,
+        _pinned: PhantomPinned,
+    }
+
+    impl std::future::Future for NotUnpin {
+        type Output = Result<usize, usize>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+            // safe since `polled` is not structurally pinned
+            let this = unsafe { self.get_unchecked_mut() };
+            if !this.polled {
+                this.polled = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            if this.attempt < 3 {
+                Poll::Ready(Err(this.attempt))
+            } else {
+                Poll::Ready(Ok(this.attempt))
+            }
+        }
+    }
+
+    let mut attempt = 0;
+    let future = Retry::spawn(FixedInterval::from_millis(1).take(5), move || {
+        attempt += 1;
+        NotUnpin {
+            attempt,
+            polled: false,
+            _pinned: PhantomPinned,
+        }
+    });
+    let res = future.await;
+
+    assert_eq!(res, Ok(3));
+}
+
+#[tokio::test]
+async fn retries_non_send_action_on_local_set() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use tokio::task::{spawn_local, LocalSet};
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Rc::new(RefCell::new(0usize));
+    let cloned_counter = counter.clone();
+    let res = LocalSet::new()
+        .run_until(async move {
+            spawn_local(Retry::spawn(FixedInterval::from_millis(1), move || {
+                let counter = cloned_counter.clone();
+                async move {
+                    *counter.borrow_mut() += 1;
+                    if *counter.borrow() < 3 {
+                        Err::<usize, ()>(())
+                    } else {
+                        Ok(*counter.borrow())
+                    }
+                }
+            }))
+            .await
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(*counter.borrow(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn spaces_attempt_starts_apart() {
+    use tokio::time::{sleep, Duration, Instant};
+    use tokio_retry::strategy::FixedInterval;
+
+    for (took, expected) in [(50, [0, 200, 400]), (300, [0, 300, 600])] {
+        let start = Instant::now();
+        let starts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cloned_starts = starts.clone();
+        let future = Retry::spawn(FixedInterval::new(Duration::ZERO).take(2), move || {
+            cloned_starts
+                .lock()
+                .unwrap()
+                .push(start.elapsed().as_millis() as u64);
+            async move {
+                sleep(Duration::from_millis(took)).await;
+                Err::<(), u64>(42)
+            }
+        })
+        .min_attempt_spacing(Duration::from_millis(200));
+        let res = future.await;
+
+        assert_eq!(res, Err(42));
+        assert_eq!(*starts.lock().unwrap(), expected);
+    }
+}
+
+#[derive(Default)]
+struct GiveupRecorder {
+    reasons: std::sync::Mutex<Vec<tokio_retry::GiveupReason>>,
+}
+
+impl tokio_retry::RetryObserver<u64> for GiveupRecorder {
+    fn on_giveup(&self, _attempts: usize, reason: tokio_retry::GiveupReason) {
+        self.reasons.lock().unwrap().push(reason);
+    }
+}
+
+#[tokio::test]
+async fn reports_why_retry_gave_up() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::{GiveupReason, Observed, RetryBudget, RetryBuilder};
+
+    let observer = GiveupRecorder::default();
+    let res = Retry::spawn_observed(
+        FixedInterval::from_millis(1).take(2),
+        || future::ready(Err::<(), u64>(42)),
+        &observer,
+    )
+    .await;
+    assert_eq!(res, Err(42));
+
+    let res = RetryBuilder::new()
+        .strategy(FixedInterval::from_millis(1))
+        .max_retries(2)
+        .notify(Observed::new(&observer))
+        .spawn(|| future::ready(Err::<(), u64>(42)))
+        .await;
+    assert_eq!(res, Err(42));
+
+    let res = RetryIf::spawn_notify(
+        FixedInterval::from_millis(1),
+        || future::ready(Err::<(), u64>(42)),
+        |e: &u64| *e != 42,
+        Observed::new(&observer),
+    )
+    .await;
+    assert_eq!(res, Err(42));
+
+    let res = RetryIf::spawn_notify(
+        FixedInterval::from_millis(1),
+        || future::ready(Err::<(), u64>(42)),
+        RetryBudget::new(1.0, 0.5),
+        Observed::new(&observer),
+    )
+    .await;
+    assert_eq!(res, Err(42));
+
+    assert_eq!(
+        *observer.reasons.lock().unwrap(),
+        vec![
+            GiveupReason::StrategyExhausted,
+            GiveupReason::MaxRetries,
+            GiveupReason::ConditionFalse,
+            GiveupReason::BudgetExhausted,
+        ]
+    );
+}
+
+#[test]
+fn composes_conditions_with_combinators() {
+    use tokio_retry::{Condition, ConditionExt};
+
+    let is_transient = |e: &u64| *e >= 500;
+    let is_not_fatal = |e: &u64| *e != 503;
+    let is_rate_limited = |e: &u64| *e == 429;
+
+    let mut and = is_transient.and(is_not_fatal);
+    assert!(and.should_retry(&500));
+    assert!(!and.should_retry(&503));
+    assert!(!and.should_retry(&429));
+
+    let mut or = is_transient.and(is_not_fatal).or(is_rate_limited);
+    assert!(or.should_retry(&500));
+    assert!(or.should_retry(&429));
+    assert!(!or.should_retry(&503));
+    assert!(!or.should_retry(&404));
+
+    let mut not = is_rate_limited.not();
+    assert!(!not.should_retry(&429));
+    assert!(not.should_retry(&500));
+}
+
+#[test]
+fn combinators_short_circuit() {
+    use std::cell::Cell;
+    use tokio_retry::{Condition, ConditionExt};
+
+    let consulted = Cell::new(0);
+    let second = |_: &u64| {
+        consulted.set(consulted.get() + 1);
+        true
+    };
+
+    assert!(!(|_: &u64| false).and(second).should_retry(&42));
+    assert!((|_: &u64| true).or(second).should_retry(&42));
+    assert_eq!(consulted.get(), 0);
+}
+
+#[tokio::test]
+async fn collects_errors_of_all_attempts() {
+    use tokio_retry::strategy::FixedInterval;
+    use tokio_retry::Error;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_collect_errors(FixedInterval::from_millis(1).take(2), move || {
+        let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(Err::<(), String>(format!("failure {}", attempt + 1)))
+    })
+    .await;
+
+    assert_eq!(
+        res,
+        Err(Error::AllFailed(vec![
+            "failure 1".to_string(),
+            "failure 2".to_string(),
+            "failure 3".to_string(),
+        ]))
+    );
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_collect_errors(FixedInterval::from_millis(1).take(2), move || {
+        let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(if attempt < 1 {
+            Err(attempt)
+        } else {
+            Ok(attempt)
+        })
+    })
+    .await;
+
+    assert_eq!(res, Ok(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedge_wins_over_slow_attempt() {
+    use std::time::Duration;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_hedged(Duration::from_millis(10), 2, move || {
+        let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        async move {
+            if attempt == 1 {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            Ok::<usize, ()>(attempt)
+        }
+    })
+    .await;
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn waits_for_hedge_delay_with_given_sleeper() {
+    use std::time::Duration;
+
+    let sleeper = RecordingSleeper::default();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    // built and polled outside of a runtime, as the hedge timer comes from the sleeper
+    let future = Retry::spawn_hedged_with_sleeper(
+        Duration::from_millis(10),
+        2,
+        move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                if attempt == 1 {
+                    future::pending::<()>().await;
+                }
+                Ok::<usize, ()>(attempt)
+            }
+        },
+        sleeper.clone(),
+    );
+    let res = futures::executor::block_on(future);
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(
+        *sleeper.delays.lock().unwrap(),
+        vec![Duration::from_millis(10)]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedged_resolves_with_last_error_when_all_attempts_fail() {
+    use std::time::Duration;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_hedged(Duration::from_secs(1), 2, move || {
+        let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        future::ready(Err::<(), usize>(attempt))
+    })
+    .await;
+
+    assert_eq!(res, Err(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn publishes_status_to_watch() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let (future, mut status) =
+        Retry::spawn_with_watch(FixedInterval::from_millis(100).take(5), move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+            future::ready(Err::<(), String>(format!("failure {}", attempt)))
+        });
+    assert_eq!(status.borrow().attempt, 1);
+
+    let handle = tokio::spawn(future);
+    let after_second_failure = status
+        .wait_for(|status| status.last_error.as_deref() == Some("failure 2"))
+        .await
+        .unwrap()
+        .clone();
+
+    assert_eq!(after_second_failure.attempt, 2);
+    let next_retry_at = after_second_failure.next_retry_at.unwrap();
+    assert!(next_retry_at > Instant::now());
+    assert!(next_retry_at <= Instant::now() + Duration::from_millis(100));
+
+    assert_eq!(handle.await.unwrap(), Err("failure 6".to_string()));
+    let last = status.borrow().clone();
+    assert_eq!(last.attempt, 6);
+    assert_eq!(last.next_retry_at, None);
+}
+
+#[tokio::test]
+async fn gives_up_with_last_error_when_strategy_panics() {
+    use std::time::Duration;
+
+    let mut yielded = 0;
+    let strategy = std::iter::from_fn(move || {
+        yielded += 1;
+        if yielded > 2 {
+            panic!("strategy failed");
+        }
+        Some(Duration::from_millis(1))
+    });
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn(strategy, move || {
+        let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        future::ready(Err::<(), usize>(attempt))
+    })
+    .catch_strategy_panic()
+    .await;
+
+    assert_eq!(res, Err(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn borrowed_strategy_restarts_for_each_retry() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_retry::strategy::ExponentialBackoff;
+
+    let strategy = ExponentialBackoff::from_millis(10);
+
+    for _ in 0..2 {
+        let start = Instant::now();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let cloned_counter = counter.clone();
+        let res = Retry::spawn(&strategy, move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(if attempt < 2 {
+                Err(attempt)
+            } else {
+                Ok(attempt)
+            })
+        })
+        .await;
+
+        assert_eq!(res, Ok(2));
+        assert_eq!(start.elapsed(), Duration::from_millis(110));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn cleans_up_once_per_retried_failure() {
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_retry::strategy::FixedInterval;
+
+    let cleaned = Arc::new(Mutex::new(Vec::new()));
+    let cloned_cleaned = cleaned.clone();
+    let start = Instant::now();
+    let res = Retry::spawn_with_cleanup(
+        FixedInterval::from_millis(100).take(2),
+        {
+            let counter = AtomicUsize::new(0);
+            move || {
+                let attempt = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                future::ready(Err::<(), usize>(attempt))
+            }
+        },
+        move |err: &usize| {
+            let cleaned = cloned_cleaned.clone();
+            let err = *err;
+            async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                cleaned.lock().unwrap().push(err);
+            }
+        },
+    )
+    .await;
+
+    assert_eq!(res, Err(3));
+    assert_eq!(*cleaned.lock().unwrap(), vec![1, 2]);
+    assert_eq!(start.elapsed(), Duration::from_millis(220));
+
+    let cleanups = Arc::new(AtomicUsize::new(0));
+    let cloned_cleanups = cleanups.clone();
+    let res = Retry::spawn_with_cleanup(
+        FixedInterval::from_millis(100).take(2),
+        {
+            let counter = AtomicUsize::new(0);
+            move || {
+                let attempt = counter.fetch_add(1, Ordering::SeqCst);
+                future::ready(if attempt < 1 {
+                    Err(attempt)
+                } else {
+                    Ok(attempt)
+                })
+            }
+        },
+        move |_: &usize| {
+            cloned_cleanups.fetch_add(1, Ordering::SeqCst);
+            future::ready(())
+        },
+    )
+    .await;
+
+    assert_eq!(res, Ok(1));
+    assert_eq!(cleanups.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn retries_until_system_time_deadline() {
+    use std::time::{Duration, SystemTime};
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = std::time::Instant::now();
+    let res = Retry::spawn_before(
+        FixedInterval::from_millis(20),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        SystemTime::now() + Duration::from_millis(100),
+    )
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert!(counter.load(Ordering::SeqCst) > 1);
+    assert!(start.elapsed() >= Duration::from_millis(90));
+}
+
+#[tokio::test]
+async fn attempts_once_when_system_time_deadline_has_passed() {
+    use std::time::{Duration, SystemTime};
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_before(
+        FixedInterval::from_millis(20),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        SystemTime::now() - Duration::from_secs(1),
+    )
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn delaying_condition_overrides_passes_through_and_stops() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = Instant::now();
+    let res = RetryIf::spawn_delaying(
+        FixedInterval::from_millis(100).take(5),
+        move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+            future::ready(Err::<(), usize>(attempt))
+        },
+        |err: &usize, default: Duration| match *err {
+            // override the first delay
+            1 => Some(Duration::from_millis(10)),
+            // keep the delay of the strategy
+            2 => Some(default),
+            _ => None,
+        },
+    )
+    .await;
+
+    assert_eq!(res, Err(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(start.elapsed(), Duration::from_millis(110));
+}
+
+#[tokio::test(start_paused = true)]
+async fn delaying_condition_consumes_one_strategy_step_per_retry() {
+    use std::time::Duration;
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = RetryIf::spawn_delaying(
+        FixedInterval::from_millis(100).take(2),
+        move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+            future::ready(Err::<(), usize>(attempt))
+        },
+        |_: &usize, _: Duration| Some(Duration::from_millis(1)),
+    )
+    .await;
+
+    assert_eq!(res, Err(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_items_that_ask_for_a_delay() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = Instant::now();
+    let res = Retry::spawn_on_with_delay(
+        FixedInterval::from_millis(100).take(5),
+        move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+            future::ready(Ok::<usize, ()>(attempt))
+        },
+        |attempt: &usize| {
+            if *attempt < 3 {
+                Some(Duration::from_millis(30))
+            } else {
+                None
+            }
+        },
+    )
+    .await;
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(start.elapsed(), Duration::from_millis(60));
+}
+
+#[tokio::test(start_paused = true)]
+async fn resolves_with_last_item_once_strategy_is_exhausted() {
+    use std::time::Duration;
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_on_with_delay(
+        FixedInterval::from_millis(100).take(1),
+        move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+            future::ready(Ok::<usize, ()>(attempt))
+        },
+        |_: &usize| Some(Duration::from_millis(30)),
+    )
+    .await;
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn stops_right_away_on_sentinel_error() {
+    use tokio_retry::strategy::FixedInterval;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_stop_on(
+        FixedInterval::from_millis(1).take(5),
+        move || {
+            let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+            future::ready(Err::<(), &str>(if attempt < 2 { "busy" } else { "gone" }))
+        },
+        |err: &&str| *err == "gone",
+    )
+    .await;
+
+    assert_eq!(res, Err("gone"));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_stop_on(
+        FixedInterval::from_millis(1).take(2),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), &str>("busy"))
+        },
+        |err: &&str| *err == "gone",
+    )
+    .await;
+
+    assert_eq!(res, Err("busy"));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn delay_hook_records_intended_delays_while_sleeping_zero() {
+    use std::time::{Duration, Instant};
+    use tokio_retry::strategy::ExponentialBackoff;
+
+    let mut delays = Vec::new();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = Instant::now();
+    let res = Retry::spawn_with_delay_hook(
+        ExponentialBackoff::from_millis(10).factor(100).take(3),
+        move || {
+            cloned_counter.fetch_add(1, Ordering::SeqCst);
+            future::ready(Err::<(), u64>(42))
+        },
+        |delay| {
+            delays.push(delay);
+            Duration::ZERO
+        },
+    )
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+    assert_eq!(
+        delays,
+        vec![
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            Duration::from_secs(100),
+        ]
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn attempts_on_interval_ticks() {
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    let start = Instant::now();
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let cloned_started = started.clone();
+    let res = Retry::spawn_interval(Duration::from_millis(100), 5, move || {
+        let mut started = cloned_started.lock().unwrap();
+        started.push(start.elapsed());
+        let attempt = started.len();
+        async move {
+            // attempts take a while, without delaying the next tick
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            if attempt < 3 {
+                Err(attempt)
+            } else {
+                Ok(attempt)
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(res, Ok(3));
+    assert_eq!(
+        *started.lock().unwrap(),
+        vec![
+            Duration::ZERO,
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_after_max_ticks() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    let start = Instant::now();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let res = Retry::spawn_interval(Duration::from_millis(100), 3, move || {
+        let attempt = cloned_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        future::ready(Err::<(), usize>(attempt))
+    })
+    .await;
+
+    assert_eq!(res, Err(3));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(start.elapsed(), Duration::from_millis(200));
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "tokio")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio_retry::retry;
+use tokio_retry::strategy::FixedInterval;
+
+#[tokio::test]
+async fn retries_block_until_success() {
+    let counter = AtomicUsize::new(0);
+    let counter = &counter;
+
+    let res = retry!(FixedInterval::from_millis(1).take(5), {
+        let attempt = counter.fetch_add(1, Ordering::SeqCst);
+        if attempt < 2 {
+            Err(attempt)
+        } else {
+            Ok(attempt)
+        }
+    });
+
+    assert_eq!(res, Ok(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn retries_block_until_strategy_is_exhausted() {
+    let counter = AtomicUsize::new(0);
+    let counter = &counter;
+
+    let res = retry!(FixedInterval::from_millis(1).take(2), {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Err::<(), &str>("failure")
+    });
+
+    assert_eq!(res, Err("failure"));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn retries_block_while_condition_holds() {
+    let counter = AtomicUsize::new(0);
+    let counter = &counter;
+
+    let res = retry!(
+        FixedInterval::from_millis(1).take(5),
+        if |e: &usize| *e < 2,
+        {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            Err::<(), usize>(attempt)
+        }
+    );
+
+    assert_eq!(res, Err(2));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "tower")]
+
+use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio_retry::middleware::RetryLayer;
+use tokio_retry::strategy::FixedInterval;
+use tower::{service_fn, Layer, ServiceExt};
+
+#[tokio::test]
+async fn retries_service_until_success() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let service = service_fn(move |request: u64| {
+        let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        if previous < 2 {
+            future::ready(Err::<u64, u64>(42))
+        } else {
+            future::ready(Ok::<u64, u64>(request * 2))
+        }
+    });
+    let layer = RetryLayer::new(|| FixedInterval::from_millis(10).take(5));
+    let res = layer.layer(service).oneshot(21).await;
+
+    assert_eq!(res, Ok(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn builds_request_for_every_attempt_with_factory() {
+    use tokio_retry::middleware::RetryService;
+
+    // deliberately not `Clone`
+    struct Request(u64);
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let service = service_fn(move |request: Request| {
+        let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        if previous < 2 {
+            future::ready(Err::<u64, u64>(42))
+        } else {
+            future::ready(Ok::<u64, u64>(request.0 * 2))
+        }
+    });
+    let built = Arc::new(AtomicUsize::new(0));
+    let cloned_built = built.clone();
+    let factory = move || {
+        cloned_built.fetch_add(1, Ordering::SeqCst);
+        Request(21)
+    };
+    let res =
+        RetryService::with_request_factory(|| FixedInterval::from_millis(10).take(5), service)
+            .oneshot(factory)
+            .await;
+
+    assert_eq!(res, Ok(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(built.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,143 @@
+#![cfg(all(feature = "serde", feature = "tokio"))]
+
+use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::time::{Duration, Instant};
+use tokio_retry::strategy::{RetryConfig, StrategyConfig};
+use tokio_retry::Retry;
+
+#[tokio::test(start_paused = true)]
+async fn retries_with_deserialized_config() {
+    let config: RetryConfig = serde_json::from_str(
+        r#"{ "kind": "exponential", "base_ms": 10, "factor": 2, "max_retries": 3 }"#,
+    )
+    .unwrap();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = Instant::now();
+    let res = Retry::spawn(config.strategy(), move || {
+        cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(Err::<(), u64>(42))
+    })
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 4);
+    assert_eq!(start.elapsed(), Duration::from_millis(20 + 200 + 2000));
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_after_max_retries_of_example_config() {
+    let config: RetryConfig = serde_json::from_str(
+        r#"{ "kind": "exponential", "base_ms": 10, "factor": 1000, "max_retries": 5 }"#,
+    )
+    .unwrap();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = Instant::now();
+    let res = Retry::spawn(config.strategy(), move || {
+        cloned_counter.fetch_add(1, Ordering::SeqCst);
+        future::ready(Err::<(), u64>(42))
+    })
+    .await;
+
+    assert_eq!(res, Err(42));
+    assert_eq!(counter.load(Ordering::SeqCst), 6);
+    assert_eq!(
+        start.elapsed(),
+        Duration::from_secs(10 + 100 + 1_000 + 10_000 + 100_000)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn succeeds_within_max_retries_of_deserialized_config() {
+    let config: RetryConfig =
+        serde_json::from_str(r#"{ "kind": "fixed", "interval_ms": 100, "max_retries": 5 }"#)
+            .unwrap();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let start = Instant::now();
+    let res = Retry::spawn(config.strategy(), move || {
+        if cloned_counter.fetch_add(1, Ordering::SeqCst) < 2 {
+            future::ready(Err::<(), u64>(42))
+        } else {
+            future::ready(Ok::<(), u64>(()))
+        }
+    })
+    .await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+    assert_eq!(start.elapsed(), Duration::from_millis(200));
+}
+
+fn round_trip(json: &str) -> (RetryConfig, RetryConfig) {
+    let config: RetryConfig = serde_json::from_str(json).unwrap();
+    let serialized = serde_json::to_string(&config).unwrap();
+    let parsed: RetryConfig = serde_json::from_str(&serialized).unwrap();
+    (config, parsed)
+}
+
+#[test]
+fn round_trips_exponential_config() {
+    let (config, parsed) = round_trip(
+        r#"{ "kind": "exponential", "base_ms": 10, "factor": 1000, "max_delay_ms": 60000, "max_retries": 5 }"#,
+    );
+
+    assert!(matches!(parsed.kind, StrategyConfig::Exponential(_)));
+    assert_eq!(parsed.max_retries, Some(5));
+    assert_eq!(
+        parsed.strategy().collect::<Vec<_>>(),
+        config.strategy().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        parsed.strategy().collect::<Vec<_>>(),
+        vec![
+            Duration::from_secs(10),
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+        ]
+    );
+}
+
+#[test]
+fn round_trips_fibonacci_config() {
+    let (config, parsed) =
+        round_trip(r#"{ "kind": "fibonacci", "base_ms": 10, "factor": 2, "max_retries": 4 }"#);
+
+    assert!(matches!(parsed.kind, StrategyConfig::Fibonacci(_)));
+    assert_eq!(parsed.max_retries, Some(4));
+    assert_eq!(
+        parsed.strategy().collect::<Vec<_>>(),
+        config.strategy().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        parsed.strategy().collect::<Vec<_>>(),
+        vec![
+            Duration::from_millis(20),
+            Duration::from_millis(20),
+            Duration::from_millis(40),
+            Duration::from_millis(60),
+        ]
+    );
+}
+
+#[test]
+fn round_trips_fixed_config() {
+    let (config, parsed) = round_trip(r#"{ "kind": "fixed", "interval_ms": 250 }"#);
+
+    assert!(matches!(parsed.kind, StrategyConfig::Fixed(_)));
+    assert_eq!(parsed.max_retries, None);
+    assert_eq!(
+        parsed.strategy().take(3).collect::<Vec<_>>(),
+        config.strategy().take(3).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        parsed.strategy().take(3).collect::<Vec<_>>(),
+        vec![Duration::from_millis(250); 3]
+    );
+}
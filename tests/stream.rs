@@ -0,0 +1,112 @@
+#![cfg(feature = "stream")]
+
+use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures::StreamExt;
+use tokio_retry::strategy::FixedInterval;
+use tokio_retry::RetryStream;
+
+#[tokio::test]
+async fn retries_failing_page_before_continuing() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let stream = RetryStream::new(FixedInterval::from_millis(10).take(1), move || {
+        let call = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        match call {
+            0 => future::ready(Ok::<_, u64>(Some("page 1"))),
+            1 => future::ready(Err(42)),
+            2 => future::ready(Ok(Some("page 2"))),
+            3 => future::ready(Err(43)),
+            4 => future::ready(Ok(Some("page 3"))),
+            _ => future::ready(Ok(None)),
+        }
+    });
+    let pages = stream.collect::<Vec<_>>().await;
+
+    assert_eq!(pages, vec![Ok("page 1"), Ok("page 2"), Ok("page 3")]);
+    assert_eq!(counter.load(Ordering::SeqCst), 6);
+}
+
+#[tokio::test]
+async fn yields_last_error_once_strategy_is_exhausted() {
+    let stream = RetryStream::new(FixedInterval::from_millis(10).take(1), || {
+        future::ready(Err::<Option<()>, u64>(42))
+    });
+    let pages = stream.collect::<Vec<_>>().await;
+
+    assert_eq!(pages, vec![Err(42)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn delay_stream_ticks_at_strategy_cadence() {
+    use tokio::time::{Duration, Instant};
+    use tokio_retry::delay_stream;
+
+    let start = Instant::now();
+    let ticks = delay_stream(FixedInterval::from_millis(100).take(3))
+        .map(|()| start.elapsed())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(
+        ticks,
+        vec![
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+            Duration::from_millis(300)
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_stream_error_between_items() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_retry::retry_stream_items;
+
+    let strategies = Arc::new(AtomicUsize::new(0));
+    let cloned_strategies = strategies.clone();
+    let inner = futures::stream::iter(vec![Ok("item 1"), Err(42), Ok("item 2")]);
+    let start = Instant::now();
+    let items = retry_stream_items(
+        move || {
+            cloned_strategies.fetch_add(1, Ordering::SeqCst);
+            FixedInterval::from_millis(10).take(1)
+        },
+        inner,
+    )
+    .collect::<Vec<_>>()
+    .await;
+
+    assert_eq!(items, vec![Ok("item 1"), Ok("item 2")]);
+    assert_eq!(strategies.load(Ordering::SeqCst), 1);
+    assert_eq!(start.elapsed(), Duration::from_millis(10));
+}
+
+#[tokio::test(start_paused = true)]
+async fn ends_stream_once_item_retries_are_exhausted() {
+    use tokio_retry::{retry_stream_items, Error};
+
+    let inner = futures::stream::iter(vec![
+        Ok("item 1"),
+        Err(42),
+        Ok("item 2"),
+        Err(43),
+        Err(44),
+        Ok("item 3"),
+    ]);
+    let items = retry_stream_items(|| FixedInterval::from_millis(10).take(1), inner)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(
+        items,
+        vec![
+            Ok("item 1"),
+            Ok("item 2"),
+            Err(Error::RetriesExhausted(44, 2)),
+        ]
+    );
+}
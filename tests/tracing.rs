@@ -0,0 +1,42 @@
+#![cfg(all(feature = "tracing", feature = "tokio"))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio_retry::strategy::FixedInterval;
+use tokio_retry::Retry;
+use tracing_test::traced_test;
+
+#[tokio::test(start_paused = true)]
+#[traced_test]
+async fn records_retries_and_success() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_traced(FixedInterval::from_millis(100), move || {
+        let previous = cloned_counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if previous < 2 {
+                Err(format!("failure {}", previous + 1))
+            } else {
+                Ok(previous)
+            }
+        }
+    });
+    let res = future.await;
+    assert_eq!(res, Ok(2));
+
+    assert!(logs_contain("attempt=1 delay_ms=100 error=failure 1"));
+    assert!(logs_contain("attempt=2 delay_ms=100 error=failure 2"));
+    assert!(logs_contain("attempts=3"));
+    logs_assert(|lines: &[&str]| {
+        let warnings = lines.iter().filter(|line| line.contains(" WARN ")).count();
+        let debugs = lines
+            .iter()
+            .filter(|line| line.contains(" DEBUG ") && line.contains("succeeded"))
+            .count();
+        match (warnings, debugs) {
+            (2, 1) => Ok(()),
+            counts => Err(format!("unexpected (warn, debug) counts: {:?}", counts)),
+        }
+    });
+}
@@ -0,0 +1,14 @@
+#![deny(unused_must_use)]
+
+use tokio_retry::strategy::{ExponentialBackoff, FixedInterval};
+use tokio_retry::{Retry, RetryIf};
+
+async fn action() -> Result<(), ()> {
+    Err(())
+}
+
+fn main() {
+    Retry::spawn(FixedInterval::from_millis(10).take(3), action);
+    RetryIf::spawn(FixedInterval::from_millis(10).take(3), action, |_: &()| true);
+    ExponentialBackoff::from_millis(10).factor(2);
+}
@@ -0,0 +1,33 @@
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use std::cell::Cell;
+use std::future;
+use std::rc::Rc;
+
+use tokio_retry::strategy::FixedInterval;
+use tokio_retry::{GlooSleeper, Retry};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn succeeds_on_third_attempt() {
+    let counter = Rc::new(Cell::new(0));
+    let cloned_counter = counter.clone();
+    let future = Retry::spawn_with_sleeper(
+        FixedInterval::from_millis(10),
+        move || {
+            cloned_counter.set(cloned_counter.get() + 1);
+            if cloned_counter.get() < 3 {
+                future::ready(Err::<(), u64>(42))
+            } else {
+                future::ready(Ok::<(), u64>(()))
+            }
+        },
+        GlooSleeper,
+    );
+    let res = future.await;
+
+    assert_eq!(res, Ok(()));
+    assert_eq!(counter.get(), 3);
+}